@@ -7,12 +7,21 @@ git-description = Git commit {$hash} on {$date}
 
 # Dialog Page
 cancel = Cancel
+save = Save
+ok = OK
+create-list = Create batch
+list-name = Batch name
+delete-list-confirm = Are you sure you want to delete this batch? The samples and their results are not affected.
+job-submitted = Job submitted
 
 # Nav Page
 run-tb-profiler = Run TB-Profiler
+results = Results
 download-results = Download Results
 delete-results = Delete Results
+templates = Templates
 settings = Settings
+logs = Logs
 
 ## App Themes
 dark = Dark
@@ -34,6 +43,242 @@ no-tasks-suggestion = Try adding a task with the text field below
 no-list-selected = No list selected
 no-list-suggestion = Create or select a new list to get started
 
+# Batches
+add-to-batch = Add to batch
+run-selected = Run selected
+check-reads = Check reads
+fastq-failed = Reads failed the check
+run-passed = Run the { $count } other samples
+run-batch = Run batch
+download-batch-results = Download results
+batch-samples = { $count ->
+    [one] 1 sample
+   *[other] { $count } samples
+}
+batch-empty = This batch has no samples yet. Add samples from the Run TB-Profiler page.
+
+# Resistance profile
+no-profile = No result available for this sample
+lineage = Lineage
+main-lineage = Main lineage
+sub-lineage = Sub-lineage
+drug-resistance = Drug resistance
+dr-type = Resistance type
+susceptible = No resistance mutations
+qc = Quality control
+percent-reads-mapped = Reads mapped
+num-reads-mapped = Number of reads mapped
+target-median-depth = Target median depth
+genome-median-depth = Genome median depth
+tbprofiler-version = TB-Profiler version
+
+# Results
+no-results = No results
+no-results-suggestion = Results appear here once TB-Profiler runs have finished
+filter-samples = Filter by sample, lineage or type
+all-dr-types = All resistance types
+other-dr-types = Other resistance types
+export-csv = Export CSV
+export-xlsx = Export XLSX
+summary-count = { $shown } of { $total } samples
+
+# Download
+download = Download
+file-types = File types
+all-samples = All samples
+transfer-progress = { $finished } of { $total } files ({ $transferred } of { $size }), { $skipped } up to date
+destination = Destination
+change = Change…
+open-folder = Open folder
+no-result-sets = No results on the cluster
+result-set-details = { $files ->
+    [one] 1 file
+   *[other] { $files } files
+}, { $size }, { $modified }
+download-template = Download default template
+download-summary = Downloaded { $transferred } files ({ $size }), { $skipped } already up to date
+
+# Delete
+run-of = Run of { $date } ({ $count ->
+    [one] 1 sample
+   *[other] { $count } samples
+})
+delete-preview = { $samples } selected: { $files } files, { $size }
+delete-permanently = Delete permanently
+delete-permanently-warning = Files will be removed permanently and cannot be restored.
+delete-trash-destination = Files will be moved to a dated folder in { $dir }.
+delete-confirm-word = delete
+delete-confirm-hint = Type "{ $word }" to confirm
+move-to-trash = Move to trash
+moved-summary = Moved { $files } files ({ $size }) to { $dir }
+moved-skipped = { $files } files were left in place because the trash already holds files of the same name.
+deleted-summary = Deleted { $files } files ({ $size })
+
+# Upload
+upload-fastq = Upload FASTQ…
+drop-fastq = or drop paired FASTQ files onto the sample list
+upload-selection = { $samples ->
+    [one] 1 sample
+   *[other] { $samples } samples
+}, { $size }
+upload = Upload
+clear = Clear
+upload-summary = Uploaded { $transferred } files ({ $size }), { $skipped } already on the cluster
+
+# Templates
+no-templates = No templates found on the cluster
+default-template = { $name } (default)
+template-details = { $size }, { $modified }, { $versions ->
+    [0] no earlier versions
+    [one] 1 earlier version
+   *[other] { $versions } earlier versions
+}
+use-for-runs = Use for runs
+used-for-runs = Used for runs
+template-name = Template name
+upload-template = Upload template…
+template-warnings = Check the template placeholders
+render-reports = Render reports locally
+render-reports-button = Render reports…
+export-pdf = Also export PDF (requires LibreOffice)
+rendered-reports = { $count ->
+    [one] 1 file written
+   *[other] { $count } files written
+}
+upload-anyway = Upload anyway
+
+# Storage
+remote-space = Cluster: { $available } free of { $total }
+remote-quota = Cluster quota: { $quota } free of { $limit }
+remote-space-quota = Cluster: { $available } free of { $total }, quota { $quota } free of { $limit }
+remote-space-unknown = Cluster: free space unknown
+local-space = Local: { $available } free of { $total }
+local-space-unknown = Local: free space unknown
+remote-space-warning = The estimated output of { $required } does not fit into the { $available } free on the cluster.
+local-space-warning = The selected results ({ $required }) do not fit into the { $available } free locally.
+insufficient-space = Not enough space
+run-anyway = Run anyway
+
+# Retention
+retention = Retention
+retention-days = Archive results older than (days, 0 to disable)
+archive-download = Download and verify archives before removing results
+retention-preview = { $samples } result sets due, archiving frees { $size } in the out directory. Archives go to { $dir }.
+archive-now = Archive now
+archive-results = Archive results
+archive-confirm = The results of { $samples } samples will be packed into an archive and removed from the out directory.
+archive = Archive
+archive-summary = Archived { $samples } samples ({ $files } files) into { $archive } ({ $size }), freeing { $freed }
+
+# Watch
+watch-new-samples = { $count ->
+    [one] New sample in the raw reads: { $samples }
+   *[other] { $count } new samples in the raw reads: { $samples }
+}
+watch-submitted = Submitted { $samples } as job { $job }
+watch-submitted-without-job = Submitted { $samples }, their results are not downloaded automatically because the job id is unknown
+watch-fastq-failed = Not submitted, the reads failed the check: { $samples }
+watch-insufficient-space = Not submitted { $samples }: about { $required } of output, only { $available } available
+watch-downloading = Job { $job } finished, downloading the results of { $samples }
+
+# Settings page
+connection = Connection
+remote-paths = Remote paths
+transfers-and-storage = Transfers and storage
+general = General
+default-page = Page shown at start
+verify-checksums = Verify checksums after downloads
+watch-raw-dir = Watch the raw reads directory for new samples
+auto-submit = Submit new samples and download their results automatically
+run-locally = Run tb-profiler on this computer instead of the cluster
+scheduler = Scheduler
+scheduler-background = None, run in the background
+reset = Reset
+test-settings = Test
+setting-username = Username
+setting-host = Host
+setting-remote-raw-dir = Raw reads directory
+setting-tb-profiler-script = TB-Profiler script
+setting-remote-out-dir = Results directory
+setting-default-template = Default template
+setting-user-template = User template
+setting-remote-template-dir = Template directory
+setting-remote-batches-file = Shared batches file
+setting-remote-profile-file = Shared profile file
+setting-remote-trash-dir = Trash directory
+setting-remote-archive-dir = Archive directory
+setting-remote-quota-command = Quota command (optional, {"{"}dir{"}"} is replaced)
+setting-local-download-dir = Local download directory (optional)
+setting-transfer-concurrency = Parallel transfers
+setting-retention-days = Retention (days)
+setting-estimated-output = Estimated output per sample (MiB)
+setting-watch-interval = Watch interval (minutes)
+setting-required = Required
+setting-no-spaces = Must not contain spaces
+setting-absolute-path = Must be an absolute path
+setting-number = Must be a whole number
+setting-range = Must be between { $min } and { $max }
+setting-found = Found on the cluster
+setting-not-found = Not found on the cluster
+profile = Group profile
+profile-description = A profile holds the host and the group's paths, not your username. Imported values are shown above and take effect when saved.
+profile-import = Import…
+profile-import-cluster = Import from cluster
+profile-export = Export…
+profile-publish = Publish to cluster
+profile-imported = Profile imported, review and save the settings.
+profile-exported = Profile written to { $path }
+profile-replace = Replace published profile
+profile-replace-confirm = A profile is already published at { $path }. Publishing replaces it for everyone in the group.
+replace = Replace
+
+# Logs
+log-all = All levels
+log-warnings = Warnings and errors
+log-errors = Errors only
+refresh-log = Refresh
+open-log-folder = Open log folder
+log-empty = Nothing logged yet.
+
+# Errors
+retry = Retry
+error-auth = Authentication failed
+error-auth-hint = Check the username and SSH key on the Settings page, or run the setup assistant to install your key.
+error-host-key = Host key could not be verified
+error-host-key-hint = Make sure you connect to the right host. Connect once with ssh from a terminal to add it to ~/.ssh/known_hosts.
+error-timeout = Connection timed out
+error-network = Network error
+error-remote-command = Command failed on the cluster
+error-details = Details
+copy = Copy
+error-sftp = File transfer error
+error-io = Local file error
+error-config-missing = Configuration incomplete
+error-config-missing-hint = Complete the configuration on the Settings page.
+error-scheduler = Scheduler error
+error-no-items-checked = No samples selected
+error-caused-by = Caused by:
+    { $causes }
+
+# Setup assistant
+setup-title = Setup assistant
+setup-connection = Connection
+setup-connection-intro = Enter the cluster host and your username on it.
+setup-key = SSH key
+setup-key-intro = tbgui connects with an SSH key. Select one of your keys or generate a new one.
+setup-no-keys = No SSH keys found in ~/.ssh
+setup-generate-key = Generate key
+setup-install-intro = If the key is not yet authorized on the cluster, enter your cluster password once to install it. The password is not stored and only sent to a host in ~/.ssh/known_hosts.
+setup-password = Password
+setup-install-key = Install key
+setup-key-installed = The key is installed on the cluster.
+setup-directories = Project directories
+setup-directories-intro = Import the profile your group published on the cluster, or enter the project directory to look for the usual layout. Then check the paths.
+setup-project-root = Project directory
+setup-discover = Discover
+setup-back = Back
+setup-next = Next
+setup-finish = Finish
 
 ### Appearance
 appearance = Appearance
@@ -47,7 +292,10 @@ light = Light
 ## File
 file = File
 new-window = New window
-new-list = New list
+new-list = New batch
+delete-list = Delete batch
+fetch-lists = Fetch shared batches
+push-lists = Share batches on cluster
 quit = Quit
 
 ## Edit
@@ -57,6 +305,7 @@ delete = Delete
 
 ## View
 view = View
+refresh = Refresh
 menu-settings = Settings...
 menu-about = About Tasks...
 
@@ -14,6 +14,11 @@ pub enum Action {
     Settings,
     WindowClose,
     WindowNew,
+    NewList,
+    DeleteList,
+    FetchLists,
+    PushLists,
+    Refresh,
 }
 
 #[derive(Debug, Clone)]
@@ -26,6 +31,9 @@ pub enum ApplicationAction {
     Focus(widget::Id),
     ToggleContextDrawer,
     ToggleContextPage(ContextPage),
+    NewList,
+    DeleteList,
+    Refresh,
 }
 
 #[derive(Debug, Clone)]
@@ -35,6 +43,11 @@ pub enum TasksAction {
     AddList(List),
     DeleteList(Option<segmented_button::Entity>),
     FetchLists,
+    PushLists,
+    AddToList(String, Vec<String>),
+    RemoveFromList(String, String),
+    RunList(String),
+    DownloadList(String),
 }
 
 impl MenuAction for Action {
@@ -49,6 +62,11 @@ impl MenuAction for Action {
             }
             Action::WindowClose => Message::Application(ApplicationAction::WindowClose),
             Action::WindowNew => Message::Application(ApplicationAction::WindowNew),
+            Action::NewList => Message::Application(ApplicationAction::NewList),
+            Action::DeleteList => Message::Application(ApplicationAction::DeleteList),
+            Action::FetchLists => Message::Tasks(TasksAction::FetchLists),
+            Action::PushLists => Message::Tasks(TasksAction::PushLists),
+            Action::Refresh => Message::Application(ApplicationAction::Refresh),
         }
     }
 }
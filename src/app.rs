@@ -1,21 +1,25 @@
 use crate::{
-    actions::{Action, ApplicationAction},
+    actions::{Action, ApplicationAction, TasksAction},
     app::key_bind::key_binds,
     content::{self, Content},
     context::ContextPage,
     dialog::DialogPage,
     fl,
-    model::Sample,
+    model::{merge_lists, profile::TbProfile, List, Sample},
+    setup::{self, Setup},
+    summary::{self, Summary},
     views::nav::{get_nav_model, NavPage},
 };
-use async_ssh2_tokio::client::Client;
-use config::TbguiConfig;
+use archive::{expired_result_sets, ArchiveSummary};
+use backend::Backend;
+use config::{BackendKind, SchedulerKind, TbguiConfig};
+use config_form::{SettingsDraft, SettingsField};
 use cosmic::app::{context_drawer, Core};
 use cosmic::cosmic_config::{self, CosmicConfigEntry};
 use cosmic::iced::{
     alignment::{Horizontal, Vertical},
     keyboard::{Event as KeyEvent, Modifiers},
-    Event, Length, Subscription,
+    window, Event, Length, Subscription,
 };
 use cosmic::prelude::*;
 use cosmic::widget::{
@@ -23,53 +27,235 @@ use cosmic::widget::{
     menu::{key_bind::KeyBind, Action as _},
     nav_bar,
 };
-use ssh::create_client;
+use delete::DeleteSummary;
+use disk::StorageStatus;
+use docx::{validate_docx, DocxReport};
+use export::{write_table, ExportFormat};
+use futures_util::stream;
+use keys::generate_ssh_key;
+use logging::{log_dir, read_log};
+use profile::Profile;
+use qc::FastqCheck;
+use report::render_reports;
+use rfd::FileDialog;
+use ssh::{
+    archive_results, check_fastq, delete_results, discover_project, download_default_template,
+    download_results, fetch_lists, fetch_profile, fetch_results, install_public_key,
+    list_remote_templates, list_result_sets, profile_published, push_lists, push_profile,
+    run_tbprofiler, storage_status, test_settings, upload_fastq, upload_user_template,
+};
 use std::{
-    collections::{HashMap, VecDeque},
-    env, process,
+    collections::{HashMap, HashSet, VecDeque},
+    env,
+    path::PathBuf,
+    process,
+    time::Duration,
+};
+use templates::RemoteTemplate;
+use transfer::{
+    DownloadRequest, ResultFileType, ResultSet, TransferProgress, TransferState, TransferSummary,
 };
 use types::AppError;
+use upload::FastqSelection;
+use utils::format_size;
+use watch::{poll, Watch, WatchPoll};
 
 const REPOSITORY: &str = env!("CARGO_PKG_REPOSITORY");
+/// Lines of the current log file shown on the logs page.
+const LOG_VIEW_LINES: usize = 500;
 
+pub mod archive;
+pub mod backend;
 pub mod config;
+pub mod config_form;
+pub mod delete;
+pub mod disk;
+pub mod docx;
+pub mod export;
 pub mod icons;
+pub mod jobs;
 pub mod key_bind;
+pub mod keys;
 pub mod localize;
+pub mod logging;
 pub mod menu;
+pub mod profile;
+pub mod qc;
+pub mod report;
 pub mod settings;
 pub mod ssh;
+pub mod templates;
+pub mod transfer;
 pub mod types;
+pub mod upload;
 pub mod utils;
+pub mod watch;
 
 pub struct Tbgui {
     core: Core,
     nav_model: nav_bar::Model,
-    client: Option<Client>,
+    backend: Option<Backend>,
     content: Content,
+    pub(crate) profiles: HashMap<String, TbProfile>,
+    summary: Summary,
+    setup: Option<Setup>,
+    fastq_checks: HashMap<String, FastqCheck>,
+    pub(crate) result_sets: Vec<ResultSet>,
+    pub(crate) download_types: Vec<ResultFileType>,
+    pub(crate) download_samples: HashSet<String>,
+    pub(crate) transfer: TransferState,
+    pub(crate) last_download: Option<TransferSummary>,
+    pub(crate) delete_samples: HashSet<String>,
+    pub(crate) delete_confirmation: String,
+    pub(crate) delete_permanently: bool,
+    pub(crate) deleting: bool,
+    pub(crate) last_delete: Option<DeleteSummary>,
+    pub(crate) archiving: bool,
+    pub(crate) storage: StorageStatus,
+    pub(crate) templates: Vec<RemoteTemplate>,
+    pub(crate) template_name: String,
+    pub(crate) uploading_template: bool,
+    pub(crate) render_pdf: bool,
+    pub(crate) rendering: bool,
+    pub(crate) rendered_reports: Vec<PathBuf>,
+    pub(crate) fastq_paths: Vec<PathBuf>,
+    pub(crate) fastq: FastqSelection,
+    pub(crate) upload: TransferState,
+    pub(crate) last_upload: Option<TransferSummary>,
+    pub(crate) last_archive: Option<ArchiveSummary>,
+    pub(crate) settings_draft: SettingsDraft,
+    pub(crate) settings_tests: HashMap<SettingsField, bool>,
+    settings_edited: bool,
+    pub(crate) testing_settings: bool,
+    pub(crate) profile_status: Option<String>,
+    pub(crate) page_names: Vec<String>,
+    pub(crate) scheduler_names: Vec<String>,
+    pub(crate) log_text: String,
+    pub(crate) log_level: usize,
+    pub(crate) log_levels: Vec<String>,
     config_handler: Option<cosmic_config::Config>,
-    config: TbguiConfig,
-    app_themes: Vec<String>,
+    pub(crate) config: TbguiConfig,
+    pub(crate) app_themes: Vec<String>,
     context_page: ContextPage,
     key_binds: HashMap<KeyBind, Action>,
     modifiers: Modifiers,
     dialog_pages: VecDeque<DialogPage>,
     dialog_text_input: widget::Id,
+    retry: Option<Message>,
+    watch: Watch,
+    toasts: widget::toaster::Toasts<Message>,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    CreateClient,
-    CreatedClient(Result<Client, AppError>),
+    Connect,
+    Connected(Result<Backend, AppError>),
     LoadRemoteState,
     LoadedRemoteState(Vec<Sample>),
+    LoadResults,
+    LoadedResults(Result<Vec<TbProfile>, AppError>),
     Content(content::Message),
+    Summary(summary::Message),
+    Setup(setup::Message),
+    StartSetup,
+    Exported(Result<(), AppError>),
     Application(ApplicationAction),
+    Tasks(TasksAction),
+    CheckFastq(Vec<String>),
+    CheckedFastq(Result<Vec<FastqCheck>, AppError>),
+    RunSamples(Vec<String>),
+    /// The FASTQ check run before submitting the samples finished.
+    CheckedRunSamples(Vec<String>, Result<Vec<FastqCheck>, AppError>),
+    RunCheckedSamples(Vec<String>),
+    SubmitSamples(Vec<String>),
+    RanSamples(Result<String, AppError>),
+    PollWatch,
+    PolledWatch(Result<WatchPoll, AppError>),
+    /// Samples found by the watch were checked, with the reads and the current storage.
+    AutoCheckedSamples(
+        Vec<String>,
+        Result<(Vec<FastqCheck>, StorageStatus), AppError>,
+    ),
+    /// Samples found by the watch were submitted, with the job id if it was recognized.
+    AutoSubmitted(Result<(Option<String>, Vec<String>), AppError>),
+    ChooseFastq,
+    AddFastq(Vec<PathBuf>),
+    ClearFastq,
+    StartUpload,
+    UploadProgress(TransferProgress),
+    UploadedFastq(Result<TransferSummary, AppError>),
+    LoadResultSets,
+    LoadStorage,
+    LoadedStorage(StorageStatus),
+    LoadedResultSets(Result<Vec<ResultSet>, AppError>),
+    ChooseDownloadDir,
+    ChoseDownloadDir(Option<PathBuf>),
+    OpenDownloadDir,
+    LoadTemplates,
+    LoadedTemplates(Result<Vec<RemoteTemplate>, AppError>),
+    SelectTemplate(String),
+    SetTemplateName(String),
+    UploadTemplate,
+    ValidatedTemplate(Option<(PathBuf, Result<DocxReport, AppError>)>),
+    UploadValidatedTemplate(PathBuf),
+    UploadedTemplate(Result<String, AppError>),
+    SetRenderPdf(bool),
+    RenderReports,
+    RenderedReports(Result<Vec<PathBuf>, AppError>),
+    DownloadTemplate,
+    DownloadedTemplate(Result<(), AppError>),
+    ToggleDeleteSample(String, bool),
+    ToggleDeleteRun(String, bool),
+    SetDeleteConfirmation(String),
+    SetDeletePermanently(bool),
+    StartDelete,
+    SetRetentionDays(String),
+    SetArchiveDownload(bool),
+    ConfirmArchive,
+    StartArchive(Vec<String>),
+    ArchivedResults(Result<ArchiveSummary, AppError>),
+    DeletedResults(Result<DeleteSummary, AppError>),
+    ToggleDownloadType(ResultFileType, bool),
+    ToggleDownloadSample(String, bool),
+    ClearDownloadSamples,
+    StartDownload,
+    DownloadProgress(TransferProgress),
+    DownloadedResults(Result<TransferSummary, AppError>),
+    EditSetting(SettingsField, String),
+    EditDefaultPage(usize),
+    EditScheduler(usize),
+    EditVerifyChecksums(bool),
+    EditRunLocally(bool),
+    EditWatchRawDir(bool),
+    EditAutoSubmit(bool),
+    ResetSettings,
+    TestSettings,
+    TestedSettings(Result<Vec<(SettingsField, bool)>, AppError>),
+    SaveSettings,
+    ImportProfile,
+    ImportClusterProfile,
+    ImportedProfile(Result<Option<Profile>, AppError>),
+    ExportProfile,
+    PublishProfile,
+    CheckedPublishedProfile(Result<bool, AppError>),
+    StartPublishProfile,
+    ExportedProfile(Result<Option<String>, AppError>),
+    LoadLog,
+    LoadedLog(String),
+    SetLogLevel(usize),
+    OpenLogDir,
     OpenRepositoryUrl,
     UpdateConfig(TbguiConfig),
     LaunchUrl(String),
     Error(AppError),
+    /// An error of an action that can be run again from the error dialog.
+    Failed(Box<Message>, AppError),
+    Retry,
+    CopyToClipboard(String),
+    CloseToast(widget::toaster::ToastId),
     DialogCancel,
+    DialogUpdate(DialogPage),
+    DialogComplete,
 }
 
 #[derive(Clone, Debug)]
@@ -93,18 +279,162 @@ impl Tbgui {
         .into()
     }
 
-    // fn create_nav_item(&mut self, list: &List) -> EntityMut<SingleSelect> {
-    //     self.nav_model
-    //         .insert()
-    //         .text(format!(
-    //             "{} {}",
-    //             list.icon
-    //                 .clone()
-    //                 .unwrap_or(emojis::get_by_shortcode("pencil").unwrap().to_string()),
-    //             list.name.clone()
-    //         ))
-    //         .data(list.clone())
-    // }
+    fn create_nav_item(&mut self, list: &List) -> nav_bar::Id {
+        self.nav_model
+            .insert()
+            .text(format!(
+                "{} {}",
+                list.icon
+                    .clone()
+                    .unwrap_or(emojis::get_by_shortcode("pencil").unwrap().to_string()),
+                list.name.clone()
+            ))
+            .data(list.clone())
+            .id()
+    }
+
+    /// Rebuilds the batch entries of the nav bar and the batch dropdown
+    /// of the run page from the configuration.
+    fn refresh_lists(&mut self) {
+        let active_list = self
+            .nav_model
+            .active_data::<List>()
+            .map(|list| list.id.clone());
+        let entities: Vec<nav_bar::Id> = self
+            .nav_model
+            .iter()
+            .filter(|id| self.nav_model.data::<List>(*id).is_some())
+            .collect();
+        for entity in entities {
+            self.nav_model.remove(entity);
+        }
+        for list in self.config.batches.clone() {
+            let id = self.create_nav_item(&list);
+            if active_list.as_ref() == Some(&list.id) {
+                self.nav_model.activate(id);
+            }
+        }
+        self.content
+            .update(content::Message::SetLists(self.config.batches.clone()));
+    }
+
+    fn save_lists(&mut self, lists: Vec<List>) {
+        match &self.config_handler {
+            Some(handler) => {
+                if let Err(err) = self.config.set_batches(handler, lists) {
+                    tracing::error!("{err}")
+                }
+            }
+            None => self.config.batches = lists,
+        }
+        self.refresh_lists();
+    }
+
+    fn find_list(&self, list_id: &str) -> Option<&List> {
+        self.config.batches.iter().find(|list| list.id == list_id)
+    }
+
+    /// Keeps the outcome of FASTQ checks and shows it in the sample list.
+    fn set_fastq_checks(&mut self, checks: Vec<FastqCheck>) {
+        for check in checks {
+            self.fastq_checks.insert(check.sample.clone(), check);
+        }
+        let checks = self
+            .fastq_checks
+            .iter()
+            .map(|(sample, check)| (sample.clone(), check.failure.clone()))
+            .collect();
+        self.content
+            .update(content::Message::SetFastqChecks(checks));
+    }
+
+    /// Deletion needs a selection and the confirmation word typed by the user.
+    pub(crate) fn delete_confirmed(&self) -> bool {
+        !self.deleting
+            && !self.delete_samples.is_empty()
+            && self.delete_confirmation.trim() == fl!("delete-confirm-word")
+    }
+
+    /// Estimated remote output of running `samples` samples.
+    pub(crate) fn estimated_output(&self, samples: usize) -> u64 {
+        samples as u64 * self.config.estimated_output_per_sample
+    }
+
+    /// Splits `samples` into those that passed the FASTQ check and the failed
+    /// ones with the reason. Samples without a check count as passed.
+    fn split_fastq_failures(&self, samples: Vec<String>) -> (Vec<String>, Vec<(String, String)>) {
+        let mut passed = Vec::with_capacity(samples.len());
+        let mut failed = Vec::new();
+        for sample in samples {
+            match self.fastq_checks.get(&sample) {
+                Some(FastqCheck {
+                    failure: Some(failure),
+                    ..
+                }) => failed.push((sample, failure.clone())),
+                _ => passed.push(sample),
+            }
+        }
+        (passed, failed)
+    }
+
+    /// Estimated output and available space if running `samples` samples
+    /// would not fit on the cluster.
+    fn insufficient_space(&self, samples: usize) -> Option<(u64, u64)> {
+        let required = self.estimated_output(samples);
+        self.storage
+            .remote_available()
+            .filter(|available| required > *available)
+            .map(|available| (required, available))
+    }
+
+    /// Result sets older than the configured retention period.
+    pub(crate) fn expired_result_sets(&self) -> Vec<&ResultSet> {
+        if self.config.retention_days == 0 {
+            return Vec::new();
+        }
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        expired_result_sets(&self.result_sets, self.config.retention_days, now)
+    }
+
+    /// Shows `text` as a toast and logs it.
+    fn notify(&mut self, text: String) -> Task<cosmic::Action<Message>> {
+        tracing::info!("{text}");
+        self.toasts
+            .push(widget::toaster::Toast::new(text))
+            .map(cosmic::Action::App)
+    }
+
+    /// Runs `download_results` in the background and forwards its progress events.
+    fn start_download(&mut self, request: DownloadRequest) -> Task<cosmic::Action<Message>> {
+        let backend = self.backend.clone();
+        let config = self.config.clone();
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        self.transfer = TransferState {
+            running: true,
+            ..TransferState::default()
+        };
+        self.last_download = None;
+
+        let download = Task::perform(
+            async move {
+                match backend {
+                    Some(backend) => download_results(&backend, &config, &request, &sender).await,
+                    None => Err(AppError::network("Not connected".to_string())),
+                }
+            },
+            |result| cosmic::Action::App(Message::DownloadedResults(result)),
+        );
+        let progress = Task::run(
+            stream::unfold(receiver, |mut receiver| async move {
+                receiver.recv().await.map(|progress| (progress, receiver))
+            }),
+            |progress| cosmic::Action::App(Message::DownloadProgress(progress)),
+        );
+        Task::batch([download, progress])
+    }
 }
 
 impl cosmic::Application for Tbgui {
@@ -127,8 +457,51 @@ impl cosmic::Application for Tbgui {
             core,
             context_page: ContextPage::About,
             nav_model: get_nav_model(&flags),
-            client: None,
+            backend: None,
             content: Content::new(),
+            profiles: HashMap::new(),
+            summary: Summary::new(),
+            setup: flags
+                .config
+                .needs_setup()
+                .then(|| Setup::new(&flags.config)),
+            fastq_checks: HashMap::new(),
+            result_sets: Vec::new(),
+            download_types: vec![ResultFileType::Docx],
+            download_samples: HashSet::new(),
+            transfer: TransferState::default(),
+            last_download: None,
+            delete_samples: HashSet::new(),
+            delete_confirmation: String::new(),
+            delete_permanently: false,
+            deleting: false,
+            last_delete: None,
+            archiving: false,
+            storage: StorageStatus::default(),
+            templates: Vec::new(),
+            template_name: String::new(),
+            uploading_template: false,
+            render_pdf: false,
+            rendering: false,
+            rendered_reports: Vec::new(),
+            fastq_paths: Vec::new(),
+            fastq: FastqSelection::default(),
+            upload: TransferState::default(),
+            last_upload: None,
+            last_archive: None,
+            settings_draft: SettingsDraft::from_config(&flags.config),
+            settings_tests: HashMap::new(),
+            settings_edited: false,
+            testing_settings: false,
+            profile_status: None,
+            page_names: NavPage::all().iter().map(NavPage::title).collect(),
+            scheduler_names: SchedulerKind::all()
+                .iter()
+                .map(SchedulerKind::label)
+                .collect(),
+            log_text: String::new(),
+            log_level: 0,
+            log_levels: vec![fl!("log-all"), fl!("log-warnings"), fl!("log-errors")],
             config_handler: flags.config_handler,
             config: flags.config,
             key_binds: key_binds(),
@@ -136,10 +509,16 @@ impl cosmic::Application for Tbgui {
             app_themes: vec![fl!("match-desktop"), fl!("dark"), fl!("light")],
             dialog_pages: VecDeque::new(),
             dialog_text_input: widget::Id::unique(),
+            retry: None,
+            watch: Watch::default(),
+            toasts: widget::toaster::Toasts::new(Message::CloseToast),
         };
 
-        commands.push(Task::done(cosmic::Action::App(Message::CreateClient)));
+        if app.setup.is_none() {
+            commands.push(Task::done(cosmic::Action::App(Message::Connect)));
+        }
 
+        app.refresh_lists();
         app.core.nav_bar_set_toggled(false);
 
         let command = app.update_title();
@@ -171,6 +550,11 @@ impl cosmic::Application for Tbgui {
                 Message::Application(ApplicationAction::ToggleContextDrawer),
             )
             .title(self.context_page.title()),
+            ContextPage::Profile(ref sample) => context_drawer::context_drawer(
+                self.view_profile(sample),
+                Message::Application(ApplicationAction::ToggleContextDrawer),
+            )
+            .title(self.context_page.title()),
         })
     }
 
@@ -181,24 +565,44 @@ impl cosmic::Application for Tbgui {
     }
 
     fn view(&self) -> Element<Self::Message> {
+        if let Some(setup) = &self.setup {
+            return setup.view().map(Message::Setup);
+        }
+        if let Some(list) = self
+            .nav_model
+            .active_data::<List>()
+            .and_then(|list| self.find_list(&list.id))
+        {
+            return widget::toaster(&self.toasts, self.view_list(list));
+        }
         let page_view = match self.nav_model.active_data::<NavPage>() {
-            Some(NavPage::RunTbProfiler) => self.content.view().map(Message::Content),
-            Some(NavPage::DownloadResults) => self.view_settings(),
-            Some(NavPage::DeleteResults) => self.view_settings(),
+            Some(NavPage::RunTbProfiler) => widget::column::with_capacity(3)
+                .push(self.view_storage(
+                    Some(self.estimated_output(self.content.checked_samples().len())),
+                    None,
+                ))
+                .push(self.view_upload())
+                .push(self.content.view().map(Message::Content))
+                .into(),
+            Some(NavPage::Results) => self.summary.view().map(Message::Summary),
+            Some(NavPage::DownloadResults) => self.view_download(),
+            Some(NavPage::DeleteResults) => self.view_delete(),
+            Some(NavPage::Templates) => self.view_templates(),
             Some(NavPage::Settings) => self.view_settings(),
+            Some(NavPage::Logs) => self.view_logs(),
             None => cosmic::widget::text("Unkown page selected.").into(),
         };
-        page_view
+        let page_view = page_view
             .apply(widget::container)
             .width(Length::Fill)
             .height(Length::Fill)
             .align_x(Horizontal::Center)
-            .align_y(Vertical::Center)
-            .into()
+            .align_y(Vertical::Center);
+        widget::toaster(&self.toasts, page_view)
     }
 
     fn subscription(&self) -> Subscription<Self::Message> {
-        Subscription::batch(vec![
+        let mut subscriptions = vec![
             cosmic::iced::event::listen_with(|event, _status, _window_id| match event {
                 Event::Keyboard(KeyEvent::KeyPressed { key, modifiers, .. }) => {
                     Some(Message::Application(ApplicationAction::Key(modifiers, key)))
@@ -206,6 +610,9 @@ impl cosmic::Application for Tbgui {
                 Event::Keyboard(KeyEvent::ModifiersChanged(modifiers)) => Some(
                     Message::Application(ApplicationAction::Modifiers(modifiers)),
                 ),
+                Event::Window(window::Event::FileDropped(path)) => {
+                    Some(Message::AddFastq(vec![path]))
+                }
                 _ => None,
             }),
             self.core()
@@ -216,60 +623,92 @@ impl cosmic::Application for Tbgui {
                     }
                     Message::UpdateConfig(update.config)
                 }),
-        ])
+        ];
+        if self.config.watch_raw_dir && self.setup.is_none() {
+            let interval = u64::from(self.config.watch_interval_minutes.max(1)) * 60;
+            subscriptions.push(
+                cosmic::iced::time::every(Duration::from_secs(interval))
+                    .map(|_| Message::PollWatch),
+            );
+        }
+        Subscription::batch(subscriptions)
     }
 
     fn update(&mut self, message: Self::Message) -> Task<cosmic::Action<Self::Message>> {
         let mut commands = vec![];
         match message {
-            Message::CreateClient => {
+            Message::Connect => {
                 let config = self.config.clone();
-                let command = Task::perform(
-                    async move { create_client(&config).await },
-                    |client| match client {
-                        Ok(client) => cosmic::Action::App(Message::CreatedClient(Ok(client))),
-                        Err(err) => cosmic::Action::App(Message::CreatedClient(Err(
-                            AppError::Network(err.to_string()),
-                        ))),
-                    },
-                );
+                let command =
+                    Task::perform(async move { Backend::connect(&config).await }, |backend| {
+                        cosmic::Action::App(Message::Connected(backend))
+                    });
                 commands.push(command);
             }
-            Message::CreatedClient(result) => {
+            Message::Connected(result) => {
                 match result {
-                    Ok(client) => {
-                        self.client = Some(client);
+                    Ok(backend) => {
+                        self.backend = Some(backend);
                     }
                     Err(err) => {
-                        eprintln!("Error creating client: {}", err);
-                        self.dialog_pages
-                            .push_back(DialogPage::Info(AppError::Network(err.to_string())));
+                        tracing::error!("failed to connect: {err}");
+                        return self.update(Message::Failed(Box::new(Message::Connect), err));
                     }
                 }
                 commands.push(Task::done(cosmic::Action::App(Message::LoadRemoteState)));
+                commands.push(Task::done(cosmic::Action::App(Message::LoadResults)));
+                commands.push(Task::done(cosmic::Action::App(Message::LoadResultSets)));
+                commands.push(Task::done(cosmic::Action::App(Message::LoadStorage)));
             }
             Message::LoadRemoteState => {
-                let client = self.client.clone();
+                let backend = self.backend.clone();
                 let config = self.config.clone();
                 let command = Task::perform(
                     async move {
-                        if let Some(client) = client {
-                            Sample::get_raw_reads(&client, &config).await
+                        if let Some(backend) = backend {
+                            Sample::get_raw_reads(&backend, &config).await
                         } else {
-                            Err(AppError::Network("Client not initialized".to_string()))
+                            Err(AppError::network("Not connected".to_string()))
                         }
                     },
                     |result| match result {
                         Ok(remote_state) => {
                             cosmic::Action::App(Message::LoadedRemoteState(remote_state))
                         }
-                        Err(err) => {
-                            cosmic::Action::App(Message::Error(AppError::Network(err.to_string())))
-                        }
+                        Err(err) => cosmic::Action::App(Message::Failed(
+                            Box::new(Message::LoadRemoteState),
+                            err,
+                        )),
                     },
                 );
                 commands.push(command);
             }
+            Message::LoadResults => {
+                let backend = self.backend.clone();
+                let config = self.config.clone();
+                commands.push(Task::perform(
+                    async move {
+                        match backend {
+                            Some(backend) => fetch_results(&backend, &config).await,
+                            None => Err(AppError::network("Not connected".to_string())),
+                        }
+                    },
+                    |result| cosmic::Action::App(Message::LoadedResults(result)),
+                ));
+            }
+            Message::LoadedResults(result) => match result {
+                Ok(profiles) => {
+                    self.summary
+                        .update(summary::Message::SetProfiles(profiles.clone()));
+                    self.profiles = profiles
+                        .into_iter()
+                        .map(|profile| (profile.id.clone(), profile))
+                        .collect();
+                    let samples: HashSet<String> = self.profiles.keys().cloned().collect();
+                    self.content.update(content::Message::SetProfiles(samples));
+                }
+                Err(err) => tracing::error!("failed to load results: {err}"),
+            },
             Message::LoadedRemoteState(result) => {
                 let items = result.clone();
                 let message = Message::Content(content::Message::SetItems(items));
@@ -281,31 +720,1190 @@ impl cosmic::Application for Tbgui {
                     match content_item {
                         content::TaskMessage::Get(list_id) => {}
                         content::TaskMessage::Update(task) => {}
+                        content::TaskMessage::AddToList(list_id, samples) => {
+                            commands.push(
+                                self.update(Message::Tasks(TasksAction::AddToList(
+                                    list_id, samples,
+                                ))),
+                            );
+                        }
+                        content::TaskMessage::Run(samples) => {
+                            commands.push(self.update(Message::RunSamples(samples)));
+                        }
+                        content::TaskMessage::CheckFastq(samples) => {
+                            commands.push(self.update(Message::CheckFastq(samples)));
+                        }
+                        content::TaskMessage::OpenProfile(sample) => {
+                            self.context_page = ContextPage::Profile(sample);
+                            self.core.window.show_context = true;
+                        }
+                    }
+                }
+            }
+            Message::Summary(message) => {
+                for summary_task in self.summary.update(message) {
+                    match summary_task {
+                        summary::TaskMessage::Export(format, headers, records) => {
+                            commands.push(Task::perform(
+                                async move {
+                                    let Some(path) = FileDialog::new()
+                                        .set_title("Export summary")
+                                        .add_filter(format.extension(), &[format.extension()])
+                                        .set_file_name(format!(
+                                            "tbprofiler-summary.{}",
+                                            format.extension()
+                                        ))
+                                        .save_file()
+                                    else {
+                                        return Ok(());
+                                    };
+                                    write_table(&path, format, &headers, &records)
+                                },
+                                |result| cosmic::Action::App(Message::Exported(result)),
+                            ));
+                        }
+                    }
+                }
+            }
+            Message::Setup(message) => {
+                let Some(setup) = &mut self.setup else {
+                    return Task::none();
+                };
+                for setup_task in setup.update(message) {
+                    match setup_task {
+                        setup::TaskMessage::GenerateKey => {
+                            commands.push(Task::perform(generate_ssh_key(), |result| {
+                                cosmic::Action::App(Message::Setup(setup::Message::GeneratedKey(
+                                    result,
+                                )))
+                            }));
+                        }
+                        setup::TaskMessage::InstallKey(config, password, key) => {
+                            commands.push(Task::perform(
+                                install_public_key(config, password, key),
+                                |result| {
+                                    cosmic::Action::App(Message::Setup(
+                                        setup::Message::InstalledKey(result),
+                                    ))
+                                },
+                            ));
+                        }
+                        setup::TaskMessage::Discover(config, root) => {
+                            commands.push(Task::perform(
+                                discover_project(config, root),
+                                |result| {
+                                    cosmic::Action::App(Message::Setup(setup::Message::Discovered(
+                                        result,
+                                    )))
+                                },
+                            ));
+                        }
+                        setup::TaskMessage::ImportProfile(config) => {
+                            commands.push(Task::perform(
+                                async move {
+                                    let backend = Backend::connect(&config).await?;
+                                    fetch_profile(&backend, &config).await
+                                },
+                                |result| {
+                                    cosmic::Action::App(Message::Setup(
+                                        setup::Message::ImportedProfile(result),
+                                    ))
+                                },
+                            ));
+                        }
+                        setup::TaskMessage::Finish(config) => {
+                            if let Some(handler) = &self.config_handler {
+                                if let Err(err) = config.write_entry(handler) {
+                                    tracing::error!("{err}")
+                                }
+                            }
+                            self.settings_draft = SettingsDraft::from_config(&config);
+                            self.settings_edited = false;
+                            self.config = config;
+                            self.setup = None;
+                            self.backend = None;
+                            commands.push(self.update(Message::Connect));
+                        }
+                    }
+                }
+            }
+            Message::StartSetup => {
+                self.setup = Some(Setup::new(&self.config));
+            }
+            Message::Exported(result) => {
+                if let Err(err) = result {
+                    return self.update(Message::Error(err));
+                }
+            }
+            Message::Tasks(tasks_action) => match tasks_action {
+                TasksAction::PopulateLists(lists) => {
+                    let mut batches = self.config.batches.clone();
+                    merge_lists(&mut batches, lists);
+                    self.save_lists(batches);
+                }
+                TasksAction::AddList(list) => {
+                    let mut batches = self.config.batches.clone();
+                    batches.push(list);
+                    self.save_lists(batches);
+                }
+                TasksAction::DeleteList(entity) => {
+                    let entity = entity.unwrap_or(self.nav_model.active());
+                    if let Some(list) = self.nav_model.data::<List>(entity) {
+                        let list_id = list.id.clone();
+                        let mut batches = self.config.batches.clone();
+                        batches.retain(|list| list.id != list_id);
+                        self.save_lists(batches);
+                        if let Some(first) = self.nav_model.iter().next() {
+                            self.nav_model.activate(first);
+                        }
+                        commands.push(self.update_title());
+                    }
+                }
+                TasksAction::AddToList(list_id, samples) => {
+                    let mut batches = self.config.batches.clone();
+                    if let Some(list) = batches.iter_mut().find(|list| list.id == list_id) {
+                        list.add_samples(&samples);
+                    }
+                    self.save_lists(batches);
+                }
+                TasksAction::RemoveFromList(list_id, sample) => {
+                    let mut batches = self.config.batches.clone();
+                    if let Some(list) = batches.iter_mut().find(|list| list.id == list_id) {
+                        list.remove_sample(&sample);
+                    }
+                    self.save_lists(batches);
+                }
+                TasksAction::RunList(list_id) => {
+                    if let Some(list) = self.find_list(&list_id) {
+                        let samples = list.samples.clone();
+                        return self.update(Message::RunSamples(samples));
+                    }
+                }
+                TasksAction::DownloadList(list_id) => {
+                    if let Some(list) = self.find_list(&list_id) {
+                        let request = DownloadRequest {
+                            samples: Some(list.samples.clone()),
+                            file_types: self.download_types.clone(),
+                        };
+                        commands.push(self.start_download(request));
+                    }
+                }
+                TasksAction::FetchLists => {
+                    let backend = self.backend.clone();
+                    let config = self.config.clone();
+                    commands.push(Task::perform(
+                        async move {
+                            match backend {
+                                Some(backend) => fetch_lists(&backend, &config).await,
+                                None => Err(AppError::network("Not connected".to_string())),
+                            }
+                        },
+                        |result| match result {
+                            Ok(lists) => cosmic::Action::App(Message::Tasks(
+                                TasksAction::PopulateLists(lists),
+                            )),
+                            Err(err) => cosmic::Action::App(Message::Error(err)),
+                        },
+                    ));
+                }
+                TasksAction::PushLists => {
+                    let backend = self.backend.clone();
+                    let config = self.config.clone();
+                    commands.push(Task::perform(
+                        async move {
+                            match backend {
+                                Some(backend) => {
+                                    push_lists(&backend, &config, &config.batches).await
+                                }
+                                None => Err(AppError::network("Not connected".to_string())),
+                            }
+                        },
+                        |result| match result {
+                            Ok(()) => cosmic::Action::None,
+                            Err(err) => cosmic::Action::App(Message::Error(err)),
+                        },
+                    ));
+                }
+                TasksAction::Export(_) => {}
+            },
+            Message::CheckFastq(samples) => {
+                let backend = self.backend.clone();
+                let config = self.config.clone();
+                commands.push(Task::perform(
+                    async move {
+                        match backend {
+                            Some(backend) => check_fastq(&backend, &config, samples).await,
+                            None => Err(AppError::network("Not connected".to_string())),
+                        }
+                    },
+                    |result| cosmic::Action::App(Message::CheckedFastq(result)),
+                ));
+            }
+            Message::CheckedFastq(result) => match result {
+                Ok(checks) => self.set_fastq_checks(checks),
+                Err(err) => return self.update(Message::Error(err)),
+            },
+            Message::RunSamples(samples) => {
+                // Samples are checked once per session, `Check reads` checks them again.
+                let unchecked: Vec<String> = samples
+                    .iter()
+                    .filter(|sample| !self.fastq_checks.contains_key(*sample))
+                    .cloned()
+                    .collect();
+                if unchecked.is_empty() {
+                    return self.update(Message::CheckedRunSamples(samples, Ok(Vec::new())));
+                }
+                let backend = self.backend.clone();
+                let config = self.config.clone();
+                let retry = Message::RunSamples(samples.clone());
+                commands.push(Task::perform(
+                    async move {
+                        match backend {
+                            Some(backend) => check_fastq(&backend, &config, unchecked).await,
+                            None => Err(AppError::network("Not connected".to_string())),
+                        }
+                    },
+                    move |result| match result {
+                        Err(err) if err.retryable() => {
+                            cosmic::Action::App(Message::Failed(Box::new(retry), err))
+                        }
+                        result => cosmic::Action::App(Message::CheckedRunSamples(samples, result)),
+                    },
+                ));
+            }
+            Message::CheckedRunSamples(samples, result) => {
+                match result {
+                    Ok(checks) => self.set_fastq_checks(checks),
+                    Err(err) => return self.update(Message::Error(err)),
+                }
+                let (samples, failed) = self.split_fastq_failures(samples);
+                if failed.is_empty() {
+                    return self.update(Message::RunCheckedSamples(samples));
+                }
+                tracing::warn!("samples failed the FASTQ check: {failed:?}");
+                self.dialog_pages
+                    .push_back(DialogPage::FastqFailed { samples, failed });
+            }
+            Message::RunCheckedSamples(samples) => {
+                if let Some((required, available)) = self.insufficient_space(samples.len()) {
+                    tracing::warn!("estimated output of {required} bytes exceeds the free space");
+                    self.dialog_pages.push_back(DialogPage::InsufficientSpace {
+                        samples,
+                        required,
+                        available,
+                    });
+                    return Task::none();
+                }
+                return self.update(Message::SubmitSamples(samples));
+            }
+            Message::SubmitSamples(samples) => {
+                let backend = self.backend.clone();
+                let config = self.config.clone();
+                let retry = Message::SubmitSamples(samples.clone());
+                commands.push(Task::perform(
+                    async move {
+                        let Some(backend) = backend else {
+                            return Err(AppError::network("Not connected".to_string()));
+                        };
+                        run_tbprofiler(&backend, samples.len(), samples.join(" "), &config).await
+                    },
+                    move |result| match result {
+                        Err(err) if err.retryable() => {
+                            cosmic::Action::App(Message::Failed(Box::new(retry), err))
+                        }
+                        result => cosmic::Action::App(Message::RanSamples(result)),
+                    },
+                ));
+            }
+            Message::RanSamples(result) => match result {
+                Ok(stdout) => {
+                    tracing::info!("submitted tbprofiler job: {}", stdout.trim());
+                    self.dialog_pages.push_back(DialogPage::Submitted(stdout));
+                }
+                Err(err) => return self.update(Message::Error(err)),
+            },
+            Message::PollWatch => {
+                let Some(backend) = self.backend.clone() else {
+                    return Task::none();
+                };
+                let config = self.config.clone();
+                let queue = !self.watch.jobs.is_empty();
+                commands.push(Task::perform(
+                    async move { poll(&backend, &config, queue).await },
+                    |result| cosmic::Action::App(Message::PolledWatch(result)),
+                ));
+            }
+            Message::PolledWatch(result) => {
+                let polled = match result {
+                    Ok(polled) => polled,
+                    Err(err) => {
+                        // Nobody may be watching, the next poll tries again.
+                        tracing::warn!("failed to poll the raw reads directory: {err}");
+                        return Task::none();
+                    }
+                };
+                let new = self.watch.detect(polled.files);
+                if !new.is_empty() {
+                    commands.push(self.notify(fl!(
+                        "watch-new-samples",
+                        count = new.len(),
+                        samples = new.join(", ")
+                    )));
+                    commands.push(Task::done(cosmic::Action::App(Message::LoadRemoteState)));
+                    if self.config.auto_submit {
+                        // Same checks as a manual run, without anyone to ask.
+                        let backend = self.backend.clone();
+                        let config = self.config.clone();
+                        let samples = new.clone();
+                        commands.push(Task::perform(
+                            async move {
+                                let Some(backend) = backend else {
+                                    return Err(AppError::network("Not connected".to_string()));
+                                };
+                                let checks = check_fastq(&backend, &config, samples).await?;
+                                Ok((checks, storage_status(&backend, &config).await))
+                            },
+                            move |result| {
+                                cosmic::Action::App(Message::AutoCheckedSamples(new, result))
+                            },
+                        ));
+                    }
+                }
+                // A running transfer is not interrupted, finished jobs are
+                // picked up again at the next poll.
+                if let Some(queue) = polled.queue.filter(|_| !self.transfer.running) {
+                    let mut samples = Vec::new();
+                    for (job, job_samples) in
+                        self.watch.finished(&queue, self.config.scheduler.driver())
+                    {
+                        commands.push(self.notify(fl!(
+                            "watch-downloading",
+                            job = job,
+                            samples = job_samples.join(", ")
+                        )));
+                        samples.extend(job_samples);
+                    }
+                    if !samples.is_empty() {
+                        let request = DownloadRequest {
+                            samples: Some(samples),
+                            file_types: self.download_types.clone(),
+                        };
+                        commands.push(self.start_download(request));
+                    }
+                }
+            }
+            Message::AutoCheckedSamples(samples, result) => {
+                match result {
+                    Ok((checks, storage)) => {
+                        self.set_fastq_checks(checks);
+                        self.storage = storage;
+                    }
+                    Err(err) => return self.update(Message::Error(err)),
+                }
+                let (samples, failed) = self.split_fastq_failures(samples);
+                if !failed.is_empty() {
+                    tracing::warn!("new samples failed the FASTQ check: {failed:?}");
+                    let failed: Vec<&str> =
+                        failed.iter().map(|(sample, _)| sample.as_str()).collect();
+                    commands
+                        .push(self.notify(fl!("watch-fastq-failed", samples = failed.join(", "))));
+                }
+                if samples.is_empty() {
+                    return Task::batch(commands);
+                }
+                if let Some((required, available)) = self.insufficient_space(samples.len()) {
+                    tracing::warn!("estimated output of {required} bytes exceeds the free space");
+                    commands.push(self.notify(fl!(
+                        "watch-insufficient-space",
+                        samples = samples.join(", "),
+                        required = format_size(required),
+                        available = format_size(available)
+                    )));
+                    return Task::batch(commands);
+                }
+                let backend = self.backend.clone();
+                let config = self.config.clone();
+                commands.push(Task::perform(
+                    async move {
+                        let Some(backend) = backend else {
+                            return Err(AppError::network("Not connected".to_string()));
+                        };
+                        let output =
+                            run_tbprofiler(&backend, samples.len(), samples.join(" "), &config)
+                                .await?;
+                        let job = config.scheduler.driver().submitted_id(&output);
+                        Ok((job.map(str::to_string), samples))
+                    },
+                    |result| cosmic::Action::App(Message::AutoSubmitted(result)),
+                ));
+            }
+            Message::AutoSubmitted(result) => match result {
+                Ok((Some(job), samples)) => {
+                    tracing::info!("submitted new samples {samples:?} as job {job}");
+                    commands.push(self.notify(fl!(
+                        "watch-submitted",
+                        samples = samples.join(", "),
+                        job = job.clone()
+                    )));
+                    self.watch.jobs.push((job, samples));
+                }
+                Ok((None, samples)) => {
+                    tracing::warn!("submitted new samples {samples:?}, but found no job id");
+                    commands.push(self.notify(fl!(
+                        "watch-submitted-without-job",
+                        samples = samples.join(", ")
+                    )));
+                }
+                Err(err) => return self.update(Message::Error(err)),
+            },
+            Message::ChooseFastq => {
+                commands.push(Task::perform(
+                    async move {
+                        FileDialog::new()
+                            .set_title("Select FASTQ files to upload")
+                            .add_filter("FASTQ", &["gz", "fastq", "fq"])
+                            .pick_files()
+                            .unwrap_or_default()
+                    },
+                    |paths| cosmic::Action::App(Message::AddFastq(paths)),
+                ));
+            }
+            Message::AddFastq(paths) => {
+                // Files can only be dropped onto the sample list.
+                if self.nav_model.active_data::<NavPage>() != Some(&NavPage::RunTbProfiler)
+                    || self.upload.running
+                {
+                    return Task::none();
+                }
+                for path in paths {
+                    if path.is_file() && !self.fastq_paths.contains(&path) {
+                        self.fastq_paths.push(path);
+                    }
+                }
+                self.fastq = FastqSelection::new(&self.fastq_paths);
+                self.last_upload = None;
+            }
+            Message::ClearFastq => {
+                self.fastq_paths.clear();
+                self.fastq = FastqSelection::default();
+                self.upload = TransferState::default();
+            }
+            Message::StartUpload => {
+                if !self.fastq.is_valid() || self.upload.running {
+                    return Task::none();
+                }
+                let backend = self.backend.clone();
+                let config = self.config.clone();
+                let files = self.fastq.files.clone();
+                let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+                self.upload = TransferState {
+                    running: true,
+                    ..TransferState::default()
+                };
+                commands.push(Task::perform(
+                    async move {
+                        match backend {
+                            Some(backend) => upload_fastq(&backend, &config, files, &sender).await,
+                            None => Err(AppError::network("Not connected".to_string())),
+                        }
+                    },
+                    |result| cosmic::Action::App(Message::UploadedFastq(result)),
+                ));
+                commands.push(Task::run(
+                    stream::unfold(receiver, |mut receiver| async move {
+                        receiver.recv().await.map(|progress| (progress, receiver))
+                    }),
+                    |progress| cosmic::Action::App(Message::UploadProgress(progress)),
+                ));
+            }
+            Message::UploadProgress(progress) => {
+                self.upload.apply(progress);
+            }
+            Message::UploadedFastq(result) => {
+                self.upload.running = false;
+                match result {
+                    Ok(summary) => {
+                        tracing::info!("upload finished: {summary:?}");
+                        self.last_upload = Some(summary);
+                        self.fastq_paths.clear();
+                        self.fastq = FastqSelection::default();
+                        commands.push(Task::done(cosmic::Action::App(Message::LoadRemoteState)));
+                        commands.push(Task::done(cosmic::Action::App(Message::LoadStorage)));
+                    }
+                    Err(err) => {
+                        return self.update(Message::Failed(Box::new(Message::StartUpload), err))
+                    }
+                }
+            }
+            Message::LoadResultSets => {
+                let backend = self.backend.clone();
+                let config = self.config.clone();
+                commands.push(Task::perform(
+                    async move {
+                        match backend {
+                            Some(backend) => list_result_sets(&backend, &config).await,
+                            None => Err(AppError::network("Not connected".to_string())),
+                        }
+                    },
+                    |result| cosmic::Action::App(Message::LoadedResultSets(result)),
+                ));
+            }
+            Message::LoadStorage => {
+                let backend = self.backend.clone();
+                let config = self.config.clone();
+                commands.push(Task::perform(
+                    async move {
+                        match backend {
+                            Some(backend) => storage_status(&backend, &config).await,
+                            None => StorageStatus::default(),
+                        }
+                    },
+                    |status| cosmic::Action::App(Message::LoadedStorage(status)),
+                ));
+            }
+            Message::LoadedStorage(status) => {
+                self.storage = status;
+            }
+            Message::LoadedResultSets(result) => match result {
+                Ok(result_sets) => {
+                    let exists =
+                        |sample: &String| result_sets.iter().any(|set| &set.sample == sample);
+                    self.download_samples.retain(exists);
+                    self.delete_samples.retain(exists);
+                    self.result_sets = result_sets;
+                }
+                Err(err) => tracing::error!("failed to list result sets: {err}"),
+            },
+            Message::ChooseDownloadDir => {
+                let directory = self.config.download_dir();
+                commands.push(Task::perform(
+                    async move {
+                        FileDialog::new()
+                            .set_title("Select directory to download results")
+                            .set_directory(directory)
+                            .pick_folder()
+                    },
+                    |dir| cosmic::Action::App(Message::ChoseDownloadDir(dir)),
+                ));
+            }
+            Message::ChoseDownloadDir(dir) => {
+                if let Some(dir) = dir {
+                    let dir = Some(dir.to_string_lossy().to_string());
+                    match &self.config_handler {
+                        Some(handler) => {
+                            if let Err(err) = self.config.set_local_download_dir(handler, dir) {
+                                tracing::error!("{err}")
+                            }
+                        }
+                        None => self.config.local_download_dir = dir,
+                    }
+                }
+            }
+            Message::OpenDownloadDir => {
+                let dir = self.config.download_dir();
+                if let Err(err) =
+                    std::fs::create_dir_all(&dir).and_then(|_| open::that_detached(&dir))
+                {
+                    tracing::error!("failed to open {dir:?}: {err}");
+                }
+            }
+            Message::LoadTemplates => {
+                let backend = self.backend.clone();
+                let config = self.config.clone();
+                commands.push(Task::perform(
+                    async move {
+                        match backend {
+                            Some(backend) => list_remote_templates(&backend, &config).await,
+                            None => Err(AppError::network("Not connected".to_string())),
+                        }
+                    },
+                    |result| cosmic::Action::App(Message::LoadedTemplates(result)),
+                ));
+            }
+            Message::LoadedTemplates(result) => match result {
+                Ok(templates) => self.templates = templates,
+                Err(err) => tracing::error!("failed to list templates: {err}"),
+            },
+            Message::SelectTemplate(path) => {
+                let path = Some(path);
+                match &self.config_handler {
+                    Some(handler) => {
+                        if let Err(err) = self.config.set_selected_template(handler, path) {
+                            tracing::error!("{err}")
+                        }
+                    }
+                    None => self.config.selected_template = path,
+                }
+            }
+            Message::SetTemplateName(name) => {
+                self.template_name = name;
+            }
+            Message::UploadTemplate => {
+                commands.push(Task::perform(
+                    async move {
+                        let local_path = FileDialog::new()
+                            .set_title("Select template to upload")
+                            .add_filter("DOCX", &["docx"])
+                            .pick_file()?;
+                        let report = validate_docx(&local_path);
+                        Some((local_path, report))
+                    },
+                    |result| cosmic::Action::App(Message::ValidatedTemplate(result)),
+                ));
+            }
+            Message::ValidatedTemplate(result) => match result {
+                Some((local_path, Ok(report))) => {
+                    let warnings = report.warnings();
+                    if warnings.is_empty() {
+                        return self.update(Message::UploadValidatedTemplate(local_path));
+                    }
+                    self.dialog_pages
+                        .push_back(DialogPage::TemplateWarnings(local_path, warnings));
+                }
+                Some((_, Err(err))) => return self.update(Message::Error(err)),
+                None => {}
+            },
+            Message::UploadValidatedTemplate(local_path) => {
+                let backend = self.backend.clone();
+                let config = self.config.clone();
+                let name = self.template_name.clone();
+                self.uploading_template = true;
+                commands.push(Task::perform(
+                    async move {
+                        match backend {
+                            Some(backend) => {
+                                upload_user_template(&backend, &config, &name, local_path).await
+                            }
+                            None => Err(AppError::network("Not connected".to_string())),
+                        }
+                    },
+                    |result| cosmic::Action::App(Message::UploadedTemplate(result)),
+                ));
+            }
+            Message::UploadedTemplate(result) => {
+                self.uploading_template = false;
+                match result {
+                    Ok(path) => {
+                        tracing::info!("uploaded template to {path}");
+                        self.template_name.clear();
+                        commands.push(Task::done(cosmic::Action::App(Message::LoadTemplates)));
+                    }
+                    Err(err) => return self.update(Message::Error(err)),
+                }
+            }
+            Message::SetRenderPdf(value) => {
+                self.render_pdf = value;
+            }
+            Message::RenderReports => {
+                let directory = self.config.download_dir();
+                let pdf = self.render_pdf;
+                self.rendering = true;
+                commands.push(Task::perform(
+                    async move {
+                        let Some(results_files) = FileDialog::new()
+                            .set_title("Select result JSON files")
+                            .add_filter("JSON", &["json"])
+                            .set_directory(&directory)
+                            .pick_files()
+                        else {
+                            return Ok(Vec::new());
+                        };
+                        let Some(template) = FileDialog::new()
+                            .set_title("Select report template")
+                            .add_filter("DOCX", &["docx"])
+                            .set_directory(&directory)
+                            .pick_file()
+                        else {
+                            return Ok(Vec::new());
+                        };
+                        render_reports(template, results_files, pdf).await
+                    },
+                    |result| cosmic::Action::App(Message::RenderedReports(result)),
+                ));
+            }
+            Message::RenderedReports(result) => {
+                self.rendering = false;
+                match result {
+                    Ok(reports) => self.rendered_reports = reports,
+                    Err(err) => return self.update(Message::Error(err)),
+                }
+            }
+            Message::DownloadTemplate => {
+                let backend = self.backend.clone();
+                let config = self.config.clone();
+                commands.push(Task::perform(
+                    async move {
+                        match backend {
+                            Some(backend) => download_default_template(&backend, &config).await,
+                            None => Err(AppError::network("Not connected".to_string())),
+                        }
+                    },
+                    |result| cosmic::Action::App(Message::DownloadedTemplate(result)),
+                ));
+            }
+            Message::DownloadedTemplate(result) => {
+                if let Err(err) = result {
+                    return self.update(Message::Error(err));
+                }
+            }
+            Message::ToggleDeleteSample(sample, value) => {
+                if value {
+                    self.delete_samples.insert(sample);
+                } else {
+                    self.delete_samples.remove(&sample);
+                }
+            }
+            Message::ToggleDeleteRun(run, value) => {
+                for set in self.result_sets.iter().filter(|set| set.run() == run) {
+                    if value {
+                        self.delete_samples.insert(set.sample.clone());
+                    } else {
+                        self.delete_samples.remove(&set.sample);
+                    }
+                }
+            }
+            Message::SetDeleteConfirmation(text) => {
+                self.delete_confirmation = text;
+            }
+            Message::SetDeletePermanently(value) => {
+                self.delete_permanently = value;
+            }
+            Message::StartDelete => {
+                if !self.delete_confirmed() {
+                    return Task::none();
+                }
+                let backend = self.backend.clone();
+                let config = self.config.clone();
+                let samples: Vec<String> = self.delete_samples.iter().cloned().collect();
+                let permanent = self.delete_permanently;
+                self.deleting = true;
+                self.last_delete = None;
+                commands.push(Task::perform(
+                    async move {
+                        match backend {
+                            Some(backend) => {
+                                delete_results(&backend, &config, samples, permanent).await
+                            }
+                            None => Err(AppError::network("Not connected".to_string())),
+                        }
+                    },
+                    |result| cosmic::Action::App(Message::DeletedResults(result)),
+                ));
+            }
+            Message::DeletedResults(result) => {
+                self.deleting = false;
+                self.delete_confirmation.clear();
+                self.delete_samples.clear();
+                commands.push(Task::done(cosmic::Action::App(Message::LoadResultSets)));
+                commands.push(Task::done(cosmic::Action::App(Message::LoadResults)));
+                match result {
+                    Ok(summary) => {
+                        tracing::info!("deleted results: {summary:?}");
+                        self.last_delete = Some(summary);
+                    }
+                    Err(err) => commands.push(self.update(Message::Error(err))),
+                }
+            }
+            Message::SetRetentionDays(days) => {
+                let days = if days.trim().is_empty() {
+                    Some(0)
+                } else {
+                    days.trim().parse().ok()
+                };
+                if let Some(days) = days {
+                    match &self.config_handler {
+                        Some(handler) => {
+                            if let Err(err) = self.config.set_retention_days(handler, days) {
+                                tracing::error!("{err}")
+                            }
+                        }
+                        None => self.config.retention_days = days,
+                    }
+                }
+            }
+            Message::SetArchiveDownload(value) => match &self.config_handler {
+                Some(handler) => {
+                    if let Err(err) = self.config.set_archive_download(handler, value) {
+                        tracing::error!("{err}")
                     }
                 }
+                None => self.config.archive_download = value,
+            },
+            Message::ConfirmArchive => {
+                let samples: Vec<String> = self
+                    .expired_result_sets()
+                    .into_iter()
+                    .map(|set| set.sample.clone())
+                    .collect();
+                if !samples.is_empty() {
+                    self.dialog_pages
+                        .push_back(DialogPage::ArchiveResults(samples));
+                }
+            }
+            Message::StartArchive(samples) => {
+                let backend = self.backend.clone();
+                let config = self.config.clone();
+                self.archiving = true;
+                self.last_archive = None;
+                commands.push(Task::perform(
+                    async move {
+                        match backend {
+                            Some(backend) => archive_results(&backend, &config, samples).await,
+                            None => Err(AppError::network("Not connected".to_string())),
+                        }
+                    },
+                    |result| cosmic::Action::App(Message::ArchivedResults(result)),
+                ));
+            }
+            Message::ArchivedResults(result) => {
+                self.archiving = false;
+                commands.push(Task::done(cosmic::Action::App(Message::LoadResultSets)));
+                commands.push(Task::done(cosmic::Action::App(Message::LoadResults)));
+                match result {
+                    Ok(summary) => {
+                        tracing::info!("archived results: {summary:?}");
+                        self.last_archive = Some(summary);
+                    }
+                    Err(err) => commands.push(self.update(Message::Error(err))),
+                }
+            }
+            Message::ToggleDownloadType(file_type, value) => {
+                self.download_types.retain(|t| *t != file_type);
+                if value {
+                    self.download_types.push(file_type);
+                }
+            }
+            Message::ToggleDownloadSample(sample, value) => {
+                if value {
+                    self.download_samples.insert(sample);
+                } else {
+                    self.download_samples.remove(&sample);
+                }
+            }
+            Message::ClearDownloadSamples => {
+                self.download_samples.clear();
+            }
+            Message::StartDownload => {
+                let samples = (!self.download_samples.is_empty())
+                    .then(|| self.download_samples.iter().cloned().collect());
+                let request = DownloadRequest {
+                    samples,
+                    file_types: self.download_types.clone(),
+                };
+                commands.push(self.start_download(request));
+            }
+            Message::DownloadProgress(progress) => {
+                self.transfer.apply(progress);
+            }
+            Message::DownloadedResults(result) => {
+                self.transfer.running = false;
+                match result {
+                    Ok(summary) => {
+                        tracing::info!("download finished: {summary:?}");
+                        self.last_download = Some(summary);
+                        commands.push(Task::done(cosmic::Action::App(Message::LoadStorage)));
+                    }
+                    Err(err) => {
+                        return self.update(Message::Failed(Box::new(Message::StartDownload), err))
+                    }
+                }
+            }
+            Message::LoadLog => {
+                commands.push(Task::perform(read_log(LOG_VIEW_LINES), |log| {
+                    cosmic::Action::App(Message::LoadedLog(log))
+                }));
+            }
+            Message::LoadedLog(log) => {
+                self.log_text = log;
+            }
+            Message::SetLogLevel(level) => {
+                self.log_level = level;
+            }
+            Message::OpenLogDir => {
+                let dir = log_dir();
+                if let Err(err) = open::that_detached(&dir) {
+                    tracing::error!("failed to open {dir:?}: {err}");
+                }
             }
             Message::OpenRepositoryUrl => {
                 _ = open::that_detached(REPOSITORY);
             }
 
+            Message::EditSetting(field, value) => {
+                self.settings_edited = true;
+                self.settings_draft.set(field, value);
+                self.settings_tests.remove(&field);
+            }
+            Message::EditDefaultPage(index) => {
+                if let Some(&page) = NavPage::all().get(index) {
+                    self.settings_edited = true;
+                    self.settings_draft.default_page = page;
+                }
+            }
+            Message::EditScheduler(index) => {
+                if let Some(&scheduler) = SchedulerKind::all().get(index) {
+                    self.settings_edited = true;
+                    self.settings_draft.scheduler = scheduler;
+                }
+            }
+            Message::EditVerifyChecksums(value) => {
+                self.settings_edited = true;
+                self.settings_draft.verify_checksums = value;
+            }
+            Message::EditRunLocally(value) => {
+                self.settings_edited = true;
+                self.settings_draft.backend = if value {
+                    BackendKind::Local
+                } else {
+                    BackendKind::Ssh
+                };
+            }
+            Message::EditWatchRawDir(value) => {
+                self.settings_edited = true;
+                self.settings_draft.watch_raw_dir = value;
+            }
+            Message::EditAutoSubmit(value) => {
+                self.settings_edited = true;
+                self.settings_draft.auto_submit = value;
+            }
+            Message::ResetSettings => {
+                self.settings_draft = SettingsDraft::from_config(&self.config);
+                self.settings_edited = false;
+                self.settings_tests.clear();
+            }
+            Message::TestSettings => {
+                if !self.settings_draft.is_valid() {
+                    return Task::none();
+                }
+                let config = self.settings_draft.apply(&self.config);
+                let paths = self.settings_draft.remote_paths();
+                self.testing_settings = true;
+                self.settings_tests.clear();
+                commands.push(Task::perform(
+                    async move { test_settings(config, paths).await },
+                    |result| cosmic::Action::App(Message::TestedSettings(result)),
+                ));
+            }
+            Message::TestedSettings(result) => {
+                self.testing_settings = false;
+                match result {
+                    Ok(results) => self.settings_tests = results.into_iter().collect(),
+                    Err(err) => {
+                        return self.update(Message::Failed(Box::new(Message::TestSettings), err))
+                    }
+                }
+            }
+            Message::SaveSettings => {
+                if !self.settings_draft.is_valid() {
+                    return Task::none();
+                }
+                let config = self.settings_draft.apply(&self.config);
+                let reconnect = config.host != self.config.host
+                    || config.username != self.config.username
+                    || config.backend != self.config.backend;
+                if let Some(handler) = &self.config_handler {
+                    if let Err(err) = config.write_entry(handler) {
+                        return self.update(Message::Error(AppError::io(format!(
+                            "Failed to save settings: {err}"
+                        ))));
+                    }
+                }
+                self.settings_draft = SettingsDraft::from_config(&config);
+                self.settings_edited = false;
+                self.config = config;
+                if reconnect {
+                    self.backend = None;
+                    commands.push(self.update(Message::Connect));
+                }
+            }
+            Message::ImportProfile => {
+                commands.push(Task::perform(
+                    async move {
+                        let Some(path) = FileDialog::new()
+                            .set_title("Import profile")
+                            .add_filter("Profile", &["toml", "json"])
+                            .pick_file()
+                        else {
+                            return Ok(None);
+                        };
+                        Profile::read(&path).await.map(Some)
+                    },
+                    |result| cosmic::Action::App(Message::ImportedProfile(result)),
+                ));
+            }
+            Message::ImportClusterProfile => {
+                let backend = self.backend.clone();
+                let config = self.settings_draft.apply(&self.config);
+                commands.push(Task::perform(
+                    async move {
+                        match backend {
+                            Some(backend) => fetch_profile(&backend, &config).await.map(Some),
+                            None => Err(AppError::network("Not connected".to_string())),
+                        }
+                    },
+                    |result| cosmic::Action::App(Message::ImportedProfile(result)),
+                ));
+            }
+            Message::ImportedProfile(result) => match result {
+                Ok(Some(profile)) => {
+                    let config = profile.apply(&self.settings_draft.apply(&self.config));
+                    self.settings_draft = SettingsDraft::from_config(&config);
+                    self.settings_edited = true;
+                    self.settings_tests.clear();
+                    self.profile_status = Some(fl!("profile-imported"));
+                }
+                Ok(None) => {}
+                Err(err) => return self.update(Message::Error(err)),
+            },
+            Message::ExportProfile => {
+                let profile = Profile::from_config(&self.config);
+                commands.push(Task::perform(
+                    async move {
+                        let Some(path) = FileDialog::new()
+                            .set_title("Export profile")
+                            .add_filter("TOML", &["toml"])
+                            .add_filter("JSON", &["json"])
+                            .set_file_name("tbgui-profile.toml")
+                            .save_file()
+                        else {
+                            return Ok(None);
+                        };
+                        profile.write(&path).await?;
+                        Ok(Some(path.to_string_lossy().to_string()))
+                    },
+                    |result| cosmic::Action::App(Message::ExportedProfile(result)),
+                ));
+            }
+            Message::PublishProfile => {
+                let backend = self.backend.clone();
+                let config = self.config.clone();
+                commands.push(Task::perform(
+                    async move {
+                        match backend {
+                            Some(backend) => profile_published(&backend, &config).await,
+                            None => Err(AppError::network("Not connected".to_string())),
+                        }
+                    },
+                    |result| cosmic::Action::App(Message::CheckedPublishedProfile(result)),
+                ));
+            }
+            Message::CheckedPublishedProfile(result) => match result {
+                Ok(true) => {
+                    let path = self.config.remote_profile_file.clone().unwrap_or_default();
+                    self.dialog_pages
+                        .push_back(DialogPage::ReplaceProfile(path));
+                }
+                Ok(false) => return self.update(Message::StartPublishProfile),
+                Err(err) => {
+                    return self.update(Message::Failed(Box::new(Message::PublishProfile), err))
+                }
+            },
+            Message::StartPublishProfile => {
+                let backend = self.backend.clone();
+                let config = self.config.clone();
+                let profile = Profile::from_config(&self.config);
+                commands.push(Task::perform(
+                    async move {
+                        match backend {
+                            Some(backend) => {
+                                push_profile(&backend, &config, &profile).await?;
+                                Ok(config.remote_profile_file)
+                            }
+                            None => Err(AppError::network("Not connected".to_string())),
+                        }
+                    },
+                    |result| cosmic::Action::App(Message::ExportedProfile(result)),
+                ));
+            }
+            Message::ExportedProfile(result) => match result {
+                Ok(Some(path)) => self.profile_status = Some(fl!("profile-exported", path = path)),
+                Ok(None) => {}
+                Err(err) => return self.update(Message::Error(err)),
+            },
             Message::UpdateConfig(config) => {
+                let lists_changed = self.config.batches != config.batches;
                 self.config = config;
+                if lists_changed {
+                    self.refresh_lists();
+                }
             }
 
             Message::LaunchUrl(url) => match open::that_detached(&url) {
                 Ok(()) => {}
                 Err(err) => {
-                    eprintln!("failed to open {url:?}: {err}");
+                    tracing::error!("failed to open {url:?}: {err}");
                 }
             },
             Message::Error(err) => {
-                eprintln!("Error: {}", err);
+                tracing::error!("{err}");
+                self.retry = None;
                 self.dialog_pages.pop_front();
-                self.dialog_pages.push_back(DialogPage::Info(err));
+                self.dialog_pages.push_back(DialogPage::Info {
+                    error: err,
+                    details: false,
+                    retry: false,
+                });
+            }
+            Message::Failed(retry, err) => {
+                tracing::error!("{err}");
+                let retryable = err.retryable();
+                self.retry = retryable.then_some(*retry);
+                self.dialog_pages.pop_front();
+                self.dialog_pages.push_back(DialogPage::Info {
+                    error: err,
+                    details: false,
+                    retry: retryable,
+                });
+            }
+            Message::CopyToClipboard(text) => {
+                return cosmic::iced::clipboard::write(text);
+            }
+            Message::CloseToast(id) => {
+                self.toasts.remove(id);
+            }
+            Message::Retry => {
+                self.dialog_pages.pop_front();
+                if let Some(retry) = self.retry.take() {
+                    return self.update(retry);
+                }
             }
             Message::DialogCancel => {
                 self.dialog_pages.pop_front();
             }
+            Message::DialogUpdate(dialog_page) => {
+                if let Some(page) = self.dialog_pages.front_mut() {
+                    *page = dialog_page;
+                }
+            }
+            Message::DialogComplete => {
+                if let Some(dialog_page) = self.dialog_pages.pop_front() {
+                    match dialog_page {
+                        DialogPage::NewList(name) => {
+                            let list = List::new(name.trim());
+                            return self.update(Message::Tasks(TasksAction::AddList(list)));
+                        }
+                        DialogPage::DeleteList(entity) => {
+                            return self.update(Message::Tasks(TasksAction::DeleteList(entity)));
+                        }
+                        DialogPage::TemplateWarnings(local_path, _) => {
+                            return self.update(Message::UploadValidatedTemplate(local_path));
+                        }
+                        DialogPage::ArchiveResults(samples) => {
+                            return self.update(Message::StartArchive(samples));
+                        }
+                        DialogPage::ReplaceProfile(_) => {
+                            return self.update(Message::StartPublishProfile);
+                        }
+                        DialogPage::FastqFailed { samples, .. } => {
+                            return self.update(Message::RunCheckedSamples(samples));
+                        }
+                        DialogPage::InsufficientSpace { samples, .. } => {
+                            return self.update(Message::SubmitSamples(samples));
+                        }
+                        DialogPage::Info { .. } | DialogPage::Submitted(_) => {}
+                    }
+                }
+            }
             Message::Application(application_action) => match application_action {
                 ApplicationAction::WindowClose => {
                     if let Some(window_id) = self.core.main_window_id() {
@@ -316,11 +1914,11 @@ impl cosmic::Application for Tbgui {
                     Ok(exe) => match process::Command::new(&exe).spawn() {
                         Ok(_) => {}
                         Err(err) => {
-                            eprintln!("failed to execute {exe:?}: {err}");
+                            tracing::error!("failed to execute {exe:?}: {err}");
                         }
                     },
                     Err(err) => {
-                        eprintln!("failed to get current executable path: {err}");
+                        tracing::error!("failed to get current executable path: {err}");
                     }
                 },
 
@@ -356,6 +1954,22 @@ impl cosmic::Application for Tbgui {
                 ApplicationAction::ToggleContextDrawer => {
                     self.core.window.show_context = !self.core.window.show_context
                 }
+                ApplicationAction::Refresh => {
+                    commands.push(Task::done(cosmic::Action::App(Message::LoadRemoteState)));
+                    commands.push(Task::done(cosmic::Action::App(Message::LoadResults)));
+                    commands.push(Task::done(cosmic::Action::App(Message::LoadResultSets)));
+                    commands.push(Task::done(cosmic::Action::App(Message::LoadStorage)));
+                }
+                ApplicationAction::NewList => {
+                    self.dialog_pages
+                        .push_back(DialogPage::NewList(String::new()));
+                }
+                ApplicationAction::DeleteList => {
+                    if self.nav_model.active_data::<List>().is_some() {
+                        self.dialog_pages
+                            .push_back(DialogPage::DeleteList(Some(self.nav_model.active())));
+                    }
+                }
             },
         }
         Task::batch(commands)
@@ -364,7 +1978,32 @@ impl cosmic::Application for Tbgui {
     /// Called when a nav item is selected.
     fn on_nav_select(&mut self, id: nav_bar::Id) -> Task<cosmic::Action<Self::Message>> {
         self.nav_model.activate(id);
-        self.update_title()
+        let mut commands = vec![self.update_title()];
+        if matches!(
+            self.nav_model.active_data::<NavPage>(),
+            Some(NavPage::DownloadResults | NavPage::DeleteResults)
+        ) {
+            commands.push(Task::done(cosmic::Action::App(Message::LoadResultSets)));
+        }
+        if matches!(
+            self.nav_model.active_data::<NavPage>(),
+            Some(NavPage::RunTbProfiler | NavPage::DownloadResults)
+        ) {
+            commands.push(Task::done(cosmic::Action::App(Message::LoadStorage)));
+        }
+        if self.nav_model.active_data::<NavPage>() == Some(&NavPage::Templates) {
+            commands.push(Task::done(cosmic::Action::App(Message::LoadTemplates)));
+        }
+        if self.nav_model.active_data::<NavPage>() == Some(&NavPage::Logs) {
+            commands.push(Task::done(cosmic::Action::App(Message::LoadLog)));
+        }
+        if self.nav_model.active_data::<NavPage>() == Some(&NavPage::Settings)
+            && !self.settings_edited
+        {
+            // Pick up changes made elsewhere, but keep unsaved edits.
+            self.settings_draft = SettingsDraft::from_config(&self.config);
+        }
+        Task::batch(commands)
     }
 }
 
@@ -390,7 +2029,7 @@ where
     fn save_config(&mut self) -> Task<cosmic::Action<Message>> {
         if let Some(ref config_handler) = self.config_handler {
             if let Err(err) = self.config.write_entry(config_handler) {
-                log::error!("failed to save config: {}", err);
+                tracing::error!("failed to save config: {err}");
             }
         }
         Task::none()
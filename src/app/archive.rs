@@ -0,0 +1,79 @@
+use super::backend::{Backend, RemoteBackend};
+use super::transfer::{copy_remote_file, local_sha256, remote_sha256, RemoteFile, ResultSet};
+use super::types::AppError;
+use super::utils::shell_quote;
+use std::path::Path;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ArchiveSummary {
+    pub samples: usize,
+    pub files: usize,
+    /// Size of the archived result files removed from `remote_out_dir`.
+    pub freed: u64,
+    pub archive_size: u64,
+    pub archive: String,
+    /// Local copy of the archive if it was downloaded.
+    pub local_copy: Option<String>,
+}
+
+/// Result sets that have not been modified for more than `days` days.
+pub fn expired_result_sets(sets: &[ResultSet], days: u32, now: u64) -> Vec<&ResultSet> {
+    let cutoff = now.saturating_sub(u64::from(days) * SECONDS_PER_DAY);
+    sets.iter()
+        .filter(|set| set.modified.is_some_and(|mtime| u64::from(mtime) < cutoff))
+        .collect()
+}
+
+/// Packs `files` into a compressed tarball at `archive`, with paths relative
+/// to `remote_out_dir`, and checks that the archive can be read back.
+pub async fn create_archive(
+    backend: &Backend,
+    remote_out_dir: &str,
+    files: &[RemoteFile],
+    archive: &str,
+) -> Result<u64, AppError> {
+    let list_path = format!("{archive}.list");
+    let list: String = files
+        .iter()
+        .map(|file| format!("{}/{}\n", file.subdir, file.name))
+        .collect();
+    if let Some((parent, _)) = archive.rsplit_once('/') {
+        backend
+            .exec(&format!("mkdir -p {}", shell_quote(parent)))
+            .await?
+            .check(format!("Failed to create {parent}"))?;
+    }
+    backend.write(&list_path, list.as_bytes()).await?;
+
+    let command = format!(
+        "tar czf {archive} -C {out} -T {list} && tar tzf {archive} > /dev/null; status=$?; rm -f {list}; exit $status",
+        archive = shell_quote(archive),
+        out = shell_quote(remote_out_dir),
+        list = shell_quote(&list_path),
+    );
+    backend
+        .exec(&command)
+        .await?
+        .check(format!("Failed to create archive {archive}"))?;
+    Ok(backend.stat(archive).await?.size)
+}
+
+/// Downloads `archive` to `local_path` and compares the SHA-256 of both copies.
+pub async fn download_archive(
+    backend: &Backend,
+    archive: &str,
+    local_path: &Path,
+) -> Result<(), AppError> {
+    copy_remote_file(backend, archive, local_path).await?;
+    let (local, remote) =
+        tokio::try_join!(local_sha256(local_path), remote_sha256(backend, archive))?;
+    if local != remote {
+        let _ = tokio::fs::remove_file(local_path).await;
+        return Err(AppError::io(format!(
+            "Checksum mismatch for downloaded archive {archive}"
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,398 @@
+use super::config::{BackendKind, TbguiConfig, TBGUI_HOST};
+use super::types::{AppError, CommandOutput, ErrorKind};
+use async_ssh2_tokio::client::{AuthMethod, Client, ServerCheckMethod};
+use russh_sftp::{client::SftpSession, protocol::OpenFlags};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncWrite, AsyncWriteExt};
+use tokio::sync::OnceCell;
+
+/// An open file of a backend, readable, writable and seekable depending on
+/// how it was opened.
+pub trait BackendFile: AsyncRead + AsyncWrite + AsyncSeek + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + AsyncSeek + Send + Unpin> BackendFile for T {}
+
+/// Size, modification time and type of a path.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Metadata {
+    pub size: u64,
+    /// Seconds since the epoch.
+    pub mtime: Option<u32>,
+    pub is_dir: bool,
+    pub is_file: bool,
+}
+
+/// An entry of [`RemoteBackend::read_dir`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DirEntry {
+    pub name: String,
+    pub metadata: Metadata,
+}
+
+/// Where tb-profiler runs and its files live. Paths are absolute POSIX paths
+/// on the machine of the backend and commands run in a POSIX shell there.
+pub trait RemoteBackend {
+    /// Runs `command` and keeps everything it printed.
+    async fn exec(&self, command: &str) -> Result<CommandOutput, AppError>;
+
+    /// The entries of the directory `path`, without `.` and `..`.
+    async fn read_dir(&self, path: &str) -> Result<Vec<DirEntry>, AppError>;
+
+    async fn stat(&self, path: &str) -> Result<Metadata, AppError>;
+
+    async fn exists(&self, path: &str) -> Result<bool, AppError>;
+
+    /// Opens `path` for reading.
+    async fn reader(&self, path: &str) -> Result<Box<dyn BackendFile>, AppError>;
+
+    /// Opens `path` for writing, creating it if needed. Existing content is
+    /// kept unless `truncate` is set.
+    async fn writer(&self, path: &str, truncate: bool) -> Result<Box<dyn BackendFile>, AppError>;
+
+    /// Removes the file `path`.
+    async fn remove(&self, path: &str) -> Result<(), AppError>;
+
+    async fn rename(&self, from: &str, to: &str) -> Result<(), AppError>;
+
+    /// The whole content of `path`.
+    async fn read(&self, path: &str) -> Result<Vec<u8>, AppError> {
+        let mut data = Vec::new();
+        self.reader(path).await?.read_to_end(&mut data).await?;
+        Ok(data)
+    }
+
+    /// Replaces the content of `path` with `data`.
+    async fn write(&self, path: &str, data: &[u8]) -> Result<(), AppError> {
+        let mut file = self.writer(path, true).await?;
+        file.write_all(data).await?;
+        file.shutdown().await?;
+        Ok(())
+    }
+
+    /// Copies the local file `local_path` to `path`.
+    async fn upload(&self, local_path: &Path, path: &str) -> Result<(), AppError> {
+        let mut local_file = tokio::fs::File::open(local_path).await?;
+        let mut file = self.writer(path, true).await?;
+        tokio::io::copy(&mut local_file, &mut file).await?;
+        file.shutdown().await?;
+        Ok(())
+    }
+}
+
+/// The backend selected in the configuration.
+#[derive(Clone, Debug)]
+pub enum Backend {
+    Ssh(SshBackend),
+    Local(LocalBackend),
+}
+
+impl Backend {
+    /// Connects to the backend selected in `config`.
+    pub async fn connect(config: &TbguiConfig) -> Result<Self, AppError> {
+        match config.backend {
+            BackendKind::Ssh => Ok(Self::Ssh(SshBackend::connect(config).await?)),
+            BackendKind::Local => Ok(Self::Local(LocalBackend::default())),
+        }
+    }
+}
+
+impl RemoteBackend for Backend {
+    async fn exec(&self, command: &str) -> Result<CommandOutput, AppError> {
+        match self {
+            Self::Ssh(backend) => backend.exec(command).await,
+            Self::Local(backend) => backend.exec(command).await,
+        }
+    }
+
+    async fn read_dir(&self, path: &str) -> Result<Vec<DirEntry>, AppError> {
+        match self {
+            Self::Ssh(backend) => backend.read_dir(path).await,
+            Self::Local(backend) => backend.read_dir(path).await,
+        }
+    }
+
+    async fn stat(&self, path: &str) -> Result<Metadata, AppError> {
+        match self {
+            Self::Ssh(backend) => backend.stat(path).await,
+            Self::Local(backend) => backend.stat(path).await,
+        }
+    }
+
+    async fn exists(&self, path: &str) -> Result<bool, AppError> {
+        match self {
+            Self::Ssh(backend) => backend.exists(path).await,
+            Self::Local(backend) => backend.exists(path).await,
+        }
+    }
+
+    async fn reader(&self, path: &str) -> Result<Box<dyn BackendFile>, AppError> {
+        match self {
+            Self::Ssh(backend) => backend.reader(path).await,
+            Self::Local(backend) => backend.reader(path).await,
+        }
+    }
+
+    async fn writer(&self, path: &str, truncate: bool) -> Result<Box<dyn BackendFile>, AppError> {
+        match self {
+            Self::Ssh(backend) => backend.writer(path, truncate).await,
+            Self::Local(backend) => backend.writer(path, truncate).await,
+        }
+    }
+
+    async fn remove(&self, path: &str) -> Result<(), AppError> {
+        match self {
+            Self::Ssh(backend) => backend.remove(path).await,
+            Self::Local(backend) => backend.remove(path).await,
+        }
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<(), AppError> {
+        match self {
+            Self::Ssh(backend) => backend.rename(from, to).await,
+            Self::Local(backend) => backend.rename(from, to).await,
+        }
+    }
+}
+
+/// The cluster, reached over SSH. Files go through one SFTP session that is
+/// opened on first use and shared by all clones.
+#[derive(Clone)]
+pub struct SshBackend {
+    client: Client,
+    sftp: Arc<OnceCell<SftpSession>>,
+}
+
+impl SshBackend {
+    #[tracing::instrument(skip_all, fields(host = ?config.host, username = ?config.username))]
+    pub async fn connect(config: &TbguiConfig) -> Result<Self, AppError> {
+        let key_path = config.ssh_key();
+        if !key_path.exists() {
+            return Err(AppError::new(
+                ErrorKind::Auth,
+                format!("SSH key file not found at path: {:?}", key_path),
+            ));
+        }
+        let auth_method = AuthMethod::with_key_file(key_path, None);
+        Self::connect_with(config, auth_method, ServerCheckMethod::NoCheck).await
+    }
+
+    /// Connects to the host and user of `config` with `auth_method`.
+    pub async fn connect_with(
+        config: &TbguiConfig,
+        auth_method: AuthMethod,
+        server_check: ServerCheckMethod,
+    ) -> Result<Self, AppError> {
+        let client = Client::connect(
+            (config.host.as_deref().unwrap_or(TBGUI_HOST), 22),
+            config.username.as_deref().ok_or_else(|| {
+                AppError::config_missing("Username is not set in the configuration")
+            })?,
+            auth_method,
+            server_check,
+        )
+        .await?;
+        Ok(Self::new(client))
+    }
+
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            sftp: Arc::new(OnceCell::new()),
+        }
+    }
+
+    async fn sftp(&self) -> Result<&SftpSession, AppError> {
+        self.sftp.get_or_try_init(|| open_sftp(&self.client)).await
+    }
+}
+
+impl std::fmt::Debug for SshBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SshBackend")
+            .field("client", &self.client)
+            .field("sftp_open", &self.sftp.initialized())
+            .finish()
+    }
+}
+
+async fn open_sftp(client: &Client) -> Result<SftpSession, AppError> {
+    let channel = client
+        .get_channel()
+        .await
+        .map_err(|e| AppError::sftp("Failed to open SSH channel").with_source(&e))?;
+    channel
+        .request_subsystem(true, "sftp")
+        .await
+        .map_err(|e| AppError::sftp("Failed to request SFTP subsystem").with_source(&e))?;
+    let sftp = SftpSession::new(channel.into_stream())
+        .await
+        .map_err(|e| AppError::sftp("Failed to start SFTP session").with_source(&e))?;
+    Ok(sftp)
+}
+
+fn sftp_metadata(metadata: &russh_sftp::client::fs::Metadata) -> Metadata {
+    let file_type = metadata.file_type();
+    Metadata {
+        size: metadata.size.unwrap_or_default(),
+        mtime: metadata.mtime,
+        is_dir: file_type.is_dir(),
+        is_file: file_type.is_file(),
+    }
+}
+
+impl RemoteBackend for SshBackend {
+    async fn exec(&self, command: &str) -> Result<CommandOutput, AppError> {
+        let result = self.client.execute(command).await?;
+        Ok(CommandOutput {
+            command: command.to_string(),
+            stdout: result.stdout,
+            stderr: result.stderr,
+            exit_status: result.exit_status,
+        })
+    }
+
+    async fn read_dir(&self, path: &str) -> Result<Vec<DirEntry>, AppError> {
+        Ok(self
+            .sftp()
+            .await?
+            .read_dir(path)
+            .await?
+            .filter(|entry| !matches!(entry.file_name().as_str(), "." | ".."))
+            .map(|entry| DirEntry {
+                name: entry.file_name(),
+                metadata: sftp_metadata(&entry.metadata()),
+            })
+            .collect())
+    }
+
+    async fn stat(&self, path: &str) -> Result<Metadata, AppError> {
+        Ok(sftp_metadata(&self.sftp().await?.metadata(path).await?))
+    }
+
+    async fn exists(&self, path: &str) -> Result<bool, AppError> {
+        Ok(self.sftp().await?.try_exists(path).await?)
+    }
+
+    async fn reader(&self, path: &str) -> Result<Box<dyn BackendFile>, AppError> {
+        let file = self
+            .sftp()
+            .await?
+            .open_with_flags(path, OpenFlags::READ)
+            .await?;
+        Ok(Box::new(file))
+    }
+
+    async fn writer(&self, path: &str, truncate: bool) -> Result<Box<dyn BackendFile>, AppError> {
+        let mut flags = OpenFlags::CREATE | OpenFlags::WRITE;
+        if truncate {
+            flags |= OpenFlags::TRUNCATE;
+        }
+        let file = self.sftp().await?.open_with_flags(path, flags).await?;
+        Ok(Box::new(file))
+    }
+
+    async fn remove(&self, path: &str) -> Result<(), AppError> {
+        Ok(self.sftp().await?.remove_file(path).await?)
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<(), AppError> {
+        Ok(self.sftp().await?.rename(from, to).await?)
+    }
+}
+
+/// This computer. Commands run in `sh` as the current user, so tb-profiler
+/// and the scheduler have to be installed locally.
+#[derive(Clone, Debug, Default)]
+pub struct LocalBackend {
+    /// Variables set for every command in addition to the app's environment.
+    env: Vec<(String, String)>,
+}
+
+impl LocalBackend {
+    /// Sets `key` to `value` for every command, e.g. a `PATH` with fake
+    /// scheduler commands.
+    #[cfg(test)]
+    pub fn with_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+}
+
+fn local_metadata(metadata: &std::fs::Metadata) -> Metadata {
+    Metadata {
+        size: metadata.len(),
+        mtime: metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .and_then(|age| u32::try_from(age.as_secs()).ok()),
+        is_dir: metadata.is_dir(),
+        is_file: metadata.is_file(),
+    }
+}
+
+impl RemoteBackend for LocalBackend {
+    async fn exec(&self, command: &str) -> Result<CommandOutput, AppError> {
+        let output = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .envs(self.env.iter().map(|(key, value)| (key, value)))
+            .output()
+            .await
+            .map_err(|e| AppError::io(format!("Failed to run {command}")).with_source(&e))?;
+        Ok(CommandOutput {
+            command: command.to_string(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            exit_status: output
+                .status
+                .code()
+                .and_then(|code| u32::try_from(code).ok())
+                .unwrap_or(u32::MAX),
+        })
+    }
+
+    async fn read_dir(&self, path: &str) -> Result<Vec<DirEntry>, AppError> {
+        let mut entries = Vec::new();
+        let mut dir = tokio::fs::read_dir(path).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            entries.push(DirEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                metadata: local_metadata(&entry.metadata().await?),
+            });
+        }
+        Ok(entries)
+    }
+
+    async fn stat(&self, path: &str) -> Result<Metadata, AppError> {
+        Ok(local_metadata(&tokio::fs::metadata(path).await?))
+    }
+
+    async fn exists(&self, path: &str) -> Result<bool, AppError> {
+        Ok(tokio::fs::try_exists(path).await?)
+    }
+
+    async fn reader(&self, path: &str) -> Result<Box<dyn BackendFile>, AppError> {
+        Ok(Box::new(tokio::fs::File::open(path).await?))
+    }
+
+    async fn writer(&self, path: &str, truncate: bool) -> Result<Box<dyn BackendFile>, AppError> {
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(truncate)
+            .open(path)
+            .await?;
+        Ok(Box::new(file))
+    }
+
+    async fn remove(&self, path: &str) -> Result<(), AppError> {
+        Ok(tokio::fs::remove_file(path).await?)
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<(), AppError> {
+        Ok(tokio::fs::rename(from, to).await?)
+    }
+}
@@ -2,13 +2,17 @@ use cosmic::{
     cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, Config, CosmicConfigEntry},
     theme, Application,
 };
+use directories_next::UserDirs;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 use super::{NavPage, Tbgui};
+use crate::model::List;
+use crate::RESULT_DIR_LOCAL;
 
 pub const CONFIG_VERSION: u64 = 1;
 
-pub const TBGUI_USERNAME: &str = default_env(option_env!("TBGUI_USERNAME"), "mimeul");
+pub const TBGUI_HOST: &str = default_env(option_env!("TBGUI_HOST"), "130.60.24.133");
 pub const REMOTE_RAW_DIR: &str = default_env(
     option_env!("REMOTE_RAW_DIR"),
     "/shares/sander.imm.uzh/MM/PRJEB57919/raw",
@@ -29,15 +33,69 @@ pub const USER_TEMPLATE_REMOTE: &str = default_env(
     option_env!("USER_TEMPLATE_REMOTE"),
     "/shares/sander.imm.uzh/MM/PRJEB57919/template/user_template.docx",
 );
+pub const REMOTE_BATCHES_FILE: &str = default_env(
+    option_env!("REMOTE_BATCHES_FILE"),
+    "/shares/sander.imm.uzh/MM/PRJEB57919/tbgui/batches.json",
+);
+pub const REMOTE_TRASH_DIR: &str = default_env(
+    option_env!("REMOTE_TRASH_DIR"),
+    "/shares/sander.imm.uzh/MM/PRJEB57919/trash",
+);
+pub const REMOTE_ARCHIVE_DIR: &str = default_env(
+    option_env!("REMOTE_ARCHIVE_DIR"),
+    "/shares/sander.imm.uzh/MM/PRJEB57919/archive",
+);
+pub const REMOTE_TEMPLATE_DIR: &str = default_env(
+    option_env!("REMOTE_TEMPLATE_DIR"),
+    "/shares/sander.imm.uzh/MM/PRJEB57919/template",
+);
+/// Prints used bytes and hard limit of the user's block quota on the file
+/// system of `{dir}`, and nothing if there is no quota.
+pub const REMOTE_QUOTA_COMMAND: &str = r#"quota -w -f {dir} 2>/dev/null | awk 'NR > 2 && $4 + 0 > 0 { printf "%.0f %.0f\n", $2 * 1024, $4 * 1024; exit }'"#;
 
 #[derive(Clone, CosmicConfigEntry, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct TbguiConfig {
     pub username: Option<String>,
+    pub host: Option<String>,
+    /// Run tb-profiler on the cluster over SSH or on this computer.
+    pub backend: BackendKind,
+    /// Batch system runs are submitted to, see [`super::jobs::Scheduler`].
+    pub scheduler: SchedulerKind,
+    /// Private key used to connect, `~/.ssh/id_rsa` if not set.
+    pub ssh_key_path: Option<String>,
+    /// The setup assistant has been completed, it is shown on start until then.
+    pub setup_complete: bool,
     pub remote_raw_dir: Option<String>,
     pub tb_profiler_script: Option<String>,
     pub remote_out_dir: Option<String>,
     pub default_template_remote: Option<String>,
     pub user_template_remote: Option<String>,
+    pub remote_template_dir: Option<String>,
+    /// Template used for new runs, `user_template_remote` if not set.
+    pub selected_template: Option<String>,
+    pub remote_batches_file: Option<String>,
+    /// Shared profile published for the group, see [`super::profile::Profile`].
+    /// Not set by default, each group chooses where it keeps its profile.
+    pub remote_profile_file: Option<String>,
+    pub remote_trash_dir: Option<String>,
+    pub remote_archive_dir: Option<String>,
+    /// Result sets older than this many days are offered for archiving, 0 disables retention.
+    pub retention_days: u32,
+    /// Download and verify archives locally before the remote results are removed.
+    pub archive_download: bool,
+    /// Command printing used and limit bytes of the quota for `{dir}`, e.g. for `remote_out_dir`.
+    pub remote_quota_command: Option<String>,
+    /// Estimated size of the output of one sample, used to warn before submitting.
+    pub estimated_output_per_sample: u64,
+    pub batches: Vec<List>,
+    pub local_download_dir: Option<String>,
+    pub transfer_concurrency: usize,
+    pub verify_checksums: bool,
+    /// Poll `remote_raw_dir` for new complete read pairs.
+    pub watch_raw_dir: bool,
+    pub watch_interval_minutes: u32,
+    /// Submit new read pairs found by the watch and download their results.
+    pub auto_submit: bool,
     pub default_page: NavPage,
     pub app_theme: AppTheme,
 }
@@ -45,12 +103,34 @@ pub struct TbguiConfig {
 impl Default for TbguiConfig {
     fn default() -> Self {
         Self {
-            username: Some(TBGUI_USERNAME.to_string()),
+            username: None,
+            host: Some(TBGUI_HOST.to_string()),
+            backend: BackendKind::Ssh,
+            scheduler: SchedulerKind::Slurm,
+            ssh_key_path: None,
+            setup_complete: false,
             remote_raw_dir: Some(REMOTE_RAW_DIR.to_string()),
             tb_profiler_script: Some(TB_PROFILER_SCRIPT.to_string()),
             remote_out_dir: Some(REMOTE_OUT_DIR.to_string()),
             default_template_remote: Some(DEFAULT_TEMPLATE_REMOTE.to_string()),
             user_template_remote: Some(USER_TEMPLATE_REMOTE.to_string()),
+            remote_template_dir: Some(REMOTE_TEMPLATE_DIR.to_string()),
+            selected_template: None,
+            remote_batches_file: Some(REMOTE_BATCHES_FILE.to_string()),
+            remote_profile_file: None,
+            remote_trash_dir: Some(REMOTE_TRASH_DIR.to_string()),
+            remote_archive_dir: Some(REMOTE_ARCHIVE_DIR.to_string()),
+            retention_days: 90,
+            archive_download: false,
+            remote_quota_command: Some(REMOTE_QUOTA_COMMAND.to_string()),
+            estimated_output_per_sample: 500 * 1024 * 1024,
+            batches: Vec::new(),
+            local_download_dir: None,
+            transfer_concurrency: 4,
+            verify_checksums: false,
+            watch_raw_dir: false,
+            watch_interval_minutes: 10,
+            auto_submit: false,
             default_page: NavPage::RunTbProfiler,
             app_theme: AppTheme::Light,
         }
@@ -73,7 +153,7 @@ impl TbguiConfig {
         match Self::config_handler() {
             Some(config_handler) => {
                 TbguiConfig::get_entry(&config_handler).unwrap_or_else(|(errs, config)| {
-                    log::info!("errors loading config: {:?}", errs);
+                    tracing::info!("errors loading config: {:?}", errs);
 
                     config
                 })
@@ -81,6 +161,69 @@ impl TbguiConfig {
             None => TbguiConfig::default(),
         }
     }
+
+    /// Remote path of the template passed to new runs.
+    pub fn run_template(&self) -> Option<&str> {
+        self.selected_template
+            .as_deref()
+            .or(self.user_template_remote.as_deref())
+    }
+
+    /// Whether the setup assistant has to run. Configurations from before it
+    /// existed count as set up once they name a user and an existing key; the
+    /// user has no default, so only a saved one counts.
+    pub fn needs_setup(&self) -> bool {
+        let saved_user = self
+            .username
+            .as_deref()
+            .is_some_and(|user| !user.trim().is_empty());
+        !(self.setup_complete || (saved_user && self.ssh_key().is_file()))
+    }
+
+    /// Private key used to connect to the cluster.
+    pub fn ssh_key(&self) -> PathBuf {
+        match &self.ssh_key_path {
+            Some(path) => PathBuf::from(path),
+            None => UserDirs::new()
+                .map(|dirs| dirs.home_dir().join(".ssh").join("id_rsa"))
+                .unwrap_or_else(|| PathBuf::from("id_rsa")),
+        }
+    }
+
+    /// Directory the scheduler writes the output of each task to.
+    pub fn remote_log_dir(&self) -> Option<String> {
+        self.remote_out_dir
+            .as_deref()
+            .map(|dir| format!("{}/logs", dir.trim_end_matches('/')))
+    }
+
+    /// Local directory results are downloaded to, `~/tb-profiler-results` unless configured.
+    pub fn download_dir(&self) -> PathBuf {
+        match &self.local_download_dir {
+            Some(dir) => PathBuf::from(dir),
+            None => UserDirs::new()
+                .map(|dirs| dirs.home_dir().join(RESULT_DIR_LOCAL))
+                .unwrap_or_else(|| PathBuf::from(RESULT_DIR_LOCAL)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum BackendKind {
+    #[default]
+    Ssh,
+    Local,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SchedulerKind {
+    #[default]
+    Slurm,
+    Pbs,
+    Sge,
+    Lsf,
+    /// No scheduler, tasks run in the background on the server.
+    Background,
 }
 
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
@@ -0,0 +1,326 @@
+use super::config::{BackendKind, SchedulerKind, TbguiConfig};
+use super::NavPage;
+use crate::fl;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// An editable text field of the settings page.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SettingsField {
+    Username,
+    Host,
+    RemoteRawDir,
+    TbProfilerScript,
+    RemoteOutDir,
+    DefaultTemplateRemote,
+    UserTemplateRemote,
+    RemoteTemplateDir,
+    RemoteBatchesFile,
+    RemoteProfileFile,
+    RemoteTrashDir,
+    RemoteArchiveDir,
+    RemoteQuotaCommand,
+    LocalDownloadDir,
+    TransferConcurrency,
+    RetentionDays,
+    EstimatedOutputPerSample,
+    WatchInterval,
+}
+
+/// What a remote path has to be for the connection test to pass.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PathKind {
+    Dir,
+    File,
+    /// Created on demand, only the parent directory has to exist.
+    Parent,
+}
+
+impl SettingsField {
+    pub fn connection() -> &'static [Self] {
+        &[Self::Username, Self::Host]
+    }
+
+    pub fn remote_paths() -> &'static [Self] {
+        &[
+            Self::RemoteRawDir,
+            Self::TbProfilerScript,
+            Self::RemoteOutDir,
+            Self::DefaultTemplateRemote,
+            Self::UserTemplateRemote,
+            Self::RemoteTemplateDir,
+            Self::RemoteBatchesFile,
+            Self::RemoteProfileFile,
+            Self::RemoteTrashDir,
+            Self::RemoteArchiveDir,
+        ]
+    }
+
+    pub fn other() -> &'static [Self] {
+        &[
+            Self::RemoteQuotaCommand,
+            Self::LocalDownloadDir,
+            Self::TransferConcurrency,
+            Self::RetentionDays,
+            Self::EstimatedOutputPerSample,
+            Self::WatchInterval,
+        ]
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::Username => fl!("setting-username"),
+            Self::Host => fl!("setting-host"),
+            Self::RemoteRawDir => fl!("setting-remote-raw-dir"),
+            Self::TbProfilerScript => fl!("setting-tb-profiler-script"),
+            Self::RemoteOutDir => fl!("setting-remote-out-dir"),
+            Self::DefaultTemplateRemote => fl!("setting-default-template"),
+            Self::UserTemplateRemote => fl!("setting-user-template"),
+            Self::RemoteTemplateDir => fl!("setting-remote-template-dir"),
+            Self::RemoteBatchesFile => fl!("setting-remote-batches-file"),
+            Self::RemoteProfileFile => fl!("setting-remote-profile-file"),
+            Self::RemoteTrashDir => fl!("setting-remote-trash-dir"),
+            Self::RemoteArchiveDir => fl!("setting-remote-archive-dir"),
+            Self::RemoteQuotaCommand => fl!("setting-remote-quota-command"),
+            Self::LocalDownloadDir => fl!("setting-local-download-dir"),
+            Self::TransferConcurrency => fl!("setting-transfer-concurrency"),
+            Self::RetentionDays => fl!("setting-retention-days"),
+            Self::EstimatedOutputPerSample => fl!("setting-estimated-output"),
+            Self::WatchInterval => fl!("setting-watch-interval"),
+        }
+    }
+
+    pub fn path_kind(&self) -> Option<PathKind> {
+        match self {
+            Self::RemoteRawDir | Self::RemoteOutDir => Some(PathKind::Dir),
+            Self::TbProfilerScript | Self::DefaultTemplateRemote => Some(PathKind::File),
+            Self::UserTemplateRemote
+            | Self::RemoteTemplateDir
+            | Self::RemoteBatchesFile
+            | Self::RemoteProfileFile
+            | Self::RemoteTrashDir
+            | Self::RemoteArchiveDir => Some(PathKind::Parent),
+            _ => None,
+        }
+    }
+
+    fn optional(&self) -> bool {
+        matches!(self, Self::RemoteQuotaCommand | Self::LocalDownloadDir)
+    }
+}
+
+/// Unsaved state of the settings page, all text fields as typed.
+#[derive(Clone, Debug)]
+pub struct SettingsDraft {
+    values: HashMap<SettingsField, String>,
+    pub backend: BackendKind,
+    pub scheduler: SchedulerKind,
+    pub default_page: NavPage,
+    pub verify_checksums: bool,
+    pub watch_raw_dir: bool,
+    pub auto_submit: bool,
+}
+
+impl SettingsDraft {
+    pub fn from_config(config: &TbguiConfig) -> Self {
+        let text = |value: &Option<String>| value.clone().unwrap_or_default();
+        let values = HashMap::from([
+            (SettingsField::Username, text(&config.username)),
+            (SettingsField::Host, text(&config.host)),
+            (SettingsField::RemoteRawDir, text(&config.remote_raw_dir)),
+            (
+                SettingsField::TbProfilerScript,
+                text(&config.tb_profiler_script),
+            ),
+            (SettingsField::RemoteOutDir, text(&config.remote_out_dir)),
+            (
+                SettingsField::DefaultTemplateRemote,
+                text(&config.default_template_remote),
+            ),
+            (
+                SettingsField::UserTemplateRemote,
+                text(&config.user_template_remote),
+            ),
+            (
+                SettingsField::RemoteTemplateDir,
+                text(&config.remote_template_dir),
+            ),
+            (
+                SettingsField::RemoteBatchesFile,
+                text(&config.remote_batches_file),
+            ),
+            (
+                SettingsField::RemoteProfileFile,
+                text(&config.remote_profile_file),
+            ),
+            (
+                SettingsField::RemoteTrashDir,
+                text(&config.remote_trash_dir),
+            ),
+            (
+                SettingsField::RemoteArchiveDir,
+                text(&config.remote_archive_dir),
+            ),
+            (
+                SettingsField::RemoteQuotaCommand,
+                text(&config.remote_quota_command),
+            ),
+            (
+                SettingsField::LocalDownloadDir,
+                text(&config.local_download_dir),
+            ),
+            (
+                SettingsField::TransferConcurrency,
+                config.transfer_concurrency.to_string(),
+            ),
+            (
+                SettingsField::RetentionDays,
+                config.retention_days.to_string(),
+            ),
+            (
+                SettingsField::EstimatedOutputPerSample,
+                (config.estimated_output_per_sample / MIB).to_string(),
+            ),
+            (
+                SettingsField::WatchInterval,
+                config.watch_interval_minutes.to_string(),
+            ),
+        ]);
+        Self {
+            values,
+            backend: config.backend,
+            scheduler: config.scheduler,
+            default_page: config.default_page,
+            verify_checksums: config.verify_checksums,
+            watch_raw_dir: config.watch_raw_dir,
+            auto_submit: config.auto_submit,
+        }
+    }
+
+    pub fn value(&self, field: SettingsField) -> &str {
+        self.values
+            .get(&field)
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+
+    pub fn set(&mut self, field: SettingsField, value: String) {
+        self.values.insert(field, value);
+    }
+
+    /// Why the current value of `field` can not be saved, if it can not.
+    pub fn error(&self, field: SettingsField) -> Option<String> {
+        let value = self.value(field).trim();
+        if value.is_empty() {
+            return (!field.optional()).then(|| fl!("setting-required"));
+        }
+        match field {
+            SettingsField::Username | SettingsField::Host => value
+                .contains(char::is_whitespace)
+                .then(|| fl!("setting-no-spaces")),
+            SettingsField::RemoteQuotaCommand => None,
+            SettingsField::TransferConcurrency => match value.parse::<usize>() {
+                Ok(1..=32) => None,
+                _ => Some(fl!("setting-range", min = 1, max = 32)),
+            },
+            SettingsField::WatchInterval => match value.parse::<u32>() {
+                Ok(1..=1440) => None,
+                _ => Some(fl!("setting-range", min = 1, max = 1440)),
+            },
+            SettingsField::RetentionDays => {
+                value.parse::<u32>().err().map(|_| fl!("setting-number"))
+            }
+            SettingsField::EstimatedOutputPerSample => {
+                value.parse::<u64>().err().map(|_| fl!("setting-number"))
+            }
+            SettingsField::LocalDownloadDir => {
+                (!Path::new(value).is_absolute()).then(|| fl!("setting-absolute-path"))
+            }
+            _ => (!value.starts_with('/')).then(|| fl!("setting-absolute-path")),
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        SettingsField::connection()
+            .iter()
+            .chain(SettingsField::remote_paths())
+            .chain(SettingsField::other())
+            .all(|&field| self.error(field).is_none())
+    }
+
+    /// `config` with the draft applied, the draft has to be valid.
+    pub fn apply(&self, config: &TbguiConfig) -> TbguiConfig {
+        let text = |field| Some(self.value(field).trim().to_string());
+        let optional = |field| text(field).filter(|value| !value.is_empty());
+        let number =
+            |field: SettingsField| -> u64 { self.value(field).trim().parse().unwrap_or_default() };
+        TbguiConfig {
+            username: text(SettingsField::Username),
+            host: text(SettingsField::Host),
+            backend: self.backend,
+            scheduler: self.scheduler,
+            remote_raw_dir: text(SettingsField::RemoteRawDir),
+            tb_profiler_script: text(SettingsField::TbProfilerScript),
+            remote_out_dir: text(SettingsField::RemoteOutDir),
+            default_template_remote: text(SettingsField::DefaultTemplateRemote),
+            user_template_remote: text(SettingsField::UserTemplateRemote),
+            remote_template_dir: text(SettingsField::RemoteTemplateDir),
+            remote_batches_file: text(SettingsField::RemoteBatchesFile),
+            remote_profile_file: text(SettingsField::RemoteProfileFile),
+            remote_trash_dir: text(SettingsField::RemoteTrashDir),
+            remote_archive_dir: text(SettingsField::RemoteArchiveDir),
+            remote_quota_command: optional(SettingsField::RemoteQuotaCommand),
+            local_download_dir: optional(SettingsField::LocalDownloadDir),
+            transfer_concurrency: number(SettingsField::TransferConcurrency) as usize,
+            retention_days: number(SettingsField::RetentionDays) as u32,
+            estimated_output_per_sample: number(SettingsField::EstimatedOutputPerSample)
+                .saturating_mul(MIB),
+            default_page: self.default_page,
+            verify_checksums: self.verify_checksums,
+            watch_raw_dir: self.watch_raw_dir,
+            watch_interval_minutes: number(SettingsField::WatchInterval) as u32,
+            auto_submit: self.auto_submit,
+            ..config.clone()
+        }
+    }
+
+    /// Remote paths to check with the connection test.
+    pub fn remote_paths(&self) -> Vec<(SettingsField, PathKind, String)> {
+        SettingsField::remote_paths()
+            .iter()
+            .filter_map(|&field| {
+                let kind = field.path_kind()?;
+                Some((field, kind, self.value(field).trim().to_string()))
+            })
+            .collect()
+    }
+}
+
+/// Where the remote paths live in a project directory set up like the
+/// default one.
+pub fn project_layout(root: &str) -> Vec<(SettingsField, String)> {
+    let root = root.trim_end_matches('/');
+    [
+        (SettingsField::RemoteRawDir, "raw"),
+        (SettingsField::TbProfilerScript, "scripts/tbprofiler.sh"),
+        (SettingsField::RemoteOutDir, "out"),
+        (
+            SettingsField::DefaultTemplateRemote,
+            "tb-profiler-templates/docx/default_template.docx",
+        ),
+        (
+            SettingsField::UserTemplateRemote,
+            "template/user_template.docx",
+        ),
+        (SettingsField::RemoteTemplateDir, "template"),
+        (SettingsField::RemoteBatchesFile, "tbgui/batches.json"),
+        (SettingsField::RemoteProfileFile, "tbgui/profile.toml"),
+        (SettingsField::RemoteTrashDir, "trash"),
+        (SettingsField::RemoteArchiveDir, "archive"),
+    ]
+    .into_iter()
+    .map(|(field, path)| (field, format!("{root}/{path}")))
+    .collect()
+}
+
+const MIB: u64 = 1024 * 1024;
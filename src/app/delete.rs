@@ -0,0 +1,73 @@
+use super::backend::{Backend, RemoteBackend};
+use super::transfer::RemoteFile;
+use super::types::AppError;
+use super::utils::shell_quote;
+use std::collections::BTreeMap;
+
+/// Number of files passed to a single `mv` invocation.
+const MOVE_BATCH: usize = 100;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeleteSummary {
+    pub files: usize,
+    pub bytes: u64,
+    /// Files left in place because the trash already held a file of the same name.
+    pub skipped: usize,
+    /// Trash directory the files were moved to, `None` if they were removed permanently.
+    pub trash_dir: Option<String>,
+}
+
+/// Dated trash directory for one delete operation, e.g. `<trash>/2025-04-01_153000`.
+pub fn dated_trash_dir(remote_trash_dir: &str) -> String {
+    format!(
+        "{}/{}",
+        remote_trash_dir.trim_end_matches('/'),
+        chrono::Local::now().format("%Y-%m-%d_%H%M%S")
+    )
+}
+
+/// Moves `files` into `trash_dir`, keeping the `results`, `bam` and `vcf` sub-directories.
+/// Files are never overwritten in the trash; returns those that were moved.
+pub async fn move_to_trash<'a>(
+    backend: &Backend,
+    files: &'a [RemoteFile],
+    trash_dir: &str,
+) -> Result<Vec<&'a RemoteFile>, AppError> {
+    let mut by_subdir: BTreeMap<&str, Vec<&RemoteFile>> = BTreeMap::new();
+    for file in files {
+        by_subdir.entry(&file.subdir).or_default().push(file);
+    }
+    let mut moved = Vec::new();
+    for (subdir, files) in by_subdir {
+        let destination = shell_quote(&format!("{}/{}", trash_dir, subdir));
+        for batch in files.chunks(MOVE_BATCH) {
+            let sources: Vec<String> = batch.iter().map(|file| shell_quote(&file.path)).collect();
+            // `mv -n` skips existing files, and depending on the version without
+            // failing, so list the sources that are still in place.
+            let command = format!(
+                "mkdir -p {destination} && {{ mv -n -- {sources} {destination}/; for f in {sources}; do if [ -e \"$f\" ]; then echo \"$f\"; fi; done; }}",
+                sources = sources.join(" ")
+            );
+            let output = backend
+                .exec(&command)
+                .await?
+                .check(format!("Failed to move results to {trash_dir}"))?;
+            let remaining: Vec<&str> = output.stdout.lines().collect();
+            moved.extend(
+                batch
+                    .iter()
+                    .copied()
+                    .filter(|file| !remaining.contains(&file.path.as_str())),
+            );
+        }
+    }
+    Ok(moved)
+}
+
+/// Removes `files` permanently.
+pub async fn remove_files(backend: &Backend, files: &[RemoteFile]) -> Result<(), AppError> {
+    for file in files {
+        backend.remove(&file.path).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use super::backend::{Backend, RemoteBackend};
+use super::types::AppError;
+use super::utils::shell_quote;
+use std::path::Path;
+
+/// Size and free space of a file system in bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DiskSpace {
+    pub total: u64,
+    pub available: u64,
+}
+
+/// Usage and limit of a quota in bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Quota {
+    pub used: u64,
+    pub limit: u64,
+}
+
+impl Quota {
+    pub fn available(&self) -> u64 {
+        self.limit.saturating_sub(self.used)
+    }
+}
+
+/// Free space for `remote_out_dir` and the local download directory.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageStatus {
+    pub remote: Option<DiskSpace>,
+    pub quota: Option<Quota>,
+    pub local: Option<DiskSpace>,
+}
+
+impl StorageStatus {
+    /// Space left on the remote, limited by the file system and the quota.
+    pub fn remote_available(&self) -> Option<u64> {
+        let quota = self.quota.as_ref().map(Quota::available);
+        match (self.remote.map(|space| space.available), quota) {
+            (Some(free), Some(quota)) => Some(free.min(quota)),
+            (free, quota) => free.or(quota),
+        }
+    }
+
+    pub fn local_available(&self) -> Option<u64> {
+        self.local.map(|space| space.available)
+    }
+}
+
+/// Parses the output of `df -Pk`, which reports sizes in KiB.
+pub fn parse_df(output: &str) -> Option<DiskSpace> {
+    let fields: Vec<&str> = output.lines().nth(1)?.split_whitespace().collect();
+    let total: u64 = fields.get(1)?.parse().ok()?;
+    let available: u64 = fields.get(3)?.parse().ok()?;
+    Some(DiskSpace {
+        total: total * 1024,
+        available: available * 1024,
+    })
+}
+
+/// Parses the output of a quota command, which must print the used bytes
+/// and the limit in bytes as the first two numbers.
+pub fn parse_quota(output: &str) -> Option<Quota> {
+    let mut numbers = output
+        .split_whitespace()
+        .filter_map(|word| word.parse::<u64>().ok());
+    Some(Quota {
+        used: numbers.next()?,
+        limit: numbers.next()?,
+    })
+}
+
+/// Free space of the file system holding `path`, or of its closest existing ancestor.
+pub async fn remote_disk_space(backend: &Backend, path: &str) -> Result<DiskSpace, AppError> {
+    let mut existing = path;
+    while !backend.exists(existing).await? {
+        match existing.rsplit_once('/') {
+            Some(("", _)) => existing = "/",
+            Some((parent, _)) => existing = parent,
+            None => break,
+        }
+    }
+    let command = format!("df -Pk {}", shell_quote(existing));
+    let result = backend
+        .exec(&command)
+        .await?
+        .check(format!("Failed to query free space of {path}"))?;
+    parse_df(&result.stdout)
+        .ok_or_else(|| AppError::network(format!("Unexpected df output: {}", result.stdout)))
+}
+
+/// Runs the configured quota command; `{dir}` is replaced with the quoted directory.
+/// A command that prints nothing reports that no quota applies.
+pub async fn remote_quota(
+    backend: &Backend,
+    command: &str,
+    path: &str,
+) -> Result<Option<Quota>, AppError> {
+    let command = command.replace("{dir}", &shell_quote(path));
+    let result = backend
+        .exec(&command)
+        .await?
+        .check("Quota command failed")?;
+    if result.stdout.trim().is_empty() {
+        return Ok(None);
+    }
+    parse_quota(&result.stdout).map(Some).ok_or_else(|| {
+        AppError::network(format!(
+            "Unexpected quota command output: {}",
+            result.stdout
+        ))
+    })
+}
+
+/// Free space of the file system holding `path`, or of its closest existing ancestor.
+pub async fn local_disk_space(path: &Path) -> Result<DiskSpace, AppError> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let existing = path.ancestors().find(|dir| dir.exists()).unwrap_or(&path);
+        Ok(DiskSpace {
+            total: fs2::total_space(existing)?,
+            available: fs2::available_space(existing)?,
+        })
+    })
+    .await
+    .map_err(|e| AppError::io(format!("Failed to query free space: {e}")))?
+}
@@ -0,0 +1,123 @@
+use super::report::template_variables;
+use super::types::AppError;
+use std::collections::BTreeSet;
+use std::io::Read;
+use std::path::Path;
+
+const DOCUMENT_CONTENT_TYPE: &str =
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml";
+
+/// Fields of the tb-profiler result that templates can refer to.
+pub const KNOWN_FIELDS: &[&str] = &[
+    "id",
+    "timestamp",
+    "tbprofiler_version",
+    "pipeline",
+    "main_lineage",
+    "sub_lineage",
+    "lineage",
+    "drtype",
+    "dr_variants",
+    "other_variants",
+    "qc",
+    "notes",
+    "date",
+];
+
+/// Fields every report is expected to show.
+pub const REQUIRED_FIELDS: &[&str] = &["id", "drtype"];
+
+/// Placeholders found in a template and what is wrong with them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DocxReport {
+    pub placeholders: BTreeSet<String>,
+    pub unknown: Vec<String>,
+    pub missing: Vec<String>,
+}
+
+impl DocxReport {
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings: Vec<String> = self
+            .unknown
+            .iter()
+            .map(|name| format!("Unknown placeholder: {name}"))
+            .collect();
+        warnings.extend(
+            self.missing
+                .iter()
+                .map(|name| format!("Missing placeholder: {name}")),
+        );
+        warnings
+    }
+}
+
+fn read_entry<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> Result<String, AppError> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|_| AppError::io(format!("Not a valid DOCX file: {name} is missing")))?;
+    let mut content = String::new();
+    entry
+        .read_to_string(&mut content)
+        .map_err(|e| AppError::io(format!("Failed to read {name}: {e}")))?;
+    Ok(content)
+}
+
+/// Opens `path` as an OOXML package and checks that it is a Word document.
+/// Returns the placeholders of the document, headers and footers, with
+/// unknown and missing ones. Files that are not DOCX or whose tags the
+/// report renderer cannot parse are refused.
+pub fn validate_docx(path: &Path) -> Result<DocxReport, AppError> {
+    if !path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("docx"))
+    {
+        return Err(AppError::io(format!("{path:?} is not a .docx file")));
+    }
+    let file = std::fs::File::open(path)?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| AppError::io(format!("{path:?} is not a valid DOCX file: {e}")))?;
+
+    let content_types = read_entry(&mut archive, "[Content_Types].xml")?;
+    if !content_types.contains(DOCUMENT_CONTENT_TYPE) {
+        return Err(AppError::io(format!(
+            "{path:?} is not a Word document (macro-enabled documents and templates are not supported)"
+        )));
+    }
+
+    let mut parts = vec!["word/document.xml".to_string()];
+    parts.extend(
+        archive
+            .file_names()
+            .filter(|name| {
+                (name.starts_with("word/header") || name.starts_with("word/footer"))
+                    && name.ends_with(".xml")
+            })
+            .map(str::to_string),
+    );
+    let mut placeholders = BTreeSet::new();
+    for part in parts {
+        let xml = read_entry(&mut archive, &part)?;
+        let variables = template_variables(&xml)
+            .map_err(|e| AppError::io(format!("Invalid placeholder in {part}: {e}")))?;
+        placeholders.extend(variables);
+    }
+
+    let unknown = placeholders
+        .iter()
+        .filter(|name| !KNOWN_FIELDS.contains(&name.as_str()))
+        .cloned()
+        .collect();
+    let missing = REQUIRED_FIELDS
+        .iter()
+        .filter(|name| !placeholders.contains(**name))
+        .map(|name| name.to_string())
+        .collect();
+    Ok(DocxReport {
+        placeholders,
+        unknown,
+        missing,
+    })
+}
@@ -0,0 +1,74 @@
+use super::types::AppError;
+use rust_xlsxwriter::{Format, Workbook};
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+pub fn write_table(
+    path: &Path,
+    format: ExportFormat,
+    headers: &[String],
+    records: &[Vec<String>],
+) -> Result<(), AppError> {
+    match format {
+        ExportFormat::Csv => write_csv(path, headers, records),
+        ExportFormat::Xlsx => write_xlsx(path, headers, records),
+    }
+}
+
+pub fn write_csv(path: &Path, headers: &[String], records: &[Vec<String>]) -> Result<(), AppError> {
+    let mut writer = csv::Writer::from_path(path)
+        .map_err(|e| AppError::io(format!("Failed to create {path:?}: {e}")))?;
+    writer
+        .write_record(headers)
+        .map_err(|e| AppError::io(format!("Failed to write CSV: {e}")))?;
+    for record in records {
+        writer
+            .write_record(record)
+            .map_err(|e| AppError::io(format!("Failed to write CSV: {e}")))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn write_xlsx(
+    path: &Path,
+    headers: &[String],
+    records: &[Vec<String>],
+) -> Result<(), AppError> {
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    let bold = Format::new().set_bold();
+    let xlsx_error =
+        |e: rust_xlsxwriter::XlsxError| AppError::io(format!("Failed to write XLSX: {e}"));
+
+    for (col, header) in headers.iter().enumerate() {
+        worksheet
+            .write_string_with_format(0, col as u16, header, &bold)
+            .map_err(xlsx_error)?;
+    }
+    for (row, record) in records.iter().enumerate() {
+        for (col, value) in record.iter().enumerate() {
+            worksheet
+                .write_string(row as u32 + 1, col as u16, value)
+                .map_err(xlsx_error)?;
+        }
+    }
+    worksheet.set_freeze_panes(1, 1).map_err(xlsx_error)?;
+    worksheet.autofit();
+    workbook.save(path).map_err(xlsx_error)?;
+    Ok(())
+}
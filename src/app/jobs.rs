@@ -0,0 +1,437 @@
+use super::config::SchedulerKind;
+use super::utils::shell_quote;
+use crate::fl;
+use serde::Serialize;
+
+/// Name runs are submitted under.
+const JOB_NAME: &str = "tbprofiler";
+
+/// Output format passed to `squeue -o`, one `|` separated line per job.
+const SQUEUE_FORMAT: &str = "%i|%j|%T|%M|%r";
+
+/// Output format passed to `bjobs -o`.
+const BJOBS_FORMAT: &str = "jobid job_name stat run_time pend_reason delimiter='|'";
+
+/// Tasks run at the same time without a scheduler, tb-profiler uses
+/// several cores for each sample itself.
+const BACKGROUND_TASKS: usize = 2;
+
+/// `$0` of the shell running background tasks, to find it with `ps`.
+const BACKGROUND_MARKER: &str = "tbgui-tbprofiler";
+
+/// Variable holding the 0-based index of the task, see [`Scheduler`].
+const TASK_INDEX: &str = "TBGUI_TASK_INDEX";
+
+/// Prints the script with `$PRELUDE` inserted before its first command,
+/// after the shebang and the scheduler directives.
+const INSERT_PRELUDE: &str = "inserted || /^#/ || /^[ \t]*$/ { print; next } \
+{ print ENVIRON[\"PRELUDE\"]; inserted = 1; print } \
+END { if (!inserted) print ENVIRON[\"PRELUDE\"] }";
+
+/// A job of the user in the scheduler queue.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct Job {
+    pub id: String,
+    pub name: String,
+    /// `PENDING`, `RUNNING` or `HELD` where the scheduler's own state maps to
+    /// one of them, the scheduler's state otherwise.
+    pub state: String,
+    /// Time the job has been running, as printed by the scheduler.
+    pub time: String,
+    /// Why the job is pending, Slurm prints `None` for running jobs.
+    pub reason: String,
+}
+
+/// A batch system runs are submitted to. Drivers build the commands and
+/// parse their output, running them is left to the backend.
+///
+/// Each sample is one task of an array job. Whatever the scheduler, the
+/// script finds the index of its task from 0 in `TBGUI_TASK_INDEX`. The
+/// drivers submit the script on standard input with the arguments and
+/// that variable set at its top, so the scheduler still reads the
+/// directives in its header.
+pub trait Scheduler: Sync {
+    /// Command submitting `script` with `args` as an array job of `tasks`
+    /// tasks, writing the output of each task to a file in `log_dir`.
+    fn submit(&self, script: &str, args: &[&str], tasks: usize, log_dir: &str) -> String;
+
+    /// The job id in the output of the submit command.
+    fn submitted_id<'a>(&self, output: &'a str) -> Option<&'a str>;
+
+    /// Command listing the unfinished jobs of `user`.
+    fn list(&self, user: &str) -> String;
+
+    fn parse_jobs(&self, output: &str) -> Vec<Job>;
+
+    fn cancel(&self, job: &str) -> String;
+
+    /// Whether the file `name` in the log directory is output of `job`.
+    fn is_log_of(&self, name: &str, job: &str) -> bool;
+
+    /// Whether `id` in the output of [`Scheduler::list`] is `job` or one of its tasks.
+    fn is_task_of(&self, id: &str, job: &str) -> bool {
+        id == job
+    }
+}
+
+pub struct Slurm;
+pub struct Pbs;
+pub struct Sge;
+pub struct Lsf;
+/// Runs the tasks with `nohup` and `xargs -P` on the server itself.
+pub struct Background;
+
+impl SchedulerKind {
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::Slurm,
+            Self::Pbs,
+            Self::Sge,
+            Self::Lsf,
+            Self::Background,
+        ]
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::Slurm => "Slurm".to_string(),
+            Self::Pbs => "PBS Pro".to_string(),
+            Self::Sge => "Sun Grid Engine".to_string(),
+            Self::Lsf => "LSF".to_string(),
+            Self::Background => fl!("scheduler-background"),
+        }
+    }
+
+    pub fn driver(&self) -> &'static dyn Scheduler {
+        match self {
+            Self::Slurm => &Slurm,
+            Self::Pbs => &Pbs,
+            Self::Sge => &Sge,
+            Self::Lsf => &Lsf,
+            Self::Background => &Background,
+        }
+    }
+}
+
+impl Scheduler for Slurm {
+    fn submit(&self, script: &str, args: &[&str], tasks: usize, log_dir: &str) -> String {
+        format!(
+            "{} | sbatch --array 0-{} --job-name={JOB_NAME} --output={}",
+            indexed_script(script, args, "$SLURM_ARRAY_TASK_ID"),
+            tasks.saturating_sub(1),
+            shell_quote(&format!("{log_dir}/%A_%a.log"))
+        )
+    }
+
+    fn submitted_id<'a>(&self, output: &'a str) -> Option<&'a str> {
+        output
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("Submitted batch job "))
+            .map(str::trim)
+    }
+
+    fn list(&self, user: &str) -> String {
+        format!(
+            "squeue -h -u {} -o {}",
+            shell_quote(user),
+            shell_quote(SQUEUE_FORMAT)
+        )
+    }
+
+    fn parse_jobs(&self, output: &str) -> Vec<Job> {
+        parse_delimited(output)
+    }
+
+    fn cancel(&self, job: &str) -> String {
+        format!("scancel {}", shell_quote(job))
+    }
+
+    fn is_log_of(&self, name: &str, job: &str) -> bool {
+        name.starts_with(&format!("{job}_"))
+    }
+
+    /// Tasks of an array job are listed as `<id>_<task>`.
+    fn is_task_of(&self, id: &str, job: &str) -> bool {
+        id == job || id.starts_with(&format!("{job}_"))
+    }
+}
+
+impl Scheduler for Pbs {
+    fn submit(&self, script: &str, args: &[&str], tasks: usize, log_dir: &str) -> String {
+        // PBS rejects arrays of a single task, that task has no index.
+        let array = if tasks > 1 {
+            format!(" -J 0-{}", tasks - 1)
+        } else {
+            String::new()
+        };
+        format!(
+            "{} | qsub -N {JOB_NAME} -j oe -o {}{array}",
+            indexed_script(script, args, "${PBS_ARRAY_INDEX:-0}"),
+            shell_quote(&format!("{log_dir}/"))
+        )
+    }
+
+    fn submitted_id<'a>(&self, output: &'a str) -> Option<&'a str> {
+        output.lines().map(str::trim).find(|line| !line.is_empty())
+    }
+
+    fn list(&self, user: &str) -> String {
+        format!("qstat -u {}", shell_quote(user))
+    }
+
+    /// Parses the table of `qstat -u`:
+    /// `Job ID Username Queue Jobname SessID NDS TSK Memory Time S Time`.
+    fn parse_jobs(&self, output: &str) -> Vec<Job> {
+        parse_table(output, |fields| {
+            Some(Job {
+                id: fields.first()?.to_string(),
+                name: fields.get(3)?.to_string(),
+                state: match *fields.get(9)? {
+                    "Q" | "W" => "PENDING".to_string(),
+                    "R" | "B" | "E" => "RUNNING".to_string(),
+                    "H" => "HELD".to_string(),
+                    state => state.to_string(),
+                },
+                time: fields.get(10)?.to_string(),
+                reason: String::new(),
+            })
+        })
+    }
+
+    fn cancel(&self, job: &str) -> String {
+        format!("qdel {}", shell_quote(job))
+    }
+
+    /// Logs are named `<name>.o<sequence number>[.<index>]`.
+    fn is_log_of(&self, name: &str, job: &str) -> bool {
+        is_grid_log(name, pbs_sequence(job))
+    }
+
+    /// Ids are `<sequence number>[<index>].<server>`, with an empty index for
+    /// the array job itself, and may be cut short by `qstat`.
+    fn is_task_of(&self, id: &str, job: &str) -> bool {
+        pbs_sequence(id) == pbs_sequence(job)
+    }
+}
+
+impl Scheduler for Sge {
+    fn submit(&self, script: &str, args: &[&str], tasks: usize, log_dir: &str) -> String {
+        format!(
+            "{} | qsub -N {JOB_NAME} -j y -o {} -t 1-{}",
+            indexed_script(script, args, "$((SGE_TASK_ID - 1))"),
+            shell_quote(log_dir),
+            tasks.max(1)
+        )
+    }
+
+    /// `Your job-array 1234.1-3:1 ("tbprofiler") has been submitted`.
+    fn submitted_id<'a>(&self, output: &'a str) -> Option<&'a str> {
+        output.lines().find_map(|line| {
+            let rest = line.trim().strip_prefix("Your job")?;
+            let rest = rest.strip_prefix("-array").unwrap_or(rest).trim_start();
+            rest.split(['.', ' ']).next().filter(|id| !id.is_empty())
+        })
+    }
+
+    fn list(&self, user: &str) -> String {
+        format!("qstat -u {}", shell_quote(user))
+    }
+
+    /// Parses the table of `qstat -u`:
+    /// `job-ID prior name user state submit/start at queue slots ja-task-ID`.
+    fn parse_jobs(&self, output: &str) -> Vec<Job> {
+        parse_table(output, |fields| {
+            let state = *fields.get(4)?;
+            Some(Job {
+                id: fields.first()?.to_string(),
+                name: fields.get(2)?.to_string(),
+                state: if state.starts_with('h') {
+                    "HELD".to_string()
+                } else if state.contains('q') {
+                    "PENDING".to_string()
+                } else if state.contains('r') || state.contains('t') {
+                    "RUNNING".to_string()
+                } else {
+                    state.to_string()
+                },
+                time: format!("{} {}", fields.get(5)?, fields.get(6)?),
+                reason: String::new(),
+            })
+        })
+    }
+
+    fn cancel(&self, job: &str) -> String {
+        format!("qdel {}", shell_quote(job))
+    }
+
+    /// Logs are named `<name>.o<job id>[.<task id>]`.
+    fn is_log_of(&self, name: &str, job: &str) -> bool {
+        is_grid_log(name, job)
+    }
+}
+
+impl Scheduler for Lsf {
+    fn submit(&self, script: &str, args: &[&str], tasks: usize, log_dir: &str) -> String {
+        format!(
+            "{} | bsub -J {} -o {}",
+            indexed_script(script, args, "$((LSB_JOBINDEX - 1))"),
+            shell_quote(&format!("{JOB_NAME}[1-{}]", tasks.max(1))),
+            shell_quote(&format!("{log_dir}/%J_%I.log"))
+        )
+    }
+
+    /// `Job <1234> is submitted to default queue <normal>.`
+    fn submitted_id<'a>(&self, output: &'a str) -> Option<&'a str> {
+        output.lines().find_map(|line| {
+            let rest = line.trim().strip_prefix("Job <")?;
+            rest.split('>').next()
+        })
+    }
+
+    fn list(&self, user: &str) -> String {
+        format!(
+            "bjobs -noheader -u {} -o {}",
+            shell_quote(user),
+            shell_quote(BJOBS_FORMAT)
+        )
+    }
+
+    fn parse_jobs(&self, output: &str) -> Vec<Job> {
+        parse_delimited(output)
+            .into_iter()
+            .map(|job| Job {
+                state: match job.state.as_str() {
+                    "PEND" => "PENDING".to_string(),
+                    "RUN" => "RUNNING".to_string(),
+                    "PSUSP" => "HELD".to_string(),
+                    _ => job.state,
+                },
+                ..job
+            })
+            .collect()
+    }
+
+    fn cancel(&self, job: &str) -> String {
+        format!("bkill {}", shell_quote(job))
+    }
+
+    fn is_log_of(&self, name: &str, job: &str) -> bool {
+        name.starts_with(&format!("{job}_"))
+    }
+}
+
+impl Scheduler for Background {
+    /// Starts a shell in its own session, so that cancelling can stop the
+    /// whole process group, and prints its process id as the job id.
+    fn submit(&self, script: &str, args: &[&str], tasks: usize, log_dir: &str) -> String {
+        let run = format!(
+            "log_dir=$1; shift; seq 0 {} | xargs -P {BACKGROUND_TASKS} -I % \
+             env {TASK_INDEX}=% sh -c '\"$@\" > \"$0\" 2>&1' \"$log_dir/$$_%.log\" \"$@\"",
+            tasks.saturating_sub(1)
+        );
+        format!(
+            "nohup setsid sh -c {} {BACKGROUND_MARKER} {} {} > /dev/null 2>&1 & \
+             echo \"Started background job $!\"",
+            shell_quote(&run),
+            shell_quote(log_dir),
+            command_line(script, args)
+        )
+    }
+
+    fn submitted_id<'a>(&self, output: &'a str) -> Option<&'a str> {
+        output
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("Started background job "))
+            .map(str::trim)
+    }
+
+    fn list(&self, user: &str) -> String {
+        format!("ps -u {} -o pid=,etime=,args=", shell_quote(user))
+    }
+
+    fn parse_jobs(&self, output: &str) -> Vec<Job> {
+        output
+            .lines()
+            .filter(|line| line.contains(BACKGROUND_MARKER))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                Some(Job {
+                    id: fields.next()?.to_string(),
+                    name: JOB_NAME.to_string(),
+                    state: "RUNNING".to_string(),
+                    time: fields.next()?.to_string(),
+                    reason: String::new(),
+                })
+            })
+            .collect()
+    }
+
+    fn cancel(&self, job: &str) -> String {
+        format!("kill -- -{}", shell_quote(job))
+    }
+
+    fn is_log_of(&self, name: &str, job: &str) -> bool {
+        name.starts_with(&format!("{job}_"))
+    }
+}
+
+fn command_line(script: &str, args: &[&str]) -> String {
+    std::iter::once(script)
+        .chain(args.iter().copied())
+        .map(shell_quote)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Command printing `script` with `args` and the task index, taken from the
+/// shell expression `index`, set before its first command.
+fn indexed_script(script: &str, args: &[&str], index: &str) -> String {
+    let args: Vec<String> = args.iter().map(|arg| shell_quote(arg)).collect();
+    let prelude = format!(
+        "set -- {}\n{TASK_INDEX}={index}\nexport {TASK_INDEX}",
+        args.join(" ")
+    );
+    format!(
+        "PRELUDE={} awk {} {}",
+        shell_quote(&prelude),
+        shell_quote(INSERT_PRELUDE),
+        shell_quote(script)
+    )
+}
+
+/// Sequence number of a PBS job id `<sequence number>[<index>].<server>`.
+fn pbs_sequence(id: &str) -> &str {
+    id.split(['[', '.']).next().unwrap_or(id)
+}
+
+/// Parses `|` separated lines of id, name, state, time and reason.
+fn parse_delimited(output: &str) -> Vec<Job> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.trim().split('|').map(str::to_string);
+            Some(Job {
+                id: fields.next().filter(|id| !id.is_empty())?,
+                name: fields.next().unwrap_or_default(),
+                state: fields.next().unwrap_or_default(),
+                time: fields.next().unwrap_or_default(),
+                reason: fields.next().unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Parses the rows below the `---` line of a `qstat` table.
+fn parse_table(output: &str, row: impl Fn(&[&str]) -> Option<Job>) -> Vec<Job> {
+    output
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("---"))
+        .skip(1)
+        .filter_map(|line| row(&line.split_whitespace().collect::<Vec<_>>()))
+        .collect()
+}
+
+fn is_grid_log(name: &str, job: &str) -> bool {
+    let prefix = format!("{JOB_NAME}.o{job}");
+    name == prefix || name.starts_with(&format!("{prefix}."))
+}
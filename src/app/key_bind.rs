@@ -25,7 +25,9 @@ pub fn key_binds() -> HashMap<KeyBind, Action> {
 
     bind!([Ctrl], Key::Character("w".into()), WindowClose);
     bind!([Ctrl, Shift], Key::Character("n".into()), WindowNew);
+    bind!([Ctrl], Key::Character("n".into()), NewList);
     bind!([Ctrl], Key::Character(",".into()), Settings);
+    bind!([Ctrl], Key::Character("r".into()), Refresh);
     bind!([Ctrl], Key::Character("i".into()), About);
 
     key_binds
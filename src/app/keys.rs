@@ -0,0 +1,54 @@
+use super::types::AppError;
+use directories_next::UserDirs;
+use std::path::{Path, PathBuf};
+
+/// Key generated by the setup assistant when the user has none.
+const GENERATED_KEY_NAME: &str = "id_ed25519_tbgui";
+
+fn ssh_dir() -> Result<PathBuf, AppError> {
+    UserDirs::new()
+        .map(|dirs| dirs.home_dir().join(".ssh"))
+        .ok_or_else(|| AppError::io("Failed to find the home directory".to_string()))
+}
+
+/// Private keys in `~/.ssh` that have a public key next to them.
+pub fn find_ssh_keys() -> Vec<PathBuf> {
+    let Ok(entries) = ssh_dir().and_then(|dir| Ok(std::fs::read_dir(dir)?)) else {
+        return Vec::new();
+    };
+    let mut keys: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_none() && public_key_path(path).is_file())
+        .collect();
+    keys.sort();
+    keys
+}
+
+pub fn public_key_path(key: &Path) -> PathBuf {
+    let mut path = key.as_os_str().to_owned();
+    path.push(".pub");
+    PathBuf::from(path)
+}
+
+/// Generates an ed25519 key without passphrase with `ssh-keygen`.
+pub async fn generate_ssh_key() -> Result<PathBuf, AppError> {
+    let dir = ssh_dir()?;
+    tokio::fs::create_dir_all(&dir).await?;
+    let key = dir.join(GENERATED_KEY_NAME);
+    if key.exists() {
+        return Err(AppError::io(format!("{key:?} already exists")));
+    }
+    let output = tokio::process::Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-C", "tbgui", "-f"])
+        .arg(&key)
+        .output()
+        .await
+        .map_err(|e| AppError::io(format!("Failed to run ssh-keygen: {e}")))?;
+    if !output.status.success() {
+        return Err(AppError::io(format!(
+            "ssh-keygen failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(key)
+}
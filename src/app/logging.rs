@@ -0,0 +1,100 @@
+use directories_next::BaseDirs;
+use std::path::{Path, PathBuf};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter};
+
+const LOG_FILE_PREFIX: &str = "tbgui";
+const LOG_FILE_SUFFIX: &str = "log";
+/// Daily log files kept before the oldest is removed.
+const MAX_LOG_FILES: usize = 14;
+
+/// `$XDG_STATE_HOME/tbgui/logs`, `~/.local/state/tbgui/logs` if unset. Other
+/// platforms have no state directory and use the local data directory.
+pub fn log_dir() -> PathBuf {
+    let state_home = std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute());
+    let base = match state_home {
+        Some(dir) => dir,
+        None => match BaseDirs::new() {
+            Some(dirs) if cfg!(target_os = "linux") => dirs.home_dir().join(".local/state"),
+            Some(dirs) => dirs.data_local_dir().to_path_buf(),
+            None => std::env::temp_dir(),
+        },
+    };
+    base.join("tbgui").join("logs")
+}
+
+/// Sets up logging to a daily rotated file in [`log_dir`] and, if `to_stderr`,
+/// to stderr. The returned guard flushes the file when dropped and has to live
+/// as long as the app. Without a writable log directory only stderr is used.
+pub fn init(to_stderr: bool) -> Option<WorkerGuard> {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn,tbgui=info"));
+    let stderr = to_stderr.then(|| fmt::layer().with_writer(std::io::stderr));
+
+    let appender = std::fs::create_dir_all(log_dir())
+        .map_err(|err| err.to_string())
+        .and_then(|_| {
+            RollingFileAppender::builder()
+                .rotation(Rotation::DAILY)
+                .filename_prefix(LOG_FILE_PREFIX)
+                .filename_suffix(LOG_FILE_SUFFIX)
+                .max_log_files(MAX_LOG_FILES)
+                .build(log_dir())
+                .map_err(|err| err.to_string())
+        });
+    match appender {
+        Ok(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let file = fmt::layer().with_ansi(false).with_writer(writer);
+            tracing_subscriber::registry()
+                .with(filter)
+                .with(stderr)
+                .with(file)
+                .init();
+            Some(guard)
+        }
+        Err(err) => {
+            tracing_subscriber::registry()
+                .with(filter)
+                .with(stderr)
+                .init();
+            tracing::warn!("logging to stderr only, log directory unavailable: {err}");
+            None
+        }
+    }
+}
+
+/// The most recent log file in `dir`.
+fn latest_log_file(dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX))
+        })
+        .max_by_key(|path| path.metadata().and_then(|meta| meta.modified()).ok())
+}
+
+/// The last `lines` lines of the current log file.
+pub async fn read_log(lines: usize) -> String {
+    let Some(path) = latest_log_file(&log_dir()) else {
+        return String::new();
+    };
+    match tokio::fs::read_to_string(&path).await {
+        Ok(log) => {
+            let all: Vec<&str> = log.lines().collect();
+            all[all.len().saturating_sub(lines)..].join("\n")
+        }
+        Err(err) => {
+            tracing::warn!("failed to read {path:?}: {err}");
+            String::new()
+        }
+    }
+}
@@ -22,6 +22,27 @@ pub fn menu_bar<'a>(key_binds: &HashMap<KeyBind, Action>) -> Element<'a, Message
                         Some(icons::get_handle("tabs-stack-symbolic", 14)),
                         Action::WindowNew,
                     ),
+                    Item::Button(
+                        fl!("new-list"),
+                        Some(icons::get_handle("list-add-symbolic", 14)),
+                        Action::NewList,
+                    ),
+                    Item::Button(
+                        fl!("delete-list"),
+                        Some(icons::get_handle("user-trash-full-symbolic", 14)),
+                        Action::DeleteList,
+                    ),
+                    Item::Divider,
+                    Item::Button(
+                        fl!("fetch-lists"),
+                        Some(icons::get_handle("document-open-symbolic", 14)),
+                        Action::FetchLists,
+                    ),
+                    Item::Button(
+                        fl!("push-lists"),
+                        Some(icons::get_handle("document-send-symbolic", 14)),
+                        Action::PushLists,
+                    ),
                     Item::Divider,
                     Item::Button(
                         fl!("quit"),
@@ -36,6 +57,12 @@ pub fn menu_bar<'a>(key_binds: &HashMap<KeyBind, Action>) -> Element<'a, Message
             items(
                 key_binds,
                 vec![
+                    Item::Button(
+                        fl!("refresh"),
+                        Some(icons::get_handle("view-refresh-symbolic", 14)),
+                        Action::Refresh,
+                    ),
+                    Item::Divider,
                     Item::Button(
                         fl!("menu-settings"),
                         Some(icons::get_handle("settings-symbolic", 14)),
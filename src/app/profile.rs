@@ -0,0 +1,158 @@
+use super::config::{SchedulerKind, TbguiConfig};
+use super::types::AppError;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The group part of the configuration that can be shared as a file. The
+/// username, the SSH key and local preferences stay personal.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Profile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheduler: Option<SchedulerKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_raw_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tb_profiler_script: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_out_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_template_remote: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_template_remote: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_template_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_batches_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_trash_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_archive_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_profile_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_quota_command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_submit: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retention_days: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_output_per_sample: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProfileFormat {
+    Toml,
+    Json,
+}
+
+impl ProfileFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ProfileFormat::Toml => "toml",
+            ProfileFormat::Json => "json",
+        }
+    }
+
+    /// Format of `path` by its extension, TOML unless it ends in `.json`.
+    pub fn of(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => ProfileFormat::Json,
+            _ => ProfileFormat::Toml,
+        }
+    }
+}
+
+impl Profile {
+    pub fn from_config(config: &TbguiConfig) -> Self {
+        Self {
+            host: config.host.clone(),
+            scheduler: Some(config.scheduler),
+            remote_raw_dir: config.remote_raw_dir.clone(),
+            tb_profiler_script: config.tb_profiler_script.clone(),
+            remote_out_dir: config.remote_out_dir.clone(),
+            default_template_remote: config.default_template_remote.clone(),
+            user_template_remote: config.user_template_remote.clone(),
+            remote_template_dir: config.remote_template_dir.clone(),
+            remote_batches_file: config.remote_batches_file.clone(),
+            remote_trash_dir: config.remote_trash_dir.clone(),
+            remote_archive_dir: config.remote_archive_dir.clone(),
+            remote_profile_file: config.remote_profile_file.clone(),
+            remote_quota_command: config.remote_quota_command.clone(),
+            auto_submit: Some(config.auto_submit),
+            retention_days: Some(config.retention_days),
+            estimated_output_per_sample: Some(config.estimated_output_per_sample),
+        }
+    }
+
+    /// `config` with every value the profile sets replaced.
+    pub fn apply(&self, config: &TbguiConfig) -> TbguiConfig {
+        let mut config = config.clone();
+        let fields = [
+            (&self.host, &mut config.host),
+            (&self.remote_raw_dir, &mut config.remote_raw_dir),
+            (&self.tb_profiler_script, &mut config.tb_profiler_script),
+            (&self.remote_out_dir, &mut config.remote_out_dir),
+            (
+                &self.default_template_remote,
+                &mut config.default_template_remote,
+            ),
+            (&self.user_template_remote, &mut config.user_template_remote),
+            (&self.remote_template_dir, &mut config.remote_template_dir),
+            (&self.remote_batches_file, &mut config.remote_batches_file),
+            (&self.remote_trash_dir, &mut config.remote_trash_dir),
+            (&self.remote_archive_dir, &mut config.remote_archive_dir),
+            (&self.remote_profile_file, &mut config.remote_profile_file),
+            (&self.remote_quota_command, &mut config.remote_quota_command),
+        ];
+        for (value, field) in fields {
+            if value.is_some() {
+                field.clone_from(value);
+            }
+        }
+        if let Some(scheduler) = self.scheduler {
+            config.scheduler = scheduler;
+        }
+        if let Some(auto_submit) = self.auto_submit {
+            config.auto_submit = auto_submit;
+        }
+        if let Some(days) = self.retention_days {
+            config.retention_days = days;
+        }
+        if let Some(size) = self.estimated_output_per_sample {
+            config.estimated_output_per_sample = size;
+        }
+        config
+    }
+
+    pub fn parse(data: &str, format: ProfileFormat) -> Result<Self, AppError> {
+        match format {
+            ProfileFormat::Toml => toml::from_str(data)
+                .map_err(|e| AppError::config_missing(format!("Failed to parse profile: {e}"))),
+            ProfileFormat::Json => serde_json::from_str(data)
+                .map_err(|e| AppError::config_missing(format!("Failed to parse profile: {e}"))),
+        }
+    }
+
+    pub fn to_string(&self, format: ProfileFormat) -> Result<String, AppError> {
+        match format {
+            ProfileFormat::Toml => toml::to_string_pretty(self)
+                .map_err(|e| AppError::io(format!("Failed to serialize profile: {e}"))),
+            ProfileFormat::Json => serde_json::to_string_pretty(self)
+                .map_err(|e| AppError::io(format!("Failed to serialize profile: {e}"))),
+        }
+    }
+
+    pub async fn read(path: &Path) -> Result<Self, AppError> {
+        let data = tokio::fs::read_to_string(path).await?;
+        Self::parse(&data, ProfileFormat::of(path))
+    }
+
+    pub async fn write(&self, path: &Path) -> Result<(), AppError> {
+        let data = self.to_string(ProfileFormat::of(path))?;
+        tokio::fs::write(path, data).await?;
+        Ok(())
+    }
+}
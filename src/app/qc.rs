@@ -0,0 +1,88 @@
+use super::backend::{Backend, RemoteBackend};
+use super::types::AppError;
+use super::utils::shell_quote;
+
+/// Outcome of the pre-flight check of one sample's FASTQ files.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FastqCheck {
+    pub sample: String,
+    /// Reason the sample failed, `None` if both reads look intact.
+    pub failure: Option<String>,
+}
+
+impl FastqCheck {
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// Shell script that prints `<file> <ok|corrupt> <lines>` for every read file of a sample.
+/// Each file is decompressed once: the exit status of `gzip` follows the data on
+/// a line of its own, so a failed decompression reports the file as corrupt.
+pub fn check_command(remote_raw_dir: &str, sample: &str) -> String {
+    format!(
+        r#"cd {dir} || exit 1
+for f in {sample}_*.fastq.gz {sample}_*.fq.gz; do
+  [ -e "$f" ] || continue
+  echo "$f $({{ gzip -dc -- "$f" 2>/dev/null; printf '\n%s\n' "$?"; }} | awk '{{ last = $0 }} END {{ print (last == "0" ? "ok" : "corrupt"), NR - 2 }}')"
+done"#,
+        dir = shell_quote(remote_raw_dir),
+        sample = shell_quote(sample),
+    )
+}
+
+/// Evaluates the output of `check_command`. Both reads must be intact, hold
+/// complete four-line records and contain the same number of reads.
+pub fn evaluate(sample: &str, output: &str) -> FastqCheck {
+    let mut files = Vec::new();
+    for line in output.lines() {
+        let mut fields = line.rsplitn(3, ' ');
+        let (Some(lines), Some(status), Some(name)) = (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        files.push((
+            name,
+            status,
+            lines.trim().parse::<u64>().unwrap_or_default(),
+        ));
+    }
+
+    let failure = if files.len() != 2 {
+        Some(format!("expected 2 read files, found {}", files.len()))
+    } else if let Some((name, _, _)) = files.iter().find(|(_, status, _)| *status != "ok") {
+        Some(format!("{name} is corrupt or truncated"))
+    } else if let Some((name, _, _)) = files.iter().find(|(_, _, lines)| lines % 4 != 0) {
+        Some(format!("{name} ends with an incomplete record"))
+    } else if files[0].2 != files[1].2 {
+        Some(format!(
+            "read counts differ: {} has {}, {} has {}",
+            files[0].0,
+            files[0].2 / 4,
+            files[1].0,
+            files[1].2 / 4
+        ))
+    } else if files[0].2 == 0 {
+        Some("read files are empty".to_string())
+    } else {
+        None
+    };
+    FastqCheck {
+        sample: sample.to_string(),
+        failure,
+    }
+}
+
+/// Runs the pre-flight check of `sample` directly on the login node.
+pub async fn check_sample(
+    backend: &Backend,
+    remote_raw_dir: &str,
+    sample: &str,
+) -> Result<FastqCheck, AppError> {
+    let command = check_command(remote_raw_dir, sample);
+    let result = backend
+        .exec(&command)
+        .await?
+        .check(format!("Failed to check reads of {sample}"))?;
+    Ok(evaluate(sample, &result.stdout))
+}
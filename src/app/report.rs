@@ -0,0 +1,1013 @@
+use super::types::AppError;
+use super::utils::sample_of;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Renders `template` with the values of a tb-profiler `results.json` and
+/// writes the report to `output`. Tags follow the docxtpl dialect of Jinja
+/// that the remote report step uses: `{{ }}` variables with filters,
+/// `{% if %}`, `{% for %}` and `{% set %}`, and the `p`, `tr`, `tc` and `r`
+/// prefixes that let a tag replace its paragraph, table row, cell or run.
+pub fn render_report(template: &Path, results: &Value, output: &Path) -> Result<(), AppError> {
+    let mut context = match results {
+        Value::Object(map) => map.clone(),
+        _ => return Err(AppError::io("Results must be a JSON object".to_string())),
+    };
+    context
+        .entry("date")
+        .or_insert_with(|| Value::String(chrono::Local::now().format("%Y-%m-%d").to_string()));
+
+    let file = std::fs::File::open(template)?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| AppError::io(format!("{template:?} is not a valid DOCX file: {e}")))?;
+    let mut writer = ZipWriter::new(std::fs::File::create(output)?);
+    let zip_error = |e: zip::result::ZipError| AppError::io(format!("Failed to write report: {e}"));
+
+    for index in 0..archive.len() {
+        let name = archive
+            .by_index_raw(index)
+            .map_err(zip_error)?
+            .name()
+            .to_string();
+        if !is_story_part(&name) {
+            let entry = archive.by_index_raw(index).map_err(zip_error)?;
+            writer.raw_copy_file(entry).map_err(zip_error)?;
+            continue;
+        }
+        let mut xml = String::new();
+        archive
+            .by_index(index)
+            .map_err(zip_error)?
+            .read_to_string(&mut xml)?;
+        let rendered = render_xml(&xml, &context)
+            .map_err(|e| AppError::io(format!("Failed to render {name}: {e}")))?;
+        writer
+            .start_file(
+                name,
+                SimpleFileOptions::default().compression_method(CompressionMethod::Deflated),
+            )
+            .map_err(zip_error)?;
+        writer.write_all(rendered.as_bytes())?;
+    }
+    writer.finish().map_err(zip_error)?;
+    Ok(())
+}
+
+/// Renders a report next to each of `results_files` with `template`, named
+/// `<sample>.report.docx`, and optionally converts them to PDF.
+pub async fn render_reports(
+    template: PathBuf,
+    results_files: Vec<PathBuf>,
+    pdf: bool,
+) -> Result<Vec<PathBuf>, AppError> {
+    let mut reports = Vec::new();
+    for results_file in results_files {
+        let template = template.clone();
+        let report = tokio::task::spawn_blocking(move || {
+            let data = std::fs::read(&results_file)?;
+            let results: Value = serde_json::from_slice(&data)
+                .map_err(|e| AppError::io(format!("Failed to parse {results_file:?}: {e}")))?;
+            // The id names the report, unless it would leave the results directory.
+            let sample = results
+                .get("id")
+                .and_then(Value::as_str)
+                .filter(|id| is_file_name(id))
+                .map(str::to_string)
+                .unwrap_or_else(|| {
+                    let name = results_file
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    sample_of(&name).to_string()
+                });
+            let output = results_file.with_file_name(format!("{sample}.report.docx"));
+            render_report(&template, &results, &output)?;
+            Ok::<PathBuf, AppError>(output)
+        })
+        .await
+        .map_err(|e| AppError::io(format!("Failed to render report: {e}")))??;
+        if pdf {
+            reports.push(export_pdf(&report).await?);
+        }
+        reports.push(report);
+    }
+    Ok(reports)
+}
+
+/// Whether `name` can be used as is as a single, visible file name.
+fn is_file_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\', '\0'])
+}
+
+/// Converts `docx` to PDF next to it with LibreOffice and returns the PDF path.
+pub async fn export_pdf(docx: &Path) -> Result<PathBuf, AppError> {
+    let out_dir = docx.parent().unwrap_or(Path::new("."));
+    let output = tokio::process::Command::new("soffice")
+        .arg("--headless")
+        .arg("--convert-to")
+        .arg("pdf")
+        .arg("--outdir")
+        .arg(out_dir)
+        .arg(docx)
+        .output()
+        .await
+        .map_err(|e| AppError::io(format!("Failed to run LibreOffice (soffice): {e}")))?;
+    if !output.status.success() {
+        return Err(AppError::io(format!(
+            "PDF export failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(docx.with_extension("pdf"))
+}
+
+fn is_story_part(name: &str) -> bool {
+    name == "word/document.xml"
+        || ((name.starts_with("word/header") || name.starts_with("word/footer"))
+            && name.ends_with(".xml"))
+}
+
+/// Renders the Jinja tags of a Word XML part.
+pub fn render_xml(xml: &str, context: &Map<String, Value>) -> Result<String, String> {
+    let xml = clean_tags(&join_delimiters(xml));
+    let xml = expand_prefixed_tags(&xml)?;
+    let nodes = parse_template(&xml)?;
+    let mut scopes = vec![context.clone()];
+    let mut output = String::with_capacity(xml.len());
+    render_nodes(&nodes, &mut scopes, &mut output)?;
+    Ok(output)
+}
+
+/// Variables a Word XML part reads from the results, leaving out those the
+/// template defines itself with `for` and `set`.
+pub fn template_variables(xml: &str) -> Result<BTreeSet<String>, String> {
+    let xml = clean_tags(&join_delimiters(xml));
+    let nodes = parse_template(&expand_prefixed_tags(&xml)?)?;
+    let mut used = BTreeSet::new();
+    let mut defined = BTreeSet::from(["loop".to_string()]);
+    collect_variables(&nodes, &mut used, &mut defined);
+    used.retain(|name| !defined.contains(name));
+    Ok(used)
+}
+
+fn collect_variables(nodes: &[Node], used: &mut BTreeSet<String>, defined: &mut BTreeSet<String>) {
+    for node in nodes {
+        match node {
+            Node::Text(_) => {}
+            Node::Output(expr) => expr.variables(used),
+            Node::If(branches, otherwise) => {
+                for (condition, body) in branches {
+                    condition.variables(used);
+                    collect_variables(body, used, defined);
+                }
+                collect_variables(otherwise, used, defined);
+            }
+            Node::For(targets, iterable, body) => {
+                defined.extend(targets.iter().cloned());
+                iterable.variables(used);
+                collect_variables(body, used, defined);
+            }
+            Node::Set(target, value) => {
+                defined.insert(target.clone());
+                value.variables(used);
+            }
+        }
+    }
+}
+
+/// Length of the XML tags starting at `text`, zero if it does not start with one.
+fn tags_len(text: &str) -> usize {
+    let mut len = 0;
+    while text[len..].starts_with('<') {
+        match text[len..].find('>') {
+            Some(end) => len += end + 1,
+            None => break,
+        }
+    }
+    len
+}
+
+/// Word often splits `{{` or `%}` across runs; removes the XML between the two characters.
+fn join_delimiters(xml: &str) -> String {
+    let mut output = String::with_capacity(xml.len());
+    let mut rest = xml;
+    while let Some(c) = rest.chars().next() {
+        output.push(c);
+        rest = &rest[c.len_utf8()..];
+        let second: &[char] = match c {
+            '{' => &['{', '%', '#'],
+            '%' | '}' | '#' => &['}'],
+            _ => continue,
+        };
+        let skip = tags_len(rest);
+        if skip > 0 && rest[skip..].starts_with(second) {
+            rest = &rest[skip..];
+        }
+    }
+    output
+}
+
+/// Removes the XML inside tags and undoes the escaping and smart quotes Word applies.
+fn clean_tags(xml: &str) -> String {
+    let mut output = String::with_capacity(xml.len());
+    let mut rest = xml;
+    while let Some(start) = find_tag_start(rest) {
+        output.push_str(&rest[..start]);
+        let close = closing_delimiter(&rest[start..]);
+        let Some(end) = rest[start + 2..].find(close) else {
+            rest = &rest[start..];
+            break;
+        };
+        let end = start + 2 + end + 2;
+        let mut tag = String::new();
+        let mut inner = &rest[start..end];
+        while !inner.is_empty() {
+            let skip = tags_len(inner);
+            if skip > 0 {
+                inner = &inner[skip..];
+                continue;
+            }
+            let c = inner.chars().next().unwrap_or_default();
+            tag.push(c);
+            inner = &inner[c.len_utf8()..];
+        }
+        output.push_str(
+            &tag.replace(['\u{201c}', '\u{201d}'], "\"")
+                .replace(['\u{2018}', '\u{2019}'], "'")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&amp;", "&"),
+        );
+        rest = &rest[end..];
+    }
+    output.push_str(rest);
+    output
+}
+
+fn find_tag_start(text: &str) -> Option<usize> {
+    text.match_indices('{')
+        .map(|(index, _)| index)
+        .find(|&index| matches!(text[index + 1..].chars().next(), Some('{' | '%' | '#')))
+}
+
+fn closing_delimiter(tag: &str) -> &'static str {
+    match tag.as_bytes().get(1) {
+        Some(b'{') => "}}",
+        Some(b'#') => "#}",
+        _ => "%}",
+    }
+}
+
+/// Index of the opening XML element `name` that encloses `index`.
+fn find_open(xml: &str, index: usize, name: &str) -> Option<usize> {
+    let mut search = &xml[..index];
+    loop {
+        let start = search.rfind(name)?;
+        let after = xml[start + name.len()..].chars().next();
+        if matches!(after, Some('>' | ' ')) {
+            return Some(start);
+        }
+        search = &search[..start];
+    }
+}
+
+/// Replaces the paragraph, table row, cell or run around `{%p`, `{%tr`, `{%tc`
+/// and `{%r` tags with the plain tag.
+fn expand_prefixed_tags(xml: &str) -> Result<String, String> {
+    let mut xml = xml.to_string();
+    for (prefix, element) in [("p", "w:p"), ("tr", "w:tr"), ("tc", "w:tc"), ("r", "w:r")] {
+        let marker = format!("{{%{prefix} ");
+        while let Some(start) = xml.find(&marker) {
+            let end = xml[start..]
+                .find("%}")
+                .map(|end| start + end + 2)
+                .ok_or_else(|| format!("unterminated tag {marker}"))?;
+            let tag = format!("{{% {}", &xml[start + marker.len()..end]);
+            let open = find_open(&xml, start, &format!("<{element}"))
+                .ok_or_else(|| format!("{marker} is not inside a {element} element"))?;
+            let close_tag = format!("</{element}>");
+            let close = xml[end..]
+                .find(&close_tag)
+                .map(|close| end + close + close_tag.len())
+                .ok_or_else(|| format!("{marker} is not inside a {element} element"))?;
+            xml.replace_range(open..close, &tag);
+        }
+    }
+    Ok(xml)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Text(String),
+    Output(Expr),
+    If(Vec<(Expr, Vec<Node>)>, Vec<Node>),
+    For(Vec<String>, Expr, Vec<Node>),
+    Set(String, Expr),
+}
+
+enum Tag<'a> {
+    Text(&'a str),
+    Output(&'a str),
+    Statement(&'a str),
+}
+
+fn split_tags(text: &str) -> Result<Vec<Tag<'_>>, String> {
+    let mut tags = Vec::new();
+    let mut rest = text;
+    while let Some(start) = find_tag_start(rest) {
+        if start > 0 {
+            tags.push(Tag::Text(&rest[..start]));
+        }
+        let close = closing_delimiter(&rest[start..]);
+        let end = rest[start + 2..]
+            .find(close)
+            .map(|end| start + 2 + end)
+            .ok_or_else(|| format!("unterminated tag near {:?}", &rest[start..]))?;
+        let inner = rest[start + 2..end]
+            .trim_start_matches('-')
+            .trim_end_matches('-')
+            .trim();
+        match close {
+            "}}" => tags.push(Tag::Output(inner)),
+            "%}" => tags.push(Tag::Statement(inner)),
+            _ => {}
+        }
+        rest = &rest[end + 2..];
+    }
+    if !rest.is_empty() {
+        tags.push(Tag::Text(rest));
+    }
+    Ok(tags)
+}
+
+fn parse_template(text: &str) -> Result<Vec<Node>, String> {
+    let tags = split_tags(text)?;
+    let mut position = 0;
+    let (nodes, end) = parse_block(&tags, &mut position)?;
+    match end {
+        None => Ok(nodes),
+        Some(tag) => Err(format!("unexpected {{% {tag} %}}")),
+    }
+}
+
+/// Parses nodes until an `elif`, `else`, `endif` or `endfor` tag, which is returned.
+fn parse_block<'a>(
+    tags: &[Tag<'a>],
+    position: &mut usize,
+) -> Result<(Vec<Node>, Option<&'a str>), String> {
+    let mut nodes = Vec::new();
+    while let Some(tag) = tags.get(*position) {
+        *position += 1;
+        match tag {
+            Tag::Text(text) => nodes.push(Node::Text(text.to_string())),
+            Tag::Output(expression) => nodes.push(Node::Output(parse_expression(expression)?)),
+            Tag::Statement(statement) => {
+                let (keyword, rest) = statement.split_once(' ').unwrap_or((statement, ""));
+                match keyword {
+                    "if" => {
+                        let mut branches = Vec::new();
+                        let mut condition = parse_expression(rest)?;
+                        loop {
+                            let (body, end) = parse_block(tags, position)?;
+                            branches.push((condition, body));
+                            match end.map(|end| end.split_once(' ').unwrap_or((end, ""))) {
+                                Some(("elif", expression)) => {
+                                    condition = parse_expression(expression)?
+                                }
+                                Some(("else", _)) => {
+                                    let (otherwise, end) = parse_block(tags, position)?;
+                                    if end != Some("endif") {
+                                        return Err("expected {% endif %}".to_string());
+                                    }
+                                    nodes.push(Node::If(branches, otherwise));
+                                    break;
+                                }
+                                Some(("endif", _)) => {
+                                    nodes.push(Node::If(branches, Vec::new()));
+                                    break;
+                                }
+                                _ => return Err("expected {% endif %}".to_string()),
+                            }
+                        }
+                    }
+                    "for" => {
+                        let (targets, iterable) = rest
+                            .split_once(" in ")
+                            .ok_or_else(|| format!("invalid for loop: {statement}"))?;
+                        let targets = targets.split(',').map(|t| t.trim().to_string()).collect();
+                        let (body, end) = parse_block(tags, position)?;
+                        if end != Some("endfor") {
+                            return Err("expected {% endfor %}".to_string());
+                        }
+                        nodes.push(Node::For(targets, parse_expression(iterable)?, body));
+                    }
+                    "set" => {
+                        let (target, value) = rest
+                            .split_once('=')
+                            .ok_or_else(|| format!("invalid set: {statement}"))?;
+                        nodes.push(Node::Set(
+                            target.trim().to_string(),
+                            parse_expression(value)?,
+                        ));
+                    }
+                    "elif" | "else" | "endif" | "endfor" => return Ok((nodes, Some(statement))),
+                    _ => return Err(format!("unsupported tag {{% {statement} %}}")),
+                }
+            }
+        }
+    }
+    Ok((nodes, None))
+}
+
+fn lookup(scopes: &[Map<String, Value>], name: &str) -> Value {
+    scopes
+        .iter()
+        .rev()
+        .find_map(|scope| scope.get(name))
+        .cloned()
+        .unwrap_or(Value::Null)
+}
+
+fn render_nodes(
+    nodes: &[Node],
+    scopes: &mut Vec<Map<String, Value>>,
+    output: &mut String,
+) -> Result<(), String> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Output(expression) => {
+                output.push_str(&escape_xml(&display(&expression.eval(scopes)?)))
+            }
+            Node::If(branches, otherwise) => {
+                let mut body = otherwise;
+                for (condition, branch) in branches {
+                    if truthy(&condition.eval(scopes)?) {
+                        body = branch;
+                        break;
+                    }
+                }
+                render_nodes(body, scopes, output)?;
+            }
+            Node::For(targets, iterable, body) => {
+                let items: Vec<Value> = match iterable.eval(scopes)? {
+                    Value::Array(items) => items,
+                    Value::Object(map) => map.keys().cloned().map(Value::String).collect(),
+                    Value::Null => Vec::new(),
+                    other => return Err(format!("cannot loop over {other}")),
+                };
+                let length = items.len();
+                for (index, item) in items.into_iter().enumerate() {
+                    let mut scope = Map::new();
+                    match (targets.as_slice(), item) {
+                        ([target], item) => {
+                            scope.insert(target.clone(), item);
+                        }
+                        (targets, Value::Array(values)) => {
+                            for (target, value) in targets.iter().zip(values) {
+                                scope.insert(target.clone(), value);
+                            }
+                        }
+                        _ => return Err("cannot unpack loop item".to_string()),
+                    }
+                    scope.insert(
+                        "loop".to_string(),
+                        serde_json::json!({
+                            "index": index + 1,
+                            "index0": index,
+                            "first": index == 0,
+                            "last": index + 1 == length,
+                            "length": length,
+                        }),
+                    );
+                    scopes.push(scope);
+                    let result = render_nodes(body, scopes, output);
+                    scopes.pop();
+                    result?;
+                }
+            }
+            Node::Set(target, value) => {
+                let value = value.eval(scopes)?;
+                if let Some(scope) = scopes.last_mut() {
+                    scope.insert(target.clone(), value);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Formats a value the way Python's `str` would.
+fn display(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Bool(true) => "True".to_string(),
+        Value::Bool(false) => "False".to_string(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(value) => *value,
+        Value::Number(number) => number.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(text) => !text.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Literal(Value),
+    Symbol(&'static str),
+}
+
+const SYMBOLS: [&str; 16] = [
+    "==", "!=", "<=", ">=", "<", ">", ".", "[", "]", "(", ")", ",", "|", "~", "=", "-",
+];
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = expression.trim_start();
+    while let Some(c) = rest.chars().next() {
+        if c == '"' || c == '\'' {
+            let end = rest[1..]
+                .find(c)
+                .ok_or_else(|| format!("unterminated string in {expression}"))?;
+            tokens.push(Token::Literal(Value::String(rest[1..end + 1].to_string())));
+            rest = &rest[end + 2..];
+        } else if c.is_ascii_digit() {
+            let end = rest
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or(rest.len());
+            let number = &rest[..end];
+            let value = match number.parse::<i64>() {
+                Ok(integer) => Value::from(integer),
+                Err(_) => number
+                    .parse::<f64>()
+                    .map(Value::from)
+                    .map_err(|_| format!("invalid number in {expression}"))?,
+            };
+            tokens.push(Token::Literal(value));
+            rest = &rest[end..];
+        } else if c.is_alphabetic() || c == '_' {
+            let end = rest
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            tokens.push(match &rest[..end] {
+                "true" | "True" => Token::Literal(Value::Bool(true)),
+                "false" | "False" => Token::Literal(Value::Bool(false)),
+                "none" | "None" => Token::Literal(Value::Null),
+                word => Token::Ident(word.to_string()),
+            });
+            rest = &rest[end..];
+        } else {
+            let symbol = SYMBOLS
+                .iter()
+                .find(|symbol| rest.starts_with(**symbol))
+                .ok_or_else(|| format!("unexpected {c:?} in {expression}"))?;
+            tokens.push(Token::Symbol(symbol));
+            rest = &rest[symbol.len()..];
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+    Literal(Value),
+    Variable(String),
+    Attribute(Box<Expr>, String),
+    Index(Box<Expr>, Box<Expr>),
+    Method(Box<Expr>, String),
+    Filter(Box<Expr>, String, Vec<Expr>),
+    List(Vec<Expr>),
+    Not(Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+    /// `is` test such as `defined` or `none`, negated by `is not`.
+    Test(Box<Expr>, String, bool),
+}
+
+fn parse_expression(expression: &str) -> Result<Expr, String> {
+    let tokens = tokenize(expression)?;
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+    };
+    let expr = parser.or()?;
+    match parser.tokens.get(parser.position) {
+        None => Ok(expr),
+        Some(token) => Err(format!("unexpected {token:?} in {expression}")),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(ident)) if ident == word) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), String> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(format!("expected {symbol}"))
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.eat_word("or") {
+            left = Expr::Binary("or", Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.not()?;
+        while self.eat_word("and") {
+            left = Expr::Binary("and", Box::new(left), Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        if self.eat_word("not") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let left = self.concat()?;
+        if self.eat_word("is") {
+            let negated = self.eat_word("not");
+            let test = match self.next() {
+                Some(Token::Ident(test)) => test,
+                Some(Token::Literal(Value::Null)) => "none".to_string(),
+                _ => return Err("expected test name".to_string()),
+            };
+            return Ok(Expr::Test(Box::new(left), test, negated));
+        }
+        let (operator, len) = match (self.peek(), self.tokens.get(self.position + 1)) {
+            (Some(Token::Symbol(symbol @ ("==" | "!=" | "<" | ">" | "<=" | ">="))), _) => {
+                (*symbol, 1)
+            }
+            (Some(Token::Ident(word)), _) if word == "in" => ("in", 1),
+            (Some(Token::Ident(word)), Some(Token::Ident(next)))
+                if word == "not" && next == "in" =>
+            {
+                ("not in", 2)
+            }
+            _ => return Ok(left),
+        };
+        self.position += len;
+        let right = self.concat()?;
+        Ok(Expr::Binary(operator, Box::new(left), Box::new(right)))
+    }
+
+    fn concat(&mut self) -> Result<Expr, String> {
+        let mut left = self.postfix()?;
+        while self.eat_symbol("~") {
+            left = Expr::Binary("~", Box::new(left), Box::new(self.postfix()?));
+        }
+        Ok(left)
+    }
+
+    fn postfix(&mut self) -> Result<Expr, String> {
+        let mut expr = self.primary()?;
+        loop {
+            if self.eat_symbol(".") {
+                let Some(Token::Ident(name)) = self.next() else {
+                    return Err("expected attribute name".to_string());
+                };
+                if self.eat_symbol("(") {
+                    self.expect_symbol(")")?;
+                    expr = Expr::Method(Box::new(expr), name);
+                } else {
+                    expr = Expr::Attribute(Box::new(expr), name);
+                }
+            } else if self.eat_symbol("[") {
+                let index = self.or()?;
+                self.expect_symbol("]")?;
+                expr = Expr::Index(Box::new(expr), Box::new(index));
+            } else if self.eat_symbol("|") {
+                let Some(Token::Ident(name)) = self.next() else {
+                    return Err("expected filter name".to_string());
+                };
+                let mut args = Vec::new();
+                if self.eat_symbol("(") {
+                    while !self.eat_symbol(")") {
+                        args.push(self.or()?);
+                        self.eat_symbol(",");
+                    }
+                }
+                expr = Expr::Filter(Box::new(expr), name, args);
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Literal(value)) => Ok(Expr::Literal(value)),
+            Some(Token::Ident(name)) => Ok(Expr::Variable(name)),
+            Some(Token::Symbol("-")) => match self.next() {
+                Some(Token::Literal(Value::Number(number))) => {
+                    let negated = match number.as_i64() {
+                        Some(integer) => Value::from(-integer),
+                        None => Value::from(-number.as_f64().unwrap_or_default()),
+                    };
+                    Ok(Expr::Literal(negated))
+                }
+                _ => Err("expected a number after -".to_string()),
+            },
+            Some(Token::Symbol("(")) => {
+                let expr = self.or()?;
+                self.expect_symbol(")")?;
+                Ok(expr)
+            }
+            Some(Token::Symbol("[")) => {
+                let mut items = Vec::new();
+                while !self.eat_symbol("]") {
+                    items.push(self.or()?);
+                    self.eat_symbol(",");
+                }
+                Ok(Expr::List(items))
+            }
+            token => Err(format!("unexpected {token:?}")),
+        }
+    }
+}
+
+fn compare(left: &Value, right: &Value) -> Option<std::cmp::Ordering> {
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+fn values_equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        _ => left == right,
+    }
+}
+
+impl Expr {
+    /// Adds the variables the expression reads to `variables`.
+    fn variables(&self, variables: &mut BTreeSet<String>) {
+        match self {
+            Expr::Literal(_) => {}
+            Expr::Variable(name) => {
+                variables.insert(name.clone());
+            }
+            Expr::Attribute(expr, _)
+            | Expr::Method(expr, _)
+            | Expr::Not(expr)
+            | Expr::Test(expr, _, _) => expr.variables(variables),
+            Expr::Index(expr, index) => {
+                expr.variables(variables);
+                index.variables(variables);
+            }
+            Expr::Filter(expr, _, args) => {
+                expr.variables(variables);
+                args.iter().for_each(|arg| arg.variables(variables));
+            }
+            Expr::List(items) => items.iter().for_each(|item| item.variables(variables)),
+            Expr::Binary(_, left, right) => {
+                left.variables(variables);
+                right.variables(variables);
+            }
+        }
+    }
+
+    fn eval(&self, scopes: &[Map<String, Value>]) -> Result<Value, String> {
+        Ok(match self {
+            Expr::Literal(value) => value.clone(),
+            Expr::Variable(name) => lookup(scopes, name),
+            Expr::Attribute(expr, name) => match expr.eval(scopes)? {
+                Value::Object(map) => map.get(name).cloned().unwrap_or(Value::Null),
+                _ => Value::Null,
+            },
+            Expr::Index(expr, index) => match (expr.eval(scopes)?, index.eval(scopes)?) {
+                (Value::Object(map), Value::String(key)) => {
+                    map.get(&key).cloned().unwrap_or(Value::Null)
+                }
+                (Value::Array(items), Value::Number(index)) => {
+                    let index = index
+                        .as_i64()
+                        .ok_or_else(|| format!("list index must be an integer, not {index}"))?;
+                    // Negative indices count from the end, like in Python.
+                    let index = if index < 0 {
+                        i64::try_from(items.len()).unwrap_or(i64::MAX) + index
+                    } else {
+                        index
+                    };
+                    usize::try_from(index)
+                        .ok()
+                        .and_then(|index| items.get(index).cloned())
+                        .unwrap_or(Value::Null)
+                }
+                _ => Value::Null,
+            },
+            Expr::Method(expr, name) => {
+                let Value::Object(map) = expr.eval(scopes)? else {
+                    return Ok(Value::Array(Vec::new()));
+                };
+                match name.as_str() {
+                    "items" => Value::Array(
+                        map.into_iter()
+                            .map(|(key, value)| Value::Array(vec![Value::String(key), value]))
+                            .collect(),
+                    ),
+                    "keys" => Value::Array(map.keys().cloned().map(Value::String).collect()),
+                    "values" => Value::Array(map.into_iter().map(|(_, value)| value).collect()),
+                    _ => return Err(format!("unsupported method {name}()")),
+                }
+            }
+            Expr::Filter(expr, name, args) => {
+                let value = expr.eval(scopes)?;
+                let args = args
+                    .iter()
+                    .map(|arg| arg.eval(scopes))
+                    .collect::<Result<Vec<_>, _>>()?;
+                apply_filter(value, name, &args)?
+            }
+            Expr::List(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| item.eval(scopes))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Expr::Not(expr) => Value::Bool(!truthy(&expr.eval(scopes)?)),
+            Expr::Test(expr, test, negated) => {
+                // Missing values evaluate to null, so `defined` cannot tell them apart.
+                let value = expr.eval(scopes)?;
+                let result = match test.as_str() {
+                    "defined" => !value.is_null(),
+                    "undefined" | "none" => value.is_null(),
+                    "string" => value.is_string(),
+                    "number" => value.is_number(),
+                    "mapping" => value.is_object(),
+                    _ => return Err(format!("unsupported test {test}")),
+                };
+                Value::Bool(result != *negated)
+            }
+            Expr::Binary(operator, left, right) => {
+                let left = left.eval(scopes)?;
+                match *operator {
+                    "and" if !truthy(&left) => left,
+                    "or" if truthy(&left) => left,
+                    "and" | "or" => right.eval(scopes)?,
+                    operator => {
+                        let right = right.eval(scopes)?;
+                        match operator {
+                            "==" => Value::Bool(values_equal(&left, &right)),
+                            "!=" => Value::Bool(!values_equal(&left, &right)),
+                            "<" | ">" | "<=" | ">=" => {
+                                let ordering = compare(&left, &right);
+                                Value::Bool(matches!(
+                                    (operator, ordering),
+                                    ("<", Some(std::cmp::Ordering::Less))
+                                        | (">", Some(std::cmp::Ordering::Greater))
+                                        | (
+                                            "<=",
+                                            Some(
+                                                std::cmp::Ordering::Less
+                                                    | std::cmp::Ordering::Equal
+                                            )
+                                        )
+                                        | (
+                                            ">=",
+                                            Some(
+                                                std::cmp::Ordering::Greater
+                                                    | std::cmp::Ordering::Equal
+                                            )
+                                        )
+                                ))
+                            }
+                            "in" | "not in" => {
+                                let contained = match &right {
+                                    Value::Array(items) => {
+                                        items.iter().any(|item| values_equal(item, &left))
+                                    }
+                                    Value::Object(map) => map.contains_key(&display(&left)),
+                                    Value::String(text) => text.contains(&display(&left)),
+                                    _ => false,
+                                };
+                                Value::Bool(contained == (operator == "in"))
+                            }
+                            "~" => Value::String(display(&left) + &display(&right)),
+                            _ => return Err(format!("unsupported operator {operator}")),
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+fn apply_filter(value: Value, name: &str, args: &[Value]) -> Result<Value, String> {
+    Ok(match name {
+        "join" => {
+            let separator = args.first().map(display).unwrap_or_default();
+            match value {
+                Value::Array(items) => Value::String(
+                    items
+                        .iter()
+                        .map(display)
+                        .collect::<Vec<_>>()
+                        .join(&separator),
+                ),
+                other => Value::String(display(&other)),
+            }
+        }
+        "length" | "count" => Value::from(match &value {
+            Value::Array(items) => items.len(),
+            Value::Object(map) => map.len(),
+            Value::String(text) => text.chars().count(),
+            _ => 0,
+        }),
+        "default" | "d" => {
+            if value.is_null() {
+                args.first().cloned().unwrap_or(Value::Null)
+            } else {
+                value
+            }
+        }
+        "upper" => Value::String(display(&value).to_uppercase()),
+        "lower" => Value::String(display(&value).to_lowercase()),
+        "string" => Value::String(display(&value)),
+        "replace" => {
+            let from = args.first().map(display).unwrap_or_default();
+            let to = args.get(1).map(display).unwrap_or_default();
+            Value::String(display(&value).replace(&from, &to))
+        }
+        "round" => {
+            let digits = args.first().and_then(Value::as_f64).unwrap_or(0.0) as i32;
+            let factor = 10f64.powi(digits);
+            let rounded = value
+                .as_f64()
+                .map(|number| (number * factor).round() / factor)
+                .unwrap_or_default();
+            serde_json::json!(rounded)
+        }
+        "first" => match value {
+            Value::Array(items) => items.into_iter().next().unwrap_or(Value::Null),
+            _ => Value::Null,
+        },
+        "last" => match value {
+            Value::Array(items) => items.last().cloned().unwrap_or(Value::Null),
+            _ => Value::Null,
+        },
+        _ => return Err(format!("unsupported filter {name}")),
+    })
+}
@@ -1,190 +1,459 @@
+use super::archive::{create_archive, download_archive, ArchiveSummary};
+use super::backend::{Backend, RemoteBackend, SshBackend};
 use super::config::TbguiConfig;
-use super::types::AppError;
+use super::config_form::{project_layout, PathKind, SettingsField};
+use super::delete::{dated_trash_dir, move_to_trash, remove_files, DeleteSummary};
+use super::disk::{local_disk_space, remote_disk_space, remote_quota, StorageStatus};
+use super::jobs::Job;
+use super::keys::public_key_path;
+use super::profile::{Profile, ProfileFormat};
+use super::qc::{check_sample, FastqCheck};
+use super::templates::{list_templates, sanitize_name, upload_template, RemoteTemplate};
+use super::transfer::{
+    copy_remote_file, download_files, group_result_sets, list_remote_files, DownloadRequest,
+    ResultFileType, ResultSet, TransferOptions, TransferProgress, TransferSummary,
+};
+use super::types::{AppError, ErrorKind};
+use super::upload::{upload_fastq_files, FastqFile};
 use super::utils::*;
-use crate::{DEFAULT_TEMPLATE_FILENAME_LOCAL, RESULT_DIR_LOCAL};
-use async_ssh2_tokio::client::{AuthMethod, Client, ServerCheckMethod};
+use crate::model::profile::TbProfile;
+use crate::model::List;
+use crate::DEFAULT_TEMPLATE_FILENAME_LOCAL;
+use async_ssh2_tokio::client::{AuthMethod, ServerCheckMethod};
 use directories_next::UserDirs; // TODO: Remove this dependency
+use futures_util::{stream, StreamExt};
 use rfd::FileDialog; // TODO: Remove this dependency
-use russh_sftp::client::SftpSession;
-use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::fs::create_dir_all;
+use tokio::sync::mpsc::UnboundedSender;
 
-pub async fn create_client(config: &TbguiConfig) -> Result<Client, AppError> {
-    let key_path = UserDirs::new()
-        .unwrap()
-        .home_dir()
-        .join(".ssh")
-        .join("id_rsa");
-    if !key_path.exists() {
-        return Err(AppError::Network(format!(
-            "SSH key file not found at path: {:?}",
-            key_path
-        )));
+/// Connects with `config` and checks each of `paths` on the remote host. A
+/// missing path is a result, a check that cannot run is an error.
+pub async fn test_settings(
+    config: TbguiConfig,
+    paths: Vec<(SettingsField, PathKind, String)>,
+) -> Result<Vec<(SettingsField, bool)>, AppError> {
+    let backend = Backend::connect(&config).await?;
+    let mut results = Vec::with_capacity(paths.len());
+    for (field, kind, path) in paths {
+        let test = match kind {
+            PathKind::Dir => format!("test -d {}", shell_quote(&path)),
+            PathKind::File => format!("test -f {}", shell_quote(&path)),
+            PathKind::Parent => format!("test -d \"$(dirname {})\"", shell_quote(&path)),
+        };
+        let result = backend
+            .exec(&format!(
+                "if {test}; then echo found; else echo missing; fi"
+            ))
+            .await?
+            .check(format!("Failed to check {path}"))?;
+        results.push((field, result.stdout.trim() == "found"));
     }
-    let auth_method = AuthMethod::with_key_file(key_path, None);
-    let client = Client::connect(
-        ("130.60.24.133", 22),
-        config.username.as_deref().ok_or_else(|| {
-            AppError::Network("Username is not set in the configuration".to_string())
-        })?,
-        auth_method,
-        ServerCheckMethod::NoCheck,
+    Ok(results)
+}
+
+/// Appends the public key of `key_path` to `~/.ssh/authorized_keys` on the
+/// cluster, logging in with `password` once. The password is only sent to a
+/// host listed in `~/.ssh/known_hosts`.
+pub async fn install_public_key(
+    config: TbguiConfig,
+    password: String,
+    key_path: PathBuf,
+) -> Result<(), AppError> {
+    let public_key = tokio::fs::read_to_string(public_key_path(&key_path)).await?;
+    let public_key = public_key.trim();
+    let backend = SshBackend::connect_with(
+        &config,
+        AuthMethod::with_password(&password),
+        ServerCheckMethod::DefaultKnownHostsFile,
     )
     .await?;
-    Ok(client)
+    let command = format!(
+        "mkdir -p ~/.ssh && chmod 700 ~/.ssh && touch ~/.ssh/authorized_keys && \
+         chmod 600 ~/.ssh/authorized_keys && \
+         (grep -qxF {key} ~/.ssh/authorized_keys || echo {key} >> ~/.ssh/authorized_keys)",
+        key = shell_quote(public_key)
+    );
+    backend
+        .exec(&command)
+        .await?
+        .check("Failed to install the public key")?;
+    Ok(())
+}
+
+/// Looks for the usual project layout below `root` on the cluster and returns
+/// the paths that exist.
+pub async fn discover_project(
+    config: TbguiConfig,
+    root: String,
+) -> Result<Vec<(SettingsField, String)>, AppError> {
+    let layout = project_layout(&root);
+    let paths = layout
+        .iter()
+        .filter_map(|(field, path)| Some((*field, field.path_kind()?, path.clone())))
+        .collect();
+    let found = test_settings(config, paths).await?;
+    Ok(layout
+        .into_iter()
+        .filter(|(field, _)| found.contains(&(*field, true)))
+        .collect())
 }
 
+#[tracing::instrument(skip(backend, samples, config))]
 pub async fn run_tbprofiler(
-    client: &Client,
+    backend: &Backend,
     items_checked: usize,
     samples: String,
     config: &TbguiConfig,
 ) -> Result<String, AppError> {
     if items_checked == 0 {
-        return Err(AppError::Network(
-            "Cannot run tbprofiler with zero items checked".to_string(),
+        return Err(AppError::no_items_checked(
+            "Cannot run tbprofiler with zero items checked",
         ));
     }
-    let command_run_tbprofiler = format!(
-        "sbatch --array 0-{} {} \"{}\" {} {} {}",
-        items_checked - 1,
-        config.tb_profiler_script.as_deref().ok_or_else(|| {
-            AppError::Network("tb_profiler_script is not set in the configuration".to_string())
-        })?,
-        samples,
+    let script = config.tb_profiler_script.as_deref().ok_or_else(|| {
+        AppError::config_missing("tb_profiler_script is not set in the configuration")
+    })?;
+    let args = [
+        samples.as_str(),
         config.remote_raw_dir.as_deref().ok_or_else(|| {
-            AppError::Network(
-                "Remote rawreads directory is not set in the configuration".to_string(),
-            )
+            AppError::config_missing("Remote rawreads directory is not set in the configuration")
         })?,
         config.remote_out_dir.as_deref().ok_or_else(|| {
-            AppError::Network("Remote out directory is not set in the configuration".to_string())
+            AppError::config_missing("Remote out directory is not set in the configuration")
         })?,
-        config.user_template_remote.as_deref().ok_or_else(|| {
-            AppError::Network("Default template remote is not set in the configuration".to_string())
+        config.run_template().ok_or_else(|| {
+            AppError::config_missing("No report template is set in the configuration")
         })?,
-    );
-    let commandexecutedresult_run_tbprofiler = client.execute(&command_run_tbprofiler).await?;
-    if commandexecutedresult_run_tbprofiler.exit_status != 0 {
-        return Err(AppError::Network("Failed to run tbprofiler".to_string()));
-    }
-    Ok(commandexecutedresult_run_tbprofiler.stdout)
+    ];
+    let log_dir = remote_log_dir(config)?;
+    backend
+        .exec(&format!("mkdir -p {}", shell_quote(&log_dir)))
+        .await?
+        .check(format!("Failed to create {log_dir}"))?;
+    let command_run_tbprofiler =
+        config
+            .scheduler
+            .driver()
+            .submit(script, &args, items_checked, &log_dir);
+    let output = backend
+        .exec(&command_run_tbprofiler)
+        .await?
+        .check_as(ErrorKind::Scheduler, "Failed to submit tbprofiler")?;
+    Ok(output.stdout)
 }
 
-pub async fn download_results(client: &Client, config: &TbguiConfig) -> Result<(), AppError> {
-    let remote_out_dir = config.remote_out_dir.as_deref().ok_or_else(|| {
-        AppError::Network("Remote out directory is not set in the configuration".to_string())
-    })?;
-    let remote_dir = format!("{}/results", remote_out_dir);
+/// Lists the unfinished jobs of the configured user.
+pub async fn list_jobs(backend: &Backend, config: &TbguiConfig) -> Result<Vec<Job>, AppError> {
+    let username = config
+        .username
+        .as_deref()
+        .ok_or_else(|| AppError::config_missing("Username is not set in the configuration"))?;
+    let scheduler = config.scheduler.driver();
+    let output = backend
+        .exec(&scheduler.list(username))
+        .await?
+        .check_as(ErrorKind::Scheduler, "Failed to list jobs")?;
+    Ok(scheduler.parse_jobs(&output.stdout))
+}
 
-    let channel = client
-        .get_channel()
-        .await
-        .map_err(|e| AppError::Network(format!("Failed to open SSH channel: {e:?}")))?;
-    channel
-        .request_subsystem(true, "sftp")
-        .await
-        .map_err(|e| AppError::Network(format!("Failed to request SFTP subsystem: {e:?}")))?;
-    let sftp = SftpSession::new(channel.into_stream())
-        .await
-        .map_err(|e| AppError::Network(format!("Failed to start SFTP session: {e:?}")))?;
+pub async fn cancel_job(
+    backend: &Backend,
+    config: &TbguiConfig,
+    job: &str,
+) -> Result<(), AppError> {
+    backend
+        .exec(&config.scheduler.driver().cancel(job))
+        .await?
+        .check_as(ErrorKind::Scheduler, format!("Failed to cancel job {job}"))?;
+    Ok(())
+}
 
-    println!(
-        "Downloading results from remote directory: {:?}",
-        remote_dir
-    );
+/// The output of every task of `job`, each file preceded by its name.
+pub async fn job_logs(
+    backend: &Backend,
+    config: &TbguiConfig,
+    job: &str,
+) -> Result<String, AppError> {
+    let log_dir = remote_log_dir(config)?;
+    let scheduler = config.scheduler.driver();
+    let mut names: Vec<String> = backend
+        .read_dir(&log_dir)
+        .await?
+        .into_iter()
+        .filter(|entry| entry.metadata.is_file && scheduler.is_log_of(&entry.name, job))
+        .map(|entry| entry.name)
+        .collect();
+    names.sort();
+    let mut logs = String::new();
+    for name in names {
+        let data = backend.read(&format!("{log_dir}/{name}")).await?;
+        logs.push_str(&format!("==> {name} <==\n"));
+        logs.push_str(&String::from_utf8_lossy(&data));
+    }
+    Ok(logs)
+}
+
+fn remote_log_dir(config: &TbguiConfig) -> Result<String, AppError> {
+    config.remote_log_dir().ok_or_else(|| {
+        AppError::config_missing("Remote out directory is not set in the configuration")
+    })
+}
 
-    let default_local_dir = UserDirs::new().unwrap().home_dir().join(RESULT_DIR_LOCAL);
-    if !default_local_dir.exists() {
-        create_dir_all(&default_local_dir).await.map_err(|e| {
-            AppError::IO(format!("Failed to create default local directory: {e:?}"))
-        })?;
+/// Queries free space and quota for `remote_out_dir` and free space for the
+/// local download directory. Failures are logged and leave the entry empty.
+pub async fn storage_status(backend: &Backend, config: &TbguiConfig) -> StorageStatus {
+    let mut status = StorageStatus::default();
+    if let Some(remote_out_dir) = config.remote_out_dir.as_deref() {
+        match remote_disk_space(backend, remote_out_dir).await {
+            Ok(space) => status.remote = Some(space),
+            Err(err) => tracing::warn!("{err}"),
+        }
+        if let Some(command) = config.remote_quota_command.as_deref() {
+            match remote_quota(backend, command, remote_out_dir).await {
+                Ok(quota) => status.quota = quota,
+                Err(err) => tracing::warn!("{err}"),
+            }
+        }
+    }
+    match local_disk_space(&config.download_dir()).await {
+        Ok(space) => status.local = Some(space),
+        Err(err) => tracing::warn!("{err}"),
     }
-    let local_dir = FileDialog::new()
-        .set_title("Select directory to download results")
-        .set_directory(&default_local_dir)
-        .pick_folder()
-        .ok_or_else(|| {
-            AppError::Network("No directory selected for downloading results".to_string())
-        })?;
+    status
+}
+
+/// Lists the result sets in `remote_out_dir`, one per sample.
+pub async fn list_result_sets(
+    backend: &Backend,
+    config: &TbguiConfig,
+) -> Result<Vec<ResultSet>, AppError> {
+    let request = DownloadRequest {
+        samples: None,
+        file_types: ResultFileType::all().to_vec(),
+    };
+    let files = list_remote_files(backend, config, &request).await?;
+    Ok(group_result_sets(&files))
+}
+
+/// Downloads the result files selected by `request` from `remote_out_dir`
+/// into the configured download directory.
+/// Files whose size and modification time already match are skipped and
+/// interrupted downloads are resumed. Progress is reported on `progress`.
+#[tracing::instrument(skip_all, fields(samples = ?request.samples))]
+pub async fn download_results(
+    backend: &Backend,
+    config: &TbguiConfig,
+    request: &DownloadRequest,
+    progress: &UnboundedSender<TransferProgress>,
+) -> Result<TransferSummary, AppError> {
+    let remote_out_dir = config.remote_out_dir.as_deref().ok_or_else(|| {
+        AppError::config_missing("Remote out directory is not set in the configuration")
+    })?;
+
+    tracing::info!("downloading results from remote directory {remote_out_dir:?}");
 
-    check_if_dir_exists(client, &remote_dir).await?;
+    let local_dir = config.download_dir();
+    check_if_dir_exists(backend, remote_out_dir).await?;
 
     create_dir_all(&local_dir)
         .await
-        .map_err(|e| AppError::IO(format!("Failed to create selected local directory: {e:?}")))?;
-    let entries = sftp
-        .read_dir(&remote_dir)
+        .map_err(|e| AppError::io("Failed to create local download directory").with_source(&e))?;
+    let files = list_remote_files(backend, config, request).await?;
+    download_files(
+        backend,
+        files,
+        &local_dir,
+        &TransferOptions::from(config),
+        progress,
+    )
+    .await
+}
+
+/// Checks the FASTQ files of `samples` with `gzip -t` and compares the read
+/// counts of both mates, a few samples at a time.
+#[tracing::instrument(skip_all, fields(samples = samples.len()))]
+pub async fn check_fastq(
+    backend: &Backend,
+    config: &TbguiConfig,
+    samples: Vec<String>,
+) -> Result<Vec<FastqCheck>, AppError> {
+    let remote_raw_dir = config.remote_raw_dir.as_deref().ok_or_else(|| {
+        AppError::config_missing("Remote rawreads directory is not set in the configuration")
+    })?;
+    stream::iter(&samples)
+        .map(|sample| check_sample(backend, remote_raw_dir, sample))
+        .buffer_unordered(config.transfer_concurrency.max(1))
+        .collect::<Vec<_>>()
         .await
-        .map_err(|e| AppError::Network(format!("Failed to list remote directory: {e:?}")))?;
-    for entry in entries {
-        let file_name = entry.file_name();
-        let file_type = entry.file_type();
-        let remote_file_path = format!("{}/{}", remote_dir, file_name);
-        let local_file_path = local_dir.join(&file_name);
-
-        if file_type.is_file() && file_name.ends_with(".docx") {
-            download_file(&sftp, &remote_file_path, &local_file_path).await?;
-        }
-    }
+        .into_iter()
+        .collect()
+}
 
-    Ok(())
+/// Uploads paired FASTQ files to `remote_raw_dir`, resuming partial uploads.
+#[tracing::instrument(skip_all, fields(files = files.len()))]
+pub async fn upload_fastq(
+    backend: &Backend,
+    config: &TbguiConfig,
+    files: Vec<FastqFile>,
+    progress: &UnboundedSender<TransferProgress>,
+) -> Result<TransferSummary, AppError> {
+    let remote_raw_dir = config.remote_raw_dir.as_deref().ok_or_else(|| {
+        AppError::config_missing("Remote rawreads directory is not set in the configuration")
+    })?;
+    check_if_dir_exists(backend, remote_raw_dir).await?;
+    upload_fastq_files(
+        backend,
+        files,
+        remote_raw_dir,
+        &TransferOptions::from(config),
+        progress,
+    )
+    .await
 }
 
-pub async fn delete_results(client: &Client, config: &TbguiConfig) -> Result<(), AppError> {
+/// Reads and parses every `results/*.results.json` file in `remote_out_dir`.
+/// Files that cannot be parsed are logged and skipped.
+pub async fn fetch_results(
+    backend: &Backend,
+    config: &TbguiConfig,
+) -> Result<Vec<TbProfile>, AppError> {
     let remote_out_dir = config.remote_out_dir.as_deref().ok_or_else(|| {
-        AppError::Network("Remote out directory is not set in the configuration".to_string())
+        AppError::config_missing("Remote out directory is not set in the configuration")
     })?;
-    let command_checkdir = format!("ls {}", remote_out_dir);
-    let commandexecutedresult_checkdir = client.execute(&command_checkdir).await?;
-    if commandexecutedresult_checkdir.exit_status != 0 {
-        return Err(AppError::Network(format!(
-            "No such remote directory: {:?}",
-            config.remote_out_dir
-        )));
+    let remote_dir = format!("{}/results", remote_out_dir);
+    if !backend.exists(&remote_dir).await? {
+        return Ok(Vec::new());
     }
-    let command_rm = format!("rm -rf {}", remote_out_dir);
-    let commandexecutedresult_rm = client.execute(&command_rm).await?;
-    if commandexecutedresult_rm.exit_status != 0 {
-        println!(
-            "Remote directory may be emmpty. Failed to delete files on remote: {:?}",
-            commandexecutedresult_rm
-        );
-    }
-    let directory = UserDirs::new().unwrap().home_dir().join(RESULT_DIR_LOCAL);
-    if !directory.is_dir() {
-        println!(
-            "Directory {RESULT_DIR_LOCAL} does not exist in local home directory: {:?}",
-            directory
-        );
-        return Ok(());
-    }
-    for entry in fs::read_dir(&directory)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_file() {
-            fs::remove_file(&path)?;
+
+    let mut profiles = Vec::new();
+    for entry in backend.read_dir(&remote_dir).await? {
+        let file_name = entry.name;
+        if !entry.metadata.is_file || !file_name.ends_with(".results.json") {
+            continue;
+        }
+        let data = backend
+            .read(&format!("{}/{}", remote_dir, file_name))
+            .await?;
+        match TbProfile::from_json(&data) {
+            Ok(profile) => profiles.push(profile),
+            Err(err) => tracing::warn!("skipping unparsable result {file_name}: {err}"),
         }
     }
-    Ok(())
+    Ok(profiles)
+}
+
+/// Moves all result files of `samples` from `remote_out_dir` to a dated
+/// directory below `remote_trash_dir`, or removes them if `permanent` is set.
+/// Local downloads are never touched.
+#[tracing::instrument(skip(backend, config))]
+pub async fn delete_results(
+    backend: &Backend,
+    config: &TbguiConfig,
+    samples: Vec<String>,
+    permanent: bool,
+) -> Result<DeleteSummary, AppError> {
+    if samples.is_empty() {
+        return Err(AppError::no_items_checked(
+            "No result sets selected for deletion".to_string(),
+        ));
+    }
+    let request = DownloadRequest {
+        samples: Some(samples),
+        file_types: ResultFileType::all().to_vec(),
+    };
+    let files = list_remote_files(backend, config, &request).await?;
+
+    if permanent {
+        remove_files(backend, &files).await?;
+        return Ok(DeleteSummary {
+            files: files.len(),
+            bytes: files.iter().map(|file| file.size).sum(),
+            skipped: 0,
+            trash_dir: None,
+        });
+    }
+    let remote_trash_dir = config.remote_trash_dir.as_deref().ok_or_else(|| {
+        AppError::config_missing("Remote trash directory is not set in the configuration")
+    })?;
+    let trash_dir = dated_trash_dir(remote_trash_dir);
+    let moved = move_to_trash(backend, &files, &trash_dir).await?;
+    Ok(DeleteSummary {
+        files: moved.len(),
+        bytes: moved.iter().map(|file| file.size).sum(),
+        skipped: files.len() - moved.len(),
+        trash_dir: Some(trash_dir),
+    })
+}
+
+/// Archives all result files of `samples` into a tarball in `remote_archive_dir`,
+/// optionally downloads and verifies it, and then removes the originals.
+#[tracing::instrument(skip(backend, config))]
+pub async fn archive_results(
+    backend: &Backend,
+    config: &TbguiConfig,
+    samples: Vec<String>,
+) -> Result<ArchiveSummary, AppError> {
+    if samples.is_empty() {
+        return Err(AppError::no_items_checked(
+            "No result sets are due for archiving".to_string(),
+        ));
+    }
+    let remote_out_dir = config.remote_out_dir.as_deref().ok_or_else(|| {
+        AppError::config_missing("Remote out directory is not set in the configuration")
+    })?;
+    let remote_archive_dir = config.remote_archive_dir.as_deref().ok_or_else(|| {
+        AppError::config_missing("Remote archive directory is not set in the configuration")
+    })?;
+    let sample_count = samples.len();
+    let request = DownloadRequest {
+        samples: Some(samples),
+        file_types: ResultFileType::all().to_vec(),
+    };
+    let files = list_remote_files(backend, config, &request).await?;
+
+    let file_name = format!(
+        "tbprofiler-results-{}.tar.gz",
+        chrono::Local::now().format("%Y-%m-%d_%H%M%S")
+    );
+    let archive = format!("{}/{}", remote_archive_dir.trim_end_matches('/'), file_name);
+    let archive_size = create_archive(backend, remote_out_dir, &files, &archive).await?;
+
+    let local_copy = if config.archive_download {
+        let local_path = config.download_dir().join("archive").join(&file_name);
+        download_archive(backend, &archive, &local_path).await?;
+        Some(local_path.to_string_lossy().to_string())
+    } else {
+        None
+    };
+
+    remove_files(backend, &files).await?;
+    Ok(ArchiveSummary {
+        samples: sample_count,
+        files: files.len(),
+        freed: files.iter().map(|file| file.size).sum(),
+        archive_size,
+        archive,
+        local_copy,
+    })
 }
 
 pub async fn download_default_template(
-    client: &Client,
+    backend: &Backend,
     config: &TbguiConfig,
 ) -> Result<(), AppError> {
     let remote_file_path = config.default_template_remote.as_deref().ok_or_else(|| {
-        AppError::Network("Default template remote is not set in the configuration".to_string())
+        AppError::config_missing("Default template remote is not set in the configuration")
     })?;
-    let save_directory: Option<PathBuf> = FileDialog::new()
-        .set_title("Select directory to save template")
-        .set_directory(UserDirs::new().unwrap().home_dir())
-        .pick_folder();
+    let mut dialog = FileDialog::new().set_title("Select directory to save template");
+    if let Some(dirs) = UserDirs::new() {
+        dialog = dialog.set_directory(dirs.home_dir());
+    }
+    let save_directory: Option<PathBuf> = dialog.pick_folder();
     let save_directory = match save_directory {
         Some(dir) => dir,
         None => {
-            println!("No directory selected. Download canceled.");
+            tracing::info!("no directory selected, template download canceled");
             return Ok(());
         }
     };
@@ -199,46 +468,160 @@ pub async fn download_default_template(
     let file_name = match file_name {
         Some(name) => name,
         None => {
-            println!("No filename specified. Download canceled.");
+            tracing::info!("no filename specified, template download canceled");
             return Ok(());
         }
     };
     let local_file_path = save_directory.join(file_name);
-
-    let channel = client.get_channel().await?;
-    channel
-        .request_subsystem(true, "sftp")
-        .await
-        .map_err(|e| AppError::Network(format!("Failed to request SFTP subsystem: {e:?}")))?;
-    let sftp = SftpSession::new(channel.into_stream()).await?;
-
-    download_file(&sftp, remote_file_path, &local_file_path).await?;
+    copy_remote_file(backend, remote_file_path, &local_file_path).await?;
     Ok(())
 }
 
-pub async fn upload_user_template(client: &Client, config: &TbguiConfig) -> Result<(), AppError> {
-    let remote_file_path = config.user_template_remote.as_deref().ok_or_else(|| {
-        AppError::Network("User template remote is not set in the configuration".to_string())
+/// Uploads `local_path` as the named user template into `remote_template_dir`,
+/// keeping the replaced version in the template history. The file must have
+/// been checked with [`super::docx::validate_docx`] before.
+#[tracing::instrument(skip(backend, config))]
+pub async fn upload_user_template(
+    backend: &Backend,
+    config: &TbguiConfig,
+    name: &str,
+    local_path: PathBuf,
+) -> Result<String, AppError> {
+    let template_dir = config.remote_template_dir.as_deref().ok_or_else(|| {
+        AppError::config_missing("Remote template directory is not set in the configuration")
     })?;
-    let local_file_path: Option<PathBuf> = FileDialog::new()
-        .set_title("Select File to Upload")
-        .set_directory(UserDirs::new().unwrap().home_dir())
-        .pick_file();
-    let local_file_path = match local_file_path {
-        Some(path) => path,
-        None => {
-            println!("No file selected. Upload canceled.");
-            return Ok(());
-        }
+    let name = sanitize_name(name);
+    if name.is_empty() {
+        return Err(AppError::io("Template name must not be empty".to_string()));
+    }
+    upload_template(backend, template_dir, &name, &local_path).await
+}
+
+/// Lists the default template, the templates in `remote_template_dir` and the
+/// configured `user_template_remote` if it lives elsewhere.
+pub async fn list_remote_templates(
+    backend: &Backend,
+    config: &TbguiConfig,
+) -> Result<Vec<RemoteTemplate>, AppError> {
+    let mut templates = Vec::new();
+    let extra = [
+        (config.default_template_remote.as_deref(), true),
+        (config.user_template_remote.as_deref(), false),
+    ];
+    let listed = match config.remote_template_dir.as_deref() {
+        Some(template_dir) => list_templates(backend, template_dir).await?,
+        None => Vec::new(),
     };
+    for (path, default) in extra {
+        let Some(path) = path else {
+            continue;
+        };
+        if listed.iter().any(|template| template.path == path) {
+            continue;
+        }
+        let Ok(metadata) = backend.stat(path).await else {
+            continue;
+        };
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        templates.push(RemoteTemplate {
+            name: file_name.trim_end_matches(".docx").to_string(),
+            path: path.to_string(),
+            size: metadata.size,
+            modified: metadata.mtime,
+            versions: 0,
+            default,
+        });
+    }
+    templates.extend(listed);
+    Ok(templates)
+}
 
-    let channel = client.get_channel().await?;
-    channel
-        .request_subsystem(true, "sftp")
-        .await
-        .map_err(|e| AppError::Network(format!("Failed to request SFTP subsystem: {e:?}")))?;
-    client
-        .upload_file(local_file_path, remote_file_path)
-        .await?;
+pub async fn fetch_lists(backend: &Backend, config: &TbguiConfig) -> Result<Vec<List>, AppError> {
+    let remote_file_path = config.remote_batches_file.as_deref().ok_or_else(|| {
+        AppError::config_missing("Remote batches file is not set in the configuration")
+    })?;
+    if !backend.exists(remote_file_path).await? {
+        return Ok(Vec::new());
+    }
+    let data = backend.read(remote_file_path).await?;
+    serde_json::from_slice(&data)
+        .map_err(|e| AppError::io(format!("Failed to parse shared batches file: {e}")))
+}
+
+/// Reads the group profile published on the cluster.
+pub async fn fetch_profile(backend: &Backend, config: &TbguiConfig) -> Result<Profile, AppError> {
+    let remote_file_path = config.remote_profile_file.as_deref().ok_or_else(|| {
+        AppError::config_missing("Remote profile file is not set in the configuration")
+    })?;
+    if !backend.exists(remote_file_path).await? {
+        return Err(AppError::config_missing(format!(
+            "No profile has been published at {remote_file_path}"
+        )));
+    }
+    let data = backend.read(remote_file_path).await?;
+    Profile::parse(
+        &String::from_utf8_lossy(&data),
+        ProfileFormat::of(Path::new(remote_file_path)),
+    )
+}
+
+/// Whether a group profile has already been published at `remote_profile_file`.
+pub async fn profile_published(backend: &Backend, config: &TbguiConfig) -> Result<bool, AppError> {
+    let remote_file_path = config.remote_profile_file.as_deref().ok_or_else(|| {
+        AppError::config_missing("Remote profile file is not set in the configuration")
+    })?;
+    backend.exists(remote_file_path).await
+}
+
+/// Publishes `profile` for the group at `remote_profile_file`. The profile is
+/// written next to it first and moved into place, so readers never see a
+/// partly written file.
+pub async fn push_profile(
+    backend: &Backend,
+    config: &TbguiConfig,
+    profile: &Profile,
+) -> Result<(), AppError> {
+    let remote_file_path = config.remote_profile_file.as_deref().ok_or_else(|| {
+        AppError::config_missing("Remote profile file is not set in the configuration")
+    })?;
+    let data = profile.to_string(ProfileFormat::of(Path::new(remote_file_path)))?;
+    let (parent, name) = remote_file_path
+        .rsplit_once('/')
+        .unwrap_or((".", remote_file_path));
+    backend
+        .exec(&format!("mkdir -p {}", shell_quote(parent)))
+        .await?
+        .check(format!("Failed to create {parent}"))?;
+    let part_path = format!("{parent}/.{name}.part");
+    backend.write(&part_path, data.as_bytes()).await?;
+    backend
+        .exec(&format!(
+            "mv -f -- {} {}",
+            shell_quote(&part_path),
+            shell_quote(remote_file_path)
+        ))
+        .await?
+        .check(format!(
+            "Failed to publish the profile at {remote_file_path}"
+        ))?;
     Ok(())
 }
+
+pub async fn push_lists(
+    backend: &Backend,
+    config: &TbguiConfig,
+    lists: &[List],
+) -> Result<(), AppError> {
+    let remote_file_path = config.remote_batches_file.as_deref().ok_or_else(|| {
+        AppError::config_missing("Remote batches file is not set in the configuration")
+    })?;
+    let data = serde_json::to_vec_pretty(lists)
+        .map_err(|e| AppError::io(format!("Failed to serialize batches: {e}")))?;
+    if let Some((parent, _)) = remote_file_path.rsplit_once('/') {
+        backend
+            .exec(&format!("mkdir -p {}", shell_quote(parent)))
+            .await?
+            .check(format!("Failed to create {parent}"))?;
+    }
+    backend.write(remote_file_path, &data).await
+}
@@ -0,0 +1,105 @@
+use super::backend::{Backend, RemoteBackend};
+use super::types::AppError;
+use super::utils::shell_quote;
+use std::path::Path;
+
+/// Directory below the template directory that keeps replaced versions.
+pub const HISTORY_DIR: &str = ".history";
+
+/// A DOCX report template on the remote.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RemoteTemplate {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    pub modified: Option<u32>,
+    /// Number of earlier versions kept in the history.
+    pub versions: usize,
+    /// The default template shipped with tb-profiler, which cannot be replaced.
+    pub default: bool,
+}
+
+/// Template name as entered by the user, reduced to characters that are safe in a file name.
+pub fn sanitize_name(name: &str) -> String {
+    name.trim()
+        .trim_end_matches(".docx")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Lists the `*.docx` templates in `template_dir` with their version count.
+pub async fn list_templates(
+    backend: &Backend,
+    template_dir: &str,
+) -> Result<Vec<RemoteTemplate>, AppError> {
+    if !backend.exists(template_dir).await? {
+        return Ok(Vec::new());
+    }
+    let mut templates = Vec::new();
+    for entry in backend.read_dir(template_dir).await? {
+        let name = entry.name;
+        let Some(stem) = name.strip_suffix(".docx") else {
+            continue;
+        };
+        if !entry.metadata.is_file {
+            continue;
+        }
+        let history = format!("{}/{}/{}", template_dir, HISTORY_DIR, stem);
+        let versions = if backend.exists(&history).await? {
+            backend.read_dir(&history).await?.len()
+        } else {
+            0
+        };
+        let metadata = entry.metadata;
+        templates.push(RemoteTemplate {
+            name: stem.to_string(),
+            path: format!("{}/{}", template_dir, name),
+            size: metadata.size,
+            modified: metadata.mtime,
+            versions,
+            default: false,
+        });
+    }
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+/// Uploads `local_path` as template `name`. The file is uploaded next to the
+/// template as a hidden `.<name>.part` first, so a failed upload leaves the
+/// current template in place. An existing template of the same name is then
+/// moved into the history, named after its modification time.
+pub async fn upload_template(
+    backend: &Backend,
+    template_dir: &str,
+    name: &str,
+    local_path: &Path,
+) -> Result<String, AppError> {
+    let remote_path = format!("{}/{}.docx", template_dir, name);
+    let part_path = format!("{}/.{}.part", template_dir, name);
+    let history = format!("{}/{}/{}", template_dir, HISTORY_DIR, name);
+    backend
+        .exec(&format!("mkdir -p {}", shell_quote(&history)))
+        .await?
+        .check(format!("Failed to create the history of template {name}"))?;
+    if let Err(err) = backend.upload(local_path, &part_path).await {
+        let _ = backend.remove(&part_path).await;
+        return Err(err);
+    }
+    let command = format!(
+        "if [ -e {path} ]; then mv -- {path} {history}/$(date -r {path} +%Y-%m-%d_%H%M%S).docx; fi && mv -- {part} {path}",
+        history = shell_quote(&history),
+        path = shell_quote(&remote_path),
+        part = shell_quote(&part_path),
+    );
+    backend.exec(&command).await?.check(format!(
+        "Failed to keep previous version of template {name}"
+    ))?;
+    Ok(remote_path)
+}
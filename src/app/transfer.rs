@@ -0,0 +1,589 @@
+use super::backend::{Backend, BackendFile, RemoteBackend};
+use super::config::TbguiConfig;
+use super::types::AppError;
+use super::utils::{belongs_to_sample, sample_of, shell_quote};
+use futures_util::{stream, Stream, StreamExt};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::fs::{create_dir_all, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::{mpsc::UnboundedSender, Mutex};
+
+/// Minimum number of bytes between two progress events of the same file.
+const PROGRESS_INTERVAL: u64 = 1 << 20;
+/// Size of a single SFTP read request, the largest most servers accept.
+const CHUNK_SIZE: usize = 255 * 1024;
+/// Number of read requests in flight per file when no configuration is available.
+const PIPELINE_DEPTH: usize = 8;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub enum ResultFileType {
+    Docx,
+    Json,
+    Csv,
+    Txt,
+    Bam,
+    Vcf,
+}
+
+impl ResultFileType {
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::Docx,
+            Self::Json,
+            Self::Csv,
+            Self::Txt,
+            Self::Bam,
+            Self::Vcf,
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Docx => "docx",
+            Self::Json => "json",
+            Self::Csv => "csv",
+            Self::Txt => "txt",
+            Self::Bam => "bam",
+            Self::Vcf => "vcf",
+        }
+    }
+
+    /// Sub-directory of `remote_out_dir` in which TB-Profiler writes this file type.
+    pub fn remote_subdir(&self) -> &'static str {
+        match self {
+            Self::Docx | Self::Json | Self::Csv | Self::Txt => "results",
+            Self::Bam => "bam",
+            Self::Vcf => "vcf",
+        }
+    }
+
+    pub fn matches(&self, file_name: &str) -> bool {
+        match self {
+            Self::Docx => file_name.ends_with(".docx"),
+            Self::Json => file_name.ends_with(".json"),
+            Self::Csv => file_name.ends_with(".csv"),
+            Self::Txt => file_name.ends_with(".txt"),
+            Self::Bam => file_name.ends_with(".bam") || file_name.ends_with(".bam.bai"),
+            Self::Vcf => file_name.ends_with(".vcf") || file_name.ends_with(".vcf.gz"),
+        }
+    }
+}
+
+/// Which result files to download. `samples: None` selects all samples.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DownloadRequest {
+    pub samples: Option<Vec<String>>,
+    pub file_types: Vec<ResultFileType>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransferOptions {
+    /// Number of files transferred at the same time.
+    pub concurrency: usize,
+    /// Number of read requests in flight per file.
+    pub pipeline_depth: usize,
+    /// Compare a remote `sha256sum` with the downloaded file.
+    pub verify_checksums: bool,
+}
+
+impl From<&TbguiConfig> for TransferOptions {
+    fn from(config: &TbguiConfig) -> Self {
+        Self {
+            concurrency: config.transfer_concurrency,
+            pipeline_depth: PIPELINE_DEPTH,
+            verify_checksums: config.verify_checksums,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemoteFile {
+    pub path: String,
+    pub name: String,
+    pub subdir: String,
+    pub size: u64,
+    pub mtime: Option<u32>,
+}
+
+impl RemoteFile {
+    /// Result files are stored directly in `local_dir`, BAM and VCF files
+    /// in a sub-directory of the same name as on the remote.
+    pub fn local_path(&self, local_dir: &Path) -> PathBuf {
+        if self.subdir == "results" {
+            local_dir.join(&self.name)
+        } else {
+            local_dir.join(&self.subdir).join(&self.name)
+        }
+    }
+
+    /// A local file is up to date if its size and modification time match the remote file.
+    pub async fn is_up_to_date(&self, local_path: &Path) -> bool {
+        let Ok(metadata) = tokio::fs::metadata(local_path).await else {
+            return false;
+        };
+        let local_mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs());
+        metadata.len() == self.size
+            && self.mtime.is_some()
+            && local_mtime == self.mtime.map(u64::from)
+    }
+}
+
+/// All result files of one sample on the remote.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResultSet {
+    pub sample: String,
+    pub files: usize,
+    pub size: u64,
+    pub modified: Option<u32>,
+}
+
+impl ResultSet {
+    /// Day the results were last written, used to group the sets of one run.
+    pub fn run(&self) -> String {
+        self.modified
+            .and_then(|mtime| chrono::DateTime::from_timestamp(i64::from(mtime), 0))
+            .map(|time| {
+                time.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d")
+                    .to_string()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Groups remote files by sample, sorted by sample name.
+pub fn group_result_sets(files: &[RemoteFile]) -> Vec<ResultSet> {
+    let mut sets: BTreeMap<&str, ResultSet> = BTreeMap::new();
+    for file in files {
+        let sample = sample_of(&file.name);
+        let set = sets.entry(sample).or_insert_with(|| ResultSet {
+            sample: sample.to_string(),
+            ..ResultSet::default()
+        });
+        set.files += 1;
+        set.size += file.size;
+        set.modified = set.modified.max(file.mtime);
+    }
+    sets.into_values().collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransferProgress {
+    Started {
+        files: usize,
+        bytes: u64,
+        skipped: usize,
+    },
+    File {
+        name: String,
+        transferred: u64,
+        size: u64,
+    },
+    FileDone {
+        name: String,
+    },
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransferSummary {
+    pub transferred: usize,
+    pub skipped: usize,
+    pub bytes: u64,
+}
+
+/// Progress of the running transfer as shown in the UI.
+#[derive(Clone, Debug, Default)]
+pub struct TransferState {
+    pub running: bool,
+    pub total_files: usize,
+    pub total_bytes: u64,
+    pub skipped_files: usize,
+    pub finished_files: usize,
+    pub finished_bytes: u64,
+    pub active: BTreeMap<String, (u64, u64)>,
+}
+
+impl TransferState {
+    pub fn apply(&mut self, progress: TransferProgress) {
+        match progress {
+            TransferProgress::Started {
+                files,
+                bytes,
+                skipped,
+            } => {
+                *self = Self {
+                    running: true,
+                    total_files: files,
+                    total_bytes: bytes,
+                    skipped_files: skipped,
+                    ..Self::default()
+                };
+            }
+            TransferProgress::File {
+                name,
+                transferred,
+                size,
+            } => {
+                self.active.insert(name, (transferred, size));
+            }
+            TransferProgress::FileDone { name } => {
+                if let Some((_, size)) = self.active.remove(&name) {
+                    self.finished_bytes += size;
+                }
+                self.finished_files += 1;
+            }
+        }
+    }
+
+    pub fn transferred_bytes(&self) -> u64 {
+        self.finished_bytes
+            + self
+                .active
+                .values()
+                .map(|(transferred, _)| transferred)
+                .sum::<u64>()
+    }
+
+    /// Overall progress between 0 and 1.
+    pub fn fraction(&self) -> f32 {
+        if self.total_bytes == 0 {
+            return if self.running { 0.0 } else { 1.0 };
+        }
+        self.transferred_bytes() as f32 / self.total_bytes as f32
+    }
+}
+
+/// Lists the remote files selected by `request`.
+pub async fn list_remote_files(
+    backend: &Backend,
+    config: &TbguiConfig,
+    request: &DownloadRequest,
+) -> Result<Vec<RemoteFile>, AppError> {
+    let remote_out_dir = config.remote_out_dir.as_deref().ok_or_else(|| {
+        AppError::config_missing("Remote out directory is not set in the configuration")
+    })?;
+
+    let mut subdirs: Vec<&str> = request
+        .file_types
+        .iter()
+        .map(ResultFileType::remote_subdir)
+        .collect();
+    subdirs.sort_unstable();
+    subdirs.dedup();
+
+    let mut files = Vec::new();
+    for subdir in subdirs {
+        let remote_dir = format!("{}/{}", remote_out_dir, subdir);
+        if !backend.exists(&remote_dir).await? {
+            continue;
+        }
+        for entry in backend.read_dir(&remote_dir).await? {
+            let name = entry.name;
+            let selected_type = request
+                .file_types
+                .iter()
+                .any(|file_type| file_type.remote_subdir() == subdir && file_type.matches(&name));
+            let selected_sample = request
+                .samples
+                .as_deref()
+                .is_none_or(|samples| belongs_to_sample(&name, samples));
+            if entry.metadata.is_file && selected_type && selected_sample {
+                let metadata = entry.metadata;
+                files.push(RemoteFile {
+                    path: format!("{}/{}", remote_dir, name),
+                    name,
+                    subdir: subdir.to_string(),
+                    size: metadata.size,
+                    mtime: metadata.mtime,
+                });
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn part_path(local_path: &Path) -> PathBuf {
+    let mut file_name = local_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".part");
+    local_path.with_file_name(file_name)
+}
+
+/// Sidecar of a `.part` file recording the size and modification time of the
+/// remote file it was started from.
+fn part_meta_path(part_path: &Path) -> PathBuf {
+    let mut file_name = part_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".meta");
+    part_path.with_file_name(file_name)
+}
+
+fn part_meta(remote: &RemoteFile) -> Option<String> {
+    remote.mtime.map(|mtime| format!("{} {mtime}", remote.size))
+}
+
+/// Where to resume the download into `part_path`: its length if it was started
+/// from the same version of `remote`, otherwise 0.
+async fn resume_offset(remote: &RemoteFile, part_path: &Path) -> u64 {
+    let Some(expected) = part_meta(remote) else {
+        return 0;
+    };
+    let recorded = tokio::fs::read_to_string(part_meta_path(part_path)).await;
+    match tokio::fs::metadata(part_path).await {
+        Ok(metadata)
+            if metadata.len() <= remote.size
+                && recorded.is_ok_and(|recorded| recorded.trim() == expected) =>
+        {
+            metadata.len()
+        }
+        _ => 0,
+    }
+}
+
+/// Opens `depth` handles of the same remote file so that `depth` read
+/// requests can be in flight at once.
+async fn open_handles(
+    backend: &Backend,
+    remote_path: &str,
+    depth: usize,
+) -> Result<Vec<Mutex<Box<dyn BackendFile>>>, AppError> {
+    let mut handles = Vec::with_capacity(depth);
+    for _ in 0..depth.max(1) {
+        let file = backend.reader(remote_path).await?;
+        handles.push(Mutex::new(file));
+    }
+    Ok(handles)
+}
+
+/// Reads the byte range `start..end` of a remote file in chunks of
+/// [`CHUNK_SIZE`]. Up to `handles.len()` chunks are requested concurrently,
+/// but they are yielded in order.
+fn read_chunks(
+    handles: &[Mutex<Box<dyn BackendFile>>],
+    start: u64,
+    end: u64,
+) -> impl Stream<Item = std::io::Result<Vec<u8>>> + '_ {
+    stream::iter((start..end).step_by(CHUNK_SIZE))
+        .map(move |offset| async move {
+            let index = ((offset - start) / CHUNK_SIZE as u64) as usize % handles.len();
+            let mut handle = handles[index].lock().await;
+            let len = (end - offset).min(CHUNK_SIZE as u64) as usize;
+            let mut buffer = vec![0u8; len];
+            handle.seek(SeekFrom::Start(offset)).await?;
+            handle.read_exact(&mut buffer).await?;
+            Ok(buffer)
+        })
+        .buffered(handles.len())
+}
+
+/// Copies a whole remote file to `local_path` using pipelined reads.
+pub async fn copy_remote_file(
+    backend: &Backend,
+    remote_path: &str,
+    local_path: &Path,
+) -> Result<u64, AppError> {
+    let size = backend.stat(remote_path).await?.size;
+    if let Some(parent) = local_path.parent() {
+        create_dir_all(parent).await?;
+    }
+    let handles = open_handles(backend, remote_path, PIPELINE_DEPTH).await?;
+    let mut local_file = tokio::fs::File::create(local_path).await?;
+    let mut chunks = read_chunks(&handles, 0, size);
+    while let Some(chunk) = chunks.next().await {
+        local_file.write_all(&chunk?).await?;
+    }
+    local_file.flush().await?;
+    Ok(size)
+}
+
+pub async fn local_sha256(path: &Path) -> Result<String, AppError> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut file = std::fs::File::open(&path)?;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut file, &mut hasher)?;
+        Ok(format!("{:x}", hasher.finalize()))
+    })
+    .await
+    .map_err(|e| AppError::io(format!("Failed to compute checksum: {e}")))?
+}
+
+pub async fn remote_sha256(backend: &Backend, remote_path: &str) -> Result<String, AppError> {
+    let command = format!("sha256sum {}", shell_quote(remote_path));
+    let result = backend.exec(&command).await?.check(format!(
+        "Failed to compute remote checksum of {remote_path}"
+    ))?;
+    result
+        .stdout
+        .split_whitespace()
+        .next()
+        .map(str::to_string)
+        .ok_or_else(|| AppError::network(format!("Empty checksum for {remote_path}")))
+}
+
+/// Checks a downloaded file against the remote size and, if enabled, the remote SHA-256.
+async fn verify_download(
+    backend: &Backend,
+    remote: &RemoteFile,
+    local_path: &Path,
+    options: &TransferOptions,
+) -> Result<(), AppError> {
+    let local_size = tokio::fs::metadata(local_path).await?.len();
+    if local_size != remote.size {
+        return Err(AppError::io(format!(
+            "Size mismatch for {}: expected {} bytes, got {}",
+            remote.name, remote.size, local_size
+        )));
+    }
+    if options.verify_checksums {
+        let (local, remote_sum) = tokio::try_join!(
+            local_sha256(local_path),
+            remote_sha256(backend, &remote.path)
+        )?;
+        if local != remote_sum {
+            return Err(AppError::io(format!(
+                "Checksum mismatch for {}",
+                remote.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Downloads `remote` to `local_path`, resuming from an existing `.part` file
+/// if the remote file has not changed since it was started. The file is only
+/// moved into place once it has been verified. It gets the
+/// modification time of the remote file so that unchanged files can be
+/// skipped next time.
+pub async fn download_remote_file(
+    backend: &Backend,
+    remote: &RemoteFile,
+    local_path: &Path,
+    options: &TransferOptions,
+    progress: &UnboundedSender<TransferProgress>,
+) -> Result<u64, AppError> {
+    if let Some(parent) = local_path.parent() {
+        create_dir_all(parent).await?;
+    }
+    let part_path = part_path(local_path);
+    let meta_path = part_meta_path(&part_path);
+    let offset = resume_offset(remote, &part_path).await;
+
+    let mut local_file = if offset > 0 {
+        OpenOptions::new().append(true).open(&part_path).await?
+    } else {
+        OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&part_path)
+            .await?
+    };
+    if offset == 0 {
+        match part_meta(remote) {
+            Some(meta) => tokio::fs::write(&meta_path, meta).await?,
+            None => {
+                let _ = tokio::fs::remove_file(&meta_path).await;
+            }
+        }
+    }
+
+    let handles = open_handles(backend, &remote.path, options.pipeline_depth).await?;
+    let mut chunks = read_chunks(&handles, offset, remote.size);
+    let mut transferred = offset;
+    let mut reported = offset;
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk?;
+        local_file.write_all(&chunk).await?;
+        transferred += chunk.len() as u64;
+        if transferred - reported >= PROGRESS_INTERVAL {
+            reported = transferred;
+            let _ = progress.send(TransferProgress::File {
+                name: remote.name.clone(),
+                transferred,
+                size: remote.size,
+            });
+        }
+    }
+    local_file.flush().await?;
+    drop(local_file);
+
+    if let Err(err) = verify_download(backend, remote, &part_path, options).await {
+        let _ = tokio::fs::remove_file(&part_path).await;
+        let _ = tokio::fs::remove_file(&meta_path).await;
+        return Err(err);
+    }
+    tokio::fs::rename(&part_path, local_path).await?;
+    let _ = tokio::fs::remove_file(&meta_path).await;
+    if let Some(mtime) = remote.mtime {
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(u64::from(mtime));
+        std::fs::File::options()
+            .write(true)
+            .open(local_path)?
+            .set_modified(modified)?;
+    }
+    Ok(transferred - offset)
+}
+
+/// Downloads all `files` that are not yet up to date in `local_dir`,
+/// with up to `options.concurrency` files in flight.
+pub async fn download_files(
+    backend: &Backend,
+    files: Vec<RemoteFile>,
+    local_dir: &Path,
+    options: &TransferOptions,
+    progress: &UnboundedSender<TransferProgress>,
+) -> Result<TransferSummary, AppError> {
+    let mut skipped = Vec::new();
+    let mut pending = Vec::new();
+    for file in files {
+        if file.is_up_to_date(&file.local_path(local_dir)).await {
+            skipped.push(file);
+        } else {
+            pending.push(file);
+        }
+    }
+
+    let _ = progress.send(TransferProgress::Started {
+        files: pending.len(),
+        bytes: pending.iter().map(|file| file.size).sum(),
+        skipped: skipped.len(),
+    });
+
+    let mut summary = TransferSummary {
+        skipped: skipped.len(),
+        ..TransferSummary::default()
+    };
+    let mut downloads = stream::iter(&pending)
+        .map(|file| async move {
+            let _ = progress.send(TransferProgress::File {
+                name: file.name.clone(),
+                transferred: 0,
+                size: file.size,
+            });
+            let bytes = download_remote_file(
+                backend,
+                file,
+                &file.local_path(local_dir),
+                options,
+                progress,
+            )
+            .await?;
+            let _ = progress.send(TransferProgress::FileDone {
+                name: file.name.clone(),
+            });
+            Ok::<u64, AppError>(bytes)
+        })
+        .buffer_unordered(options.concurrency.max(1));
+    while let Some(result) = downloads.next().await {
+        summary.bytes += result?;
+        summary.transferred += 1;
+    }
+    Ok(summary)
+}
@@ -1,34 +1,199 @@
+use std::error::Error;
+
+/// An error shown to the user, with what went wrong and the underlying causes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AppError {
+    pub kind: ErrorKind,
+    pub message: String,
+    /// Messages of the underlying errors, outermost first.
+    pub causes: Vec<String>,
+    /// The remote command that failed, with everything it printed.
+    pub output: Option<Box<CommandOutput>>,
+}
+
+/// A command that ran on the cluster and what it printed.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CommandOutput {
+    pub command: String,
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_status: u32,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub enum AppError {
-    Network(String),
-    NoItemsChecked(String),
-    IO(String),
+pub enum ErrorKind {
+    /// The cluster rejected the key or password.
+    Auth,
+    /// The host key of the cluster could not be verified.
+    HostKey,
+    Timeout,
+    /// The connection failed or was lost.
+    Network,
+    RemoteCommand,
+    Sftp,
+    /// A local file or process failed.
+    Io,
+    ConfigMissing,
+    /// The scheduler rejected a submission or query.
+    Scheduler,
+    NoItemsChecked,
+}
+
+impl AppError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            causes: Vec::new(),
+            output: None,
+        }
+    }
+
+    pub fn network(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Network, message)
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Io, message)
+    }
+
+    pub fn sftp(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Sftp, message)
+    }
+
+    pub fn config_missing(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::ConfigMissing, message)
+    }
+
+    pub fn scheduler(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Scheduler, message)
+    }
+
+    pub fn no_items_checked(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::NoItemsChecked, message)
+    }
+
+    pub fn with_output(mut self, output: CommandOutput) -> Self {
+        self.output = Some(Box::new(output));
+        self
+    }
+
+    /// Appends `source` and its own sources to the causes.
+    pub fn with_source(mut self, source: &dyn Error) -> Self {
+        let mut source = Some(source);
+        while let Some(error) = source {
+            self.causes.push(error.to_string());
+            source = error.source();
+        }
+        self
+    }
+
+    /// Builds an error of `kind` from `error`, keeping its sources as causes.
+    fn from_error(kind: ErrorKind, error: &(dyn Error + 'static)) -> Self {
+        let error_kind = if is_timeout(error) {
+            ErrorKind::Timeout
+        } else {
+            kind
+        };
+        let mut app_error = Self::new(error_kind, error.to_string());
+        if let Some(source) = error.source() {
+            app_error = app_error.with_source(source);
+        }
+        app_error
+    }
+
+    /// Whether running the same action again may succeed.
+    pub fn retryable(&self) -> bool {
+        matches!(
+            self.kind,
+            ErrorKind::Timeout | ErrorKind::Network | ErrorKind::Sftp
+        )
+    }
+}
+
+impl CommandOutput {
+    pub fn success(&self) -> bool {
+        self.exit_status == 0
+    }
+
+    /// The output if the command succeeded, a [`ErrorKind::RemoteCommand`]
+    /// error with `message` otherwise.
+    pub fn check(self, message: impl Into<String>) -> Result<Self, AppError> {
+        self.check_as(ErrorKind::RemoteCommand, message)
+    }
+
+    /// Like [`Self::check`] with an error of `kind`.
+    pub fn check_as(self, kind: ErrorKind, message: impl Into<String>) -> Result<Self, AppError> {
+        if self.success() {
+            Ok(self)
+        } else {
+            Err(AppError::new(kind, message).with_output(self))
+        }
+    }
+
+    /// Everything known about the command, for the error details.
+    pub fn details(&self) -> String {
+        format!(
+            "$ {}\nexit status: {}\n\nstdout:\n{}\n\nstderr:\n{}",
+            self.command,
+            self.exit_status,
+            self.stdout.trim_end(),
+            self.stderr.trim_end()
+        )
+    }
+}
+
+/// Whether `error` or one of its sources is an IO timeout.
+fn is_timeout(error: &(dyn Error + 'static)) -> bool {
+    let mut source = Some(error);
+    while let Some(error) = source {
+        if let Some(io_error) = error.downcast_ref::<std::io::Error>() {
+            if io_error.kind() == std::io::ErrorKind::TimedOut {
+                return true;
+            }
+        }
+        source = error.source();
+    }
+    false
 }
 
 impl std::fmt::Display for AppError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            AppError::Network(err) => write!(f, "{}", err),
-            AppError::NoItemsChecked(err) => write!(f, "{}", err),
-            AppError::IO(err) => write!(f, "{}", err),
+        write!(f, "{}", self.message)?;
+        if let Some(output) = &self.output {
+            if !output.stderr.trim().is_empty() {
+                write!(f, ": {}", output.stderr.trim())?;
+            }
+        }
+        for cause in &self.causes {
+            write!(f, ": {}", cause)?;
         }
+        Ok(())
     }
 }
 
 impl From<async_ssh2_tokio::Error> for AppError {
     fn from(error: async_ssh2_tokio::Error) -> Self {
-        AppError::Network(format!("async_ssh2_tokio error: {}", error))
+        let kind = match &error {
+            async_ssh2_tokio::Error::KeyAuthFailed
+            | async_ssh2_tokio::Error::PasswordWrong
+            | async_ssh2_tokio::Error::KeyInvalid(_) => ErrorKind::Auth,
+            async_ssh2_tokio::Error::ServerCheckFailed => ErrorKind::HostKey,
+            async_ssh2_tokio::Error::SftpError(_) => ErrorKind::Sftp,
+            _ => ErrorKind::Network,
+        };
+        AppError::from_error(kind, &error)
     }
 }
 
 impl From<std::io::Error> for AppError {
     fn from(error: std::io::Error) -> Self {
-        AppError::Network(format!("std::io::Error: {}", error))
+        AppError::from_error(ErrorKind::Io, &error)
     }
 }
 
 impl From<russh_sftp::client::error::Error> for AppError {
     fn from(error: russh_sftp::client::error::Error) -> Self {
-        AppError::Network(format!("russh_sftp::client::error::Error: {}", error))
+        AppError::from_error(ErrorKind::Sftp, &error)
     }
 }
@@ -0,0 +1,242 @@
+use super::backend::{Backend, RemoteBackend};
+use super::transfer::{
+    local_sha256, remote_sha256, TransferOptions, TransferProgress, TransferSummary,
+};
+use super::types::AppError;
+use futures_util::{stream, StreamExt};
+use std::collections::BTreeMap;
+use std::io::SeekFrom;
+use std::path::PathBuf;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Size of a single SFTP write request.
+const CHUNK_SIZE: usize = 255 * 1024;
+/// Minimum number of bytes between two progress events of the same file.
+const PROGRESS_INTERVAL: u64 = 1 << 20;
+const FASTQ_EXTENSIONS: [&str; 4] = [".fastq.gz", ".fq.gz", ".fastq", ".fq"];
+
+/// A local FASTQ file named `<sample>_1.fastq.gz` or `<sample>_2.fastq.gz`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FastqFile {
+    pub path: PathBuf,
+    pub name: String,
+    pub sample: String,
+    pub mate: u8,
+    pub size: u64,
+}
+
+/// Sample name and mate (1 or 2) of a paired FASTQ file name. `_R1` and `_R2`
+/// are accepted as well, but the sample name must not contain another `_`
+/// because the remote sample discovery splits at the first one.
+pub fn parse_fastq_name(name: &str) -> Option<(&str, u8)> {
+    let stem = FASTQ_EXTENSIONS
+        .iter()
+        .find_map(|extension| name.strip_suffix(extension))?;
+    let (sample, mate) = stem.rsplit_once('_')?;
+    let mate = match mate {
+        "1" | "R1" => 1,
+        "2" | "R2" => 2,
+        _ => return None,
+    };
+    (!sample.is_empty() && !sample.contains('_')).then_some((sample, mate))
+}
+
+/// Local FASTQ files selected for upload and the problems found in their names.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FastqSelection {
+    pub files: Vec<FastqFile>,
+    pub problems: Vec<String>,
+}
+
+impl FastqSelection {
+    /// Checks that every file has a valid name and that both mates of each sample are present.
+    pub fn new(paths: &[PathBuf]) -> Self {
+        let mut selection = Self::default();
+        let mut samples: BTreeMap<String, [bool; 2]> = BTreeMap::new();
+        for path in paths {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let Some((sample, mate)) = parse_fastq_name(&name) else {
+                selection.problems.push(format!(
+                    "{name}: expected <sample>_1.fastq.gz or <sample>_2.fastq.gz"
+                ));
+                continue;
+            };
+            let mates = samples.entry(sample.to_string()).or_default();
+            if mates[usize::from(mate - 1)] {
+                selection
+                    .problems
+                    .push(format!("{name}: mate {mate} of {sample} selected twice"));
+                continue;
+            }
+            mates[usize::from(mate - 1)] = true;
+            let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or_default();
+            selection.files.push(FastqFile {
+                path: path.clone(),
+                sample: sample.to_string(),
+                name,
+                mate,
+                size,
+            });
+        }
+        for (sample, mates) in samples {
+            if let Some(missing) = mates.iter().position(|present| !present) {
+                selection
+                    .problems
+                    .push(format!("{sample}: read {} is missing", missing + 1));
+            }
+        }
+        selection.files.sort_by(|a, b| a.name.cmp(&b.name));
+        selection
+    }
+
+    pub fn is_valid(&self) -> bool {
+        !self.files.is_empty() && self.problems.is_empty()
+    }
+
+    pub fn samples(&self) -> usize {
+        self.files.len() / 2
+    }
+}
+
+/// Uploads `file` to `remote_dir` through a hidden `.<name>.part` file, resuming
+/// a previous partial upload. Unlike [`RemoteBackend::upload`], this keeps what was already
+/// transferred and reports progress. The file is renamed into place once its
+/// SHA-256 matches the local file.
+pub async fn upload_fastq_file(
+    backend: &Backend,
+    file: &FastqFile,
+    remote_dir: &str,
+    progress: &UnboundedSender<TransferProgress>,
+) -> Result<u64, AppError> {
+    let remote_path = format!("{}/{}", remote_dir, file.name);
+    // Hidden, so that sample discovery and the watch ignore it.
+    let part_path = format!("{}/.{}.part", remote_dir, file.name);
+    let offset = match backend.stat(&part_path).await {
+        Ok(metadata) => metadata.size.min(file.size),
+        Err(_) => 0,
+    };
+
+    let mut remote_file = backend.writer(&part_path, offset == 0).await?;
+    remote_file.seek(SeekFrom::Start(offset)).await?;
+    let mut local_file = tokio::fs::File::open(&file.path).await?;
+    local_file.seek(SeekFrom::Start(offset)).await?;
+
+    let mut buffer = vec![0; CHUNK_SIZE];
+    let mut transferred = offset;
+    let mut reported = offset;
+    loop {
+        let read = local_file.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        remote_file.write_all(&buffer[..read]).await?;
+        transferred += read as u64;
+        if transferred - reported >= PROGRESS_INTERVAL {
+            reported = transferred;
+            let _ = progress.send(TransferProgress::File {
+                name: file.name.clone(),
+                transferred,
+                size: file.size,
+            });
+        }
+    }
+    remote_file.shutdown().await?;
+
+    let (local, remote) =
+        tokio::try_join!(local_sha256(&file.path), remote_sha256(backend, &part_path))?;
+    if local != remote {
+        let _ = backend.remove(&part_path).await;
+        return Err(AppError::io(format!("Checksum mismatch for {}", file.name)));
+    }
+    if backend.exists(&remote_path).await? {
+        backend.remove(&remote_path).await?;
+    }
+    backend.rename(&part_path, &remote_path).await?;
+    Ok(transferred - offset)
+}
+
+/// Whether `file` already exists at `remote_path`: same size and, if checksums
+/// are verified, the same SHA-256, otherwise modified after the local file.
+async fn is_uploaded(
+    backend: &Backend,
+    file: &FastqFile,
+    remote_path: &str,
+    options: &TransferOptions,
+) -> Result<bool, AppError> {
+    let Ok(metadata) = backend.stat(remote_path).await else {
+        return Ok(false);
+    };
+    if metadata.size != file.size {
+        return Ok(false);
+    }
+    if options.verify_checksums {
+        let (local, remote) = tokio::try_join!(
+            local_sha256(&file.path),
+            remote_sha256(backend, remote_path)
+        )?;
+        return Ok(local == remote);
+    }
+    let local_mtime = std::fs::metadata(&file.path)?
+        .modified()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    Ok(metadata
+        .mtime
+        .is_some_and(|mtime| u64::from(mtime) >= local_mtime))
+}
+
+/// Uploads all `files` to `remote_dir`, skipping files that were uploaded
+/// before, with up to `options.concurrency` files in flight.
+pub async fn upload_fastq_files(
+    backend: &Backend,
+    files: Vec<FastqFile>,
+    remote_dir: &str,
+    options: &TransferOptions,
+    progress: &UnboundedSender<TransferProgress>,
+) -> Result<TransferSummary, AppError> {
+    let mut pending = Vec::new();
+    let mut skipped = 0;
+    for file in files {
+        let remote_path = format!("{}/{}", remote_dir, file.name);
+        if is_uploaded(backend, &file, &remote_path, options).await? {
+            skipped += 1;
+        } else {
+            pending.push(file);
+        }
+    }
+
+    let _ = progress.send(TransferProgress::Started {
+        files: pending.len(),
+        bytes: pending.iter().map(|file| file.size).sum(),
+        skipped,
+    });
+
+    let mut summary = TransferSummary {
+        skipped,
+        ..TransferSummary::default()
+    };
+    let mut uploads = stream::iter(&pending)
+        .map(|file| async move {
+            let _ = progress.send(TransferProgress::File {
+                name: file.name.clone(),
+                transferred: 0,
+                size: file.size,
+            });
+            let bytes = upload_fastq_file(backend, file, remote_dir, progress).await?;
+            let _ = progress.send(TransferProgress::FileDone {
+                name: file.name.clone(),
+            });
+            Ok::<u64, AppError>(bytes)
+        })
+        .buffer_unordered(options.concurrency.max(1));
+    while let Some(result) = uploads.next().await {
+        summary.bytes += result?;
+        summary.transferred += 1;
+    }
+    Ok(summary)
+}
@@ -1,102 +1,71 @@
+use super::backend::{Backend, RemoteBackend};
 use super::config::TbguiConfig;
+use super::ssh::list_jobs;
 use super::types::AppError;
-use crate::RESULT_DIR_LOCAL;
-use async_ssh2_tokio::client::Client;
-use directories_next::UserDirs;
-use russh_sftp::{client::SftpSession, protocol::OpenFlags};
-use std::fs::{self, OpenOptions};
-use std::io::Write;
-use std::path::Path;
-use std::path::PathBuf;
-use tokio::fs::{create_dir_all, File};
-use tokio::io::AsyncReadExt;
-use tokio::io::AsyncWriteExt;
 
-pub async fn download_file(
-    sftp: &SftpSession,
-    remote_file_path: &str,
-    local_file_path: &PathBuf,
-) -> Result<(), async_ssh2_tokio::Error> {
-    let mut remote_file = sftp
-        .open_with_flags(remote_file_path, OpenFlags::READ)
-        .await?;
-    if let Some(parent) = Path::new(local_file_path).parent() {
-        create_dir_all(parent).await?;
-    }
-    let mut local_file = File::create(local_file_path.clone()).await?;
-    let mut buffer = [0u8; 4096];
-
-    loop {
-        let n = remote_file.read(&mut buffer).await?;
-        if n == 0 {
-            break; // End of file
-        }
-        local_file.write_all(&buffer[..n]).await?;
-    }
-    Ok(())
+/// Sample a result file belongs to: the name up to the first `.` or `_`,
+/// e.g. `ERR123` for `ERR123.results.json`.
+pub fn sample_of(file_name: &str) -> &str {
+    file_name.split(['.', '_']).next().unwrap_or(file_name)
 }
 
-pub async fn check_if_running(client: &Client, config: &TbguiConfig) -> Result<bool, AppError> {
-    let username = config
-        .username
-        .as_deref()
-        .ok_or_else(|| AppError::Network("Username is not set in the configuration".to_string()))?;
+/// Returns true if `file_name` is a result file of one of `samples`,
+/// e.g. `ERR123.results.docx` for sample `ERR123`.
+pub fn belongs_to_sample(file_name: &str, samples: &[String]) -> bool {
+    let sample = sample_of(file_name);
+    samples.iter().any(|s| s == sample)
+}
 
-    let command_check_running = format!("squeue -u {}", username);
-    let commandexecutedresult_check_if_running = client.execute(&command_check_running).await?;
+/// Quotes `value` for use as a single word in a POSIX shell command.
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
 
-    let running = commandexecutedresult_check_if_running
-        .stdout
-        .contains(username);
+/// Formats a byte count with binary units, e.g. `1.5 GiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
 
-    Ok(running)
+/// Formats a remote modification time as local date and time.
+pub fn format_mtime(mtime: u32) -> String {
+    chrono::DateTime::from_timestamp(i64::from(mtime), 0)
+        .map(|time| {
+            time.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
 }
 
-pub async fn check_if_dir_exists(client: &Client, remote_raw_dir: &str) -> Result<(), AppError> {
-    let command = format!("test -d {} && echo 'exists'", remote_raw_dir);
+pub async fn check_if_running(backend: &Backend, config: &TbguiConfig) -> Result<bool, AppError> {
+    Ok(!list_jobs(backend, config).await?.is_empty())
+}
 
-    let result = client.execute(&command).await.map_err(|e| {
-        let msg = format!("Failed to check if remote directory exists: {:?}", e);
-        log_error(&msg);
-        AppError::Network(msg)
+pub async fn check_if_dir_exists(backend: &Backend, remote_raw_dir: &str) -> Result<(), AppError> {
+    let is_dir = async {
+        Ok(backend.exists(remote_raw_dir).await? && backend.stat(remote_raw_dir).await?.is_dir)
+    }
+    .await
+    .inspect_err(|e: &AppError| {
+        tracing::error!("failed to check if remote directory exists: {e}");
     })?;
 
-    if result.stdout.trim() != "exists" {
+    if !is_dir {
         let msg = format!("Remote directory does not exist: {:?}", remote_raw_dir);
-        log_error(&msg);
-        Err(AppError::Network(msg))
+        tracing::error!("{msg}");
+        Err(AppError::config_missing(msg))
     } else {
         Ok(())
     }
 }
-
-pub fn log_error(message: &str) {
-    let log_dir = UserDirs::new()
-        .expect("Failed to get user directories")
-        .home_dir()
-        .join(RESULT_DIR_LOCAL);
-    if !log_dir.exists() {
-        std::fs::create_dir_all(&log_dir).expect("Failed to create RESULT_DIR_LOCAL");
-    }
-    let error_file = log_dir.join("error.log");
-    let mut file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&error_file)
-        .expect("Failed to open or create log file");
-    writeln!(file, "{}", message).expect("Failed to write to log file");
-}
-
-pub fn delete_log_file() {
-    let error_file = UserDirs::new()
-        .unwrap()
-        .home_dir()
-        .join(RESULT_DIR_LOCAL)
-        .join("error.log");
-    if error_file.exists() && fs::remove_file(&error_file).is_err() {
-        log_error(&format!(
-            "Failed to delete error log file: {:?}",
-            error_file
-        ));
-    }
-}
@@ -0,0 +1,101 @@
+use super::backend::{Backend, RemoteBackend};
+use super::config::TbguiConfig;
+use super::jobs::{Job, Scheduler};
+use super::ssh::list_jobs;
+use super::types::AppError;
+use super::upload::parse_fastq_name;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// What a poll of the raw reads directory found.
+#[derive(Clone, Debug, Default)]
+pub struct WatchPoll {
+    /// Name and size of every file in `remote_raw_dir`.
+    pub files: Vec<(String, u64)>,
+    /// Jobs in the queue, only queried while submitted jobs are watched.
+    pub queue: Option<Vec<Job>>,
+}
+
+/// New reads and automatically submitted jobs, kept between polls.
+#[derive(Clone, Debug, Default)]
+pub struct Watch {
+    /// Sizes of the reads at the last poll, `None` before the first one.
+    sizes: Option<HashMap<String, u64>>,
+    /// Samples complete at the first poll or reported since.
+    seen: HashSet<String>,
+    /// Submitted jobs and their samples, downloaded once they left the queue.
+    pub jobs: Vec<(String, Vec<String>)>,
+}
+
+impl Watch {
+    /// Records the reads of a poll and returns the samples that are new and
+    /// complete: both files present with the same size as at the previous
+    /// poll. Pairs complete at the first poll are taken as known.
+    pub fn detect(&mut self, files: Vec<(String, u64)>) -> Vec<String> {
+        let previous = self.sizes.take();
+        let sizes: HashMap<String, u64> = files.into_iter().collect();
+
+        // Per sample and mate, whether the file is stable.
+        let mut pairs: BTreeMap<&str, [Option<bool>; 2]> = BTreeMap::new();
+        for (name, size) in &sizes {
+            if let Some((sample, mate)) = parse_fastq_name(name) {
+                let stable = previous
+                    .as_ref()
+                    .is_some_and(|previous| previous.get(name) == Some(size));
+                pairs.entry(sample).or_default()[usize::from(mate - 1)] = Some(stable);
+            }
+        }
+
+        let mut new = Vec::new();
+        for (sample, [first, second]) in pairs {
+            let Some(stable) = first.zip(second) else {
+                continue;
+            };
+            if previous.is_none() {
+                self.seen.insert(sample.to_string());
+            } else if stable == (true, true) && self.seen.insert(sample.to_string()) {
+                new.push(sample.to_string());
+            }
+        }
+        self.sizes = Some(sizes);
+        new
+    }
+
+    /// Removes and returns the watched jobs that are no longer in `queue`.
+    pub fn finished(
+        &mut self,
+        queue: &[Job],
+        scheduler: &dyn Scheduler,
+    ) -> Vec<(String, Vec<String>)> {
+        let (finished, running): (Vec<_>, Vec<_>) = self
+            .jobs
+            .drain(..)
+            .partition(|(id, _)| !queue.iter().any(|job| scheduler.is_task_of(&job.id, id)));
+        self.jobs = running;
+        finished
+    }
+}
+
+/// Lists the raw reads with their sizes and, if `queue` is set, the jobs
+/// of the user.
+pub async fn poll(
+    backend: &Backend,
+    config: &TbguiConfig,
+    queue: bool,
+) -> Result<WatchPoll, AppError> {
+    let remote_raw_dir = config.remote_raw_dir.as_deref().ok_or_else(|| {
+        AppError::config_missing("Remote rawreads directory is not set in the configuration")
+    })?;
+    let files = backend
+        .read_dir(remote_raw_dir)
+        .await?
+        .into_iter()
+        .filter(|entry| entry.metadata.is_file)
+        .map(|entry| (entry.name, entry.metadata.size))
+        .collect();
+    let queue = if queue {
+        Some(list_jobs(backend, config).await?)
+    } else {
+        None
+    };
+    Ok(WatchPoll { files, queue })
+}
@@ -0,0 +1,268 @@
+use crate::app::backend::Backend;
+use crate::app::config::TbguiConfig;
+use crate::app::profile::Profile;
+use crate::app::ssh::{
+    cancel_job, delete_results, download_results, fetch_results, job_logs, list_jobs,
+    run_tbprofiler,
+};
+use crate::app::transfer::{DownloadRequest, ResultFileType};
+use crate::app::types::AppError;
+use crate::model::profile::DRUGS;
+use crate::model::summary::{summary_headers, summary_rows, SortColumn};
+use crate::model::Sample;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use tokio::sync::mpsc::unbounded_channel;
+
+/// Without a subcommand the GUI is started. Subcommands use the saved
+/// configuration, print JSON on stdout and errors as JSON on stderr.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Group profile (TOML or JSON) applied on top of the saved configuration.
+    #[arg(long, global = true)]
+    pub profile: Option<PathBuf>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Whether the arguments are meant for the CLI: a subcommand, help, the
+/// version or a profile. Other arguments that fail to parse start the GUI.
+pub fn requested() -> bool {
+    let cli = Cli::command();
+    std::env::args().skip(1).any(|arg| {
+        cli.find_subcommand(&arg).is_some()
+            || matches!(
+                arg.as_str(),
+                "-h" | "--help" | "-V" | "--version" | "--profile"
+            )
+            || arg.starts_with("--profile=")
+    })
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Samples with reads in the raw reads directory.
+    Samples {
+        #[command(subcommand)]
+        command: SamplesCommand,
+    },
+    /// Submits tb-profiler for the given samples.
+    Run {
+        #[arg(long, required = true, num_args = 1.., value_delimiter = ',')]
+        samples: Vec<String>,
+    },
+    /// Jobs of the configured user in the queue.
+    Jobs {
+        #[command(subcommand)]
+        command: Option<JobsCommand>,
+    },
+    /// Result files in the out directory.
+    Results {
+        #[command(subcommand)]
+        command: ResultsCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SamplesCommand {
+    /// Lists the samples.
+    List,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum JobsCommand {
+    /// Cancels a job.
+    Cancel { job: String },
+    /// Prints the output of every task of a job.
+    Logs { job: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ResultsCommand {
+    /// Downloads result files into the download directory, skipping files
+    /// that are up to date.
+    Download {
+        /// Samples to download, all if not given.
+        #[arg(long, num_args = 1.., value_delimiter = ',')]
+        samples: Vec<String>,
+        /// File types to download, all if not given.
+        #[arg(long = "type", num_args = 1.., value_delimiter = ',', value_parser = parse_file_type)]
+        types: Vec<ResultFileType>,
+    },
+    /// Lineage, drug resistance type and resistance mutations per sample.
+    Summary {
+        #[arg(long, value_enum, default_value_t = SummaryFormat::Json)]
+        format: SummaryFormat,
+    },
+    /// Moves the result files of samples to the trash directory.
+    Delete {
+        #[arg(long, required = true, num_args = 1.., value_delimiter = ',')]
+        samples: Vec<String>,
+        /// Removes the files instead of moving them to the trash.
+        #[arg(long)]
+        permanent: bool,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum SummaryFormat {
+    Json,
+    Csv,
+}
+
+fn parse_file_type(value: &str) -> Result<ResultFileType, String> {
+    ResultFileType::all()
+        .iter()
+        .find(|file_type| file_type.label() == value)
+        .copied()
+        .ok_or_else(|| {
+            let labels: Vec<&str> = ResultFileType::all().iter().map(|t| t.label()).collect();
+            format!("expected one of {}", labels.join(", "))
+        })
+}
+
+/// Runs `command` to completion and prints its result.
+pub fn run(profile: Option<PathBuf>, command: Command) -> ExitCode {
+    let result = tokio::runtime::Runtime::new()
+        .map_err(AppError::from)
+        .and_then(|runtime| runtime.block_on(execute(profile, command)));
+    match result {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            let error = json!({
+                "error": err.to_string(),
+                "kind": format!("{:?}", err.kind),
+                "causes": err.causes,
+            });
+            eprintln!("{error:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn execute(profile: Option<PathBuf>, command: Command) -> Result<String, AppError> {
+    let mut config = TbguiConfig::config();
+    if let Some(path) = profile {
+        config = Profile::read(&path).await?.apply(&config);
+    }
+    if config.needs_setup() {
+        return Err(AppError::config_missing(
+            "tbgui is not set up yet, run the setup assistant in the GUI first",
+        ));
+    }
+    let backend = Backend::connect(&config).await?;
+
+    let output = match command {
+        Command::Results { command } => return results(&backend, &config, command).await,
+        Command::Samples {
+            command: SamplesCommand::List,
+        } => {
+            let samples = Sample::get_raw_reads(&backend, &config).await?;
+            json!(samples
+                .iter()
+                .map(|sample| sample.title.as_str())
+                .collect::<Vec<_>>())
+        }
+        Command::Run { samples } => {
+            let output =
+                run_tbprofiler(&backend, samples.len(), samples.join(" "), &config).await?;
+            json!({
+                "job_id": config.scheduler.driver().submitted_id(&output),
+                "samples": samples,
+                "output": output.trim(),
+            })
+        }
+        Command::Jobs { command: None } => json!(list_jobs(&backend, &config).await?),
+        Command::Jobs {
+            command: Some(JobsCommand::Cancel { job }),
+        } => {
+            cancel_job(&backend, &config, &job).await?;
+            json!({ "cancelled": job })
+        }
+        Command::Jobs {
+            command: Some(JobsCommand::Logs { job }),
+        } => return job_logs(&backend, &config, &job).await,
+    };
+    Ok(format!("{output:#}"))
+}
+
+async fn results(
+    backend: &Backend,
+    config: &TbguiConfig,
+    command: ResultsCommand,
+) -> Result<String, AppError> {
+    let output = match command {
+        ResultsCommand::Download { samples, types } => {
+            let request = DownloadRequest {
+                samples: (!samples.is_empty()).then_some(samples),
+                file_types: if types.is_empty() {
+                    ResultFileType::all().to_vec()
+                } else {
+                    types
+                },
+            };
+            let (sender, _receiver) = unbounded_channel();
+            let summary = download_results(backend, config, &request, &sender).await?;
+            json!({
+                "directory": config.download_dir(),
+                "transferred": summary.transferred,
+                "skipped": summary.skipped,
+                "bytes": summary.bytes,
+            })
+        }
+        ResultsCommand::Summary { format } => {
+            let profiles = fetch_results(backend, config).await?;
+            let profiles: Vec<_> = profiles.iter().collect();
+            let rows = summary_rows(&profiles, SortColumn::Sample, true, "", None);
+            match format {
+                SummaryFormat::Json => rows
+                    .iter()
+                    .map(|row| {
+                        let drugs: serde_json::Map<String, Value> = DRUGS
+                            .iter()
+                            .zip(&row.drugs)
+                            .map(|(drug, mutations)| (drug.to_string(), json!(mutations)))
+                            .collect();
+                        json!({
+                            "sample": row.sample,
+                            "main_lineage": row.main_lineage,
+                            "sub_lineage": row.sub_lineage,
+                            "drtype": row.drtype.as_str(),
+                            "drugs": drugs,
+                            "other": row.other,
+                        })
+                    })
+                    .collect(),
+                SummaryFormat::Csv => {
+                    let mut writer = csv::Writer::from_writer(Vec::new());
+                    let csv_error =
+                        |e: csv::Error| AppError::io(format!("Failed to write CSV: {e}"));
+                    writer.write_record(summary_headers()).map_err(csv_error)?;
+                    for row in &rows {
+                        writer.write_record(row.record()).map_err(csv_error)?;
+                    }
+                    let data = writer
+                        .into_inner()
+                        .map_err(|e| AppError::io(format!("Failed to write CSV: {e}")))?;
+                    return Ok(String::from_utf8_lossy(&data).trim_end().to_string());
+                }
+            }
+        }
+        ResultsCommand::Delete { samples, permanent } => {
+            let summary = delete_results(backend, config, samples, permanent).await?;
+            json!({
+                "files": summary.files,
+                "bytes": summary.bytes,
+                "skipped": summary.skipped,
+                "trash_dir": summary.trash_dir,
+            })
+        }
+    };
+    Ok(format!("{output:#}"))
+}
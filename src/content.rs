@@ -12,21 +12,40 @@ use cosmic::{
     theme, widget, Apply, Element,
 };
 use slotmap::{DefaultKey, SecondaryMap, SlotMap};
+use std::collections::{HashMap, HashSet};
 
 pub struct Content {
     tasks: SlotMap<DefaultKey, Sample>,
     task_input_ids: SecondaryMap<DefaultKey, widget::Id>,
+    lists: Vec<List>,
+    list_names: Vec<String>,
+    selected_list: Option<usize>,
+    profiles: HashSet<String>,
+    /// FASTQ pre-flight results by sample, with the failure reason if any.
+    fastq_checks: HashMap<String, Option<String>>,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Complete(DefaultKey, bool),
     SetItems(Vec<Sample>),
+    SetLists(Vec<List>),
+    SelectList(usize),
+    AddToList,
+    RunChecked,
+    CheckChecked,
+    SetFastqChecks(HashMap<String, Option<String>>),
+    SetProfiles(HashSet<String>),
+    OpenProfile(DefaultKey),
 }
 
 pub enum TaskMessage {
     Get(String),
     Update(Sample),
+    AddToList(String, Vec<String>),
+    Run(Vec<String>),
+    CheckFastq(Vec<String>),
+    OpenProfile(String),
 }
 
 impl Content {
@@ -34,17 +53,48 @@ impl Content {
         Self {
             tasks: SlotMap::new(),
             task_input_ids: SecondaryMap::new(),
+            lists: Vec::new(),
+            list_names: Vec::new(),
+            selected_list: None,
+            profiles: HashSet::new(),
+            fastq_checks: HashMap::new(),
         }
     }
 
+    pub fn checked_samples(&self) -> Vec<String> {
+        self.tasks
+            .values()
+            .filter(|task| task.status == Status::Completed)
+            .map(|task| task.title.clone())
+            .collect()
+    }
+
     fn list_header<'a>(&'a self) -> Element<'a, Message> {
         let spacing = theme::active().cosmic().spacing;
-        let default_icon = emojis::get_by_shortcode("pencil").unwrap().to_string();
+        let has_checked = self
+            .tasks
+            .values()
+            .any(|task| task.status == Status::Completed);
+
+        let list_dropdown =
+            widget::dropdown(&self.list_names, self.selected_list, Message::SelectList);
+        let add_to_list = widget::button::standard(fl!("add-to-batch")).on_press_maybe(
+            (has_checked && self.selected_list.is_some()).then_some(Message::AddToList),
+        );
+        let check = widget::button::standard(fl!("check-reads"))
+            .on_press_maybe(has_checked.then_some(Message::CheckChecked));
+        let run = widget::button::suggested(fl!("run-selected"))
+            .on_press_maybe(has_checked.then_some(Message::RunChecked));
 
-        widget::row::with_capacity(3)
+        widget::row::with_capacity(5)
             .align_y(Alignment::Center)
             .spacing(spacing.space_s)
             .padding([spacing.space_none, spacing.space_xxs])
+            .push(list_dropdown)
+            .push(add_to_list)
+            .push(widget::horizontal_space())
+            .push(check)
+            .push(run)
             .into()
     }
 
@@ -66,13 +116,34 @@ impl Content {
 
             let task_item_text = widget::text::title1(item.title.clone());
 
-            let row = widget::row::with_capacity(4)
+            let mut row = widget::row::with_capacity(5)
                 .align_y(Alignment::Center)
                 .spacing(spacing.space_xxs)
                 .padding([spacing.space_xxxs, spacing.space_xxs])
                 .push(item_checkbox)
                 .push(task_item_text);
 
+            match self.fastq_checks.get(&item.title) {
+                Some(Some(failure)) => {
+                    row = row.push(widget::tooltip(
+                        get_icon("dialog-warning-symbolic", 16),
+                        widget::text(failure.clone()),
+                        widget::tooltip::Position::Top,
+                    ));
+                }
+                Some(None) => {
+                    row = row.push(get_icon("emblem-ok-symbolic", 16));
+                }
+                None => {}
+            }
+
+            if self.profiles.contains(&item.title) {
+                row = row.push(widget::horizontal_space()).push(
+                    widget::button::icon(icons::get_handle("detail", 16))
+                        .on_press(Message::OpenProfile(id)),
+                );
+            }
+
             items = items.add(row);
         }
 
@@ -119,6 +190,44 @@ impl Content {
                     self.task_input_ids.insert(id, widget::Id::unique());
                 }
             }
+            Message::SetLists(lists) => {
+                let selected_id = self
+                    .selected_list
+                    .and_then(|index| self.lists.get(index))
+                    .map(|list| list.id.clone());
+                self.list_names = lists.iter().map(|list| list.name.clone()).collect();
+                self.selected_list =
+                    selected_id.and_then(|id| lists.iter().position(|list| list.id == id));
+                self.lists = lists;
+            }
+            Message::SelectList(index) => {
+                self.selected_list = Some(index);
+            }
+            Message::AddToList => {
+                if let Some(list) = self.selected_list.and_then(|index| self.lists.get(index)) {
+                    tasks.push(TaskMessage::AddToList(
+                        list.id.clone(),
+                        self.checked_samples(),
+                    ));
+                }
+            }
+            Message::RunChecked => {
+                tasks.push(TaskMessage::Run(self.checked_samples()));
+            }
+            Message::CheckChecked => {
+                tasks.push(TaskMessage::CheckFastq(self.checked_samples()));
+            }
+            Message::SetFastqChecks(checks) => {
+                self.fastq_checks = checks;
+            }
+            Message::SetProfiles(profiles) => {
+                self.profiles = profiles;
+            }
+            Message::OpenProfile(id) => {
+                if let Some(task) = self.tasks.get(id) {
+                    tasks.push(TaskMessage::OpenProfile(task.title.clone()));
+                }
+            }
             Message::Complete(id, complete) => {
                 let task = self.tasks.get_mut(id);
                 if let Some(task) = task {
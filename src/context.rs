@@ -4,6 +4,7 @@ use crate::fl;
 pub enum ContextPage {
     About,
     Settings,
+    Profile(String),
 }
 
 impl ContextPage {
@@ -11,6 +12,7 @@ impl ContextPage {
         match self {
             Self::About => fl!("about"),
             Self::Settings => fl!("settings"),
+            Self::Profile(sample) => sample.clone(),
         }
     }
 }
@@ -1,44 +1,240 @@
-use crate::app::types::AppError;
+use crate::app::types::{AppError, ErrorKind};
+use crate::app::utils::format_size;
 use crate::{app::Message, fl};
-use cosmic::widget;
+use cosmic::widget::{self, segmented_button};
+use cosmic::Apply;
+use std::path::PathBuf;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DialogPage {
-    Info(AppError),
+    Info {
+        error: AppError,
+        details: bool,
+        /// There is an action to run again.
+        retry: bool,
+    },
+    NewList(String),
+    DeleteList(Option<segmented_button::Entity>),
+    Submitted(String),
+    TemplateWarnings(PathBuf, Vec<String>),
+    /// Samples whose results are archived and then removed from the out directory.
+    ArchiveResults(Vec<String>),
+    /// Path of a group profile that publishing would replace.
+    ReplaceProfile(String),
+    /// Samples that passed the FASTQ check and the failed ones with the reason.
+    FastqFailed {
+        samples: Vec<String>,
+        failed: Vec<(String, String)>,
+    },
+    /// Samples whose estimated output of `required` bytes exceeds the space available.
+    InsufficientSpace {
+        samples: Vec<String>,
+        required: u64,
+        available: u64,
+    },
 }
 
 impl DialogPage {
-    pub fn view(&self, _text_input_id: &widget::Id) -> widget::Dialog<Message> {
-        let _spacing = cosmic::theme::active().cosmic().spacing;
+    pub fn view(&self, text_input_id: &widget::Id) -> widget::Dialog<Message> {
+        let spacing = cosmic::theme::active().cosmic().spacing;
 
         match self {
-            DialogPage::Info(error) => {
-                let mut content = widget::column::with_capacity(2).spacing(12);
-
-                match error {
-                    AppError::Network(body) => {
-                        let title = widget::text::title4("Network error");
-                        content = content.push(title);
-                        content = content.push(widget::text(body));
-                    }
-                    AppError::NoItemsChecked(body) => {
-                        let title =
-                            widget::text::title4("Cannot run tbprofiler with zero items checked");
-                        content = content.push(title);
-                        content = content.push(widget::text::body(body));
-                    }
-                    AppError::IO(body) => {
-                        let title = widget::text::title4("IO error");
-                        content = content.push(title);
-                        content = content.push(widget::text(body));
+            DialogPage::Info {
+                error,
+                details,
+                retry,
+            } => {
+                let mut content = widget::column::with_capacity(5)
+                    .spacing(12)
+                    .push(widget::text::title4(error_title(&error.kind)))
+                    .push(widget::text::body(error.to_string()));
+                if let Some(hint) = error_hint(&error.kind) {
+                    content = content.push(widget::text::body(hint));
+                }
+                let text = error_details(error);
+                if !text.is_empty() {
+                    let icon = if *details {
+                        "go-down-symbolic"
+                    } else {
+                        "go-next-symbolic"
+                    };
+                    content = content.push(
+                        widget::row::with_capacity(3)
+                            .spacing(spacing.space_xxs)
+                            .align_y(cosmic::iced::Alignment::Center)
+                            .push(
+                                widget::button::text(fl!("error-details"))
+                                    .leading_icon(widget::icon::from_name(icon))
+                                    .on_press(Message::DialogUpdate(DialogPage::Info {
+                                        error: error.clone(),
+                                        details: !details,
+                                        retry: *retry,
+                                    })),
+                            )
+                            .push(widget::horizontal_space())
+                            .push(
+                                widget::button::text(fl!("copy"))
+                                    .leading_icon(widget::icon::from_name("edit-copy-symbolic"))
+                                    .on_press(Message::CopyToClipboard(text.clone())),
+                            ),
+                    );
+                    if *details {
+                        content = content.push(
+                            widget::text::monotext(text)
+                                .apply(widget::scrollable)
+                                .height(cosmic::iced::Length::Shrink),
+                        );
                     }
                 }
-                widget::dialog()
+                let mut dialog = widget::dialog()
                     .secondary_action(
                         widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
                     )
-                    .control(content)
+                    .control(content);
+                if *retry {
+                    dialog = dialog.primary_action(
+                        widget::button::suggested(fl!("retry")).on_press(Message::Retry),
+                    );
+                }
+                dialog
             }
+            DialogPage::NewList(name) => widget::dialog()
+                .title(fl!("create-list"))
+                .primary_action(
+                    widget::button::suggested(fl!("save")).on_press_maybe(
+                        (!name.trim().is_empty()).then_some(Message::DialogComplete),
+                    ),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                )
+                .control(
+                    widget::column::with_children(vec![
+                        widget::text::body(fl!("list-name")).into(),
+                        widget::text_input("", name.as_str())
+                            .id(text_input_id.clone())
+                            .on_input(move |name| Message::DialogUpdate(DialogPage::NewList(name)))
+                            .into(),
+                    ])
+                    .spacing(spacing.space_xxs),
+                ),
+            DialogPage::DeleteList(_) => widget::dialog()
+                .title(fl!("delete-list"))
+                .body(fl!("delete-list-confirm"))
+                .primary_action(
+                    widget::button::destructive(fl!("delete")).on_press(Message::DialogComplete),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                ),
+            DialogPage::Submitted(stdout) => widget::dialog()
+                .title(fl!("job-submitted"))
+                .body(stdout.trim().to_string())
+                .primary_action(
+                    widget::button::suggested(fl!("ok")).on_press(Message::DialogCancel),
+                ),
+            DialogPage::TemplateWarnings(_, warnings) => widget::dialog()
+                .title(fl!("template-warnings"))
+                .body(warnings.join("\n"))
+                .primary_action(
+                    widget::button::suggested(fl!("upload-anyway"))
+                        .on_press(Message::DialogComplete),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                ),
+            DialogPage::FastqFailed { samples, failed } => {
+                let failures: Vec<String> = failed
+                    .iter()
+                    .map(|(sample, failure)| format!("{sample}: {failure}"))
+                    .collect();
+                let mut dialog = widget::dialog()
+                    .title(fl!("fastq-failed"))
+                    .body(failures.join("\n"))
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    );
+                if !samples.is_empty() {
+                    dialog = dialog.primary_action(
+                        widget::button::suggested(fl!("run-passed", count = samples.len()))
+                            .on_press(Message::DialogComplete),
+                    );
+                }
+                dialog
+            }
+            DialogPage::InsufficientSpace {
+                required,
+                available,
+                ..
+            } => widget::dialog()
+                .title(fl!("insufficient-space"))
+                .body(fl!(
+                    "remote-space-warning",
+                    required = format_size(*required),
+                    available = format_size(*available)
+                ))
+                .primary_action(
+                    widget::button::destructive(fl!("run-anyway"))
+                        .on_press(Message::DialogComplete),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                ),
+            DialogPage::ArchiveResults(samples) => widget::dialog()
+                .title(fl!("archive-results"))
+                .body(fl!("archive-confirm", samples = samples.len()))
+                .primary_action(
+                    widget::button::destructive(fl!("archive")).on_press(Message::DialogComplete),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                ),
+            DialogPage::ReplaceProfile(path) => widget::dialog()
+                .title(fl!("profile-replace"))
+                .body(fl!("profile-replace-confirm", path = path.clone()))
+                .primary_action(
+                    widget::button::destructive(fl!("replace")).on_press(Message::DialogComplete),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                ),
         }
     }
 }
+
+fn error_title(kind: &ErrorKind) -> String {
+    match kind {
+        ErrorKind::Auth => fl!("error-auth"),
+        ErrorKind::HostKey => fl!("error-host-key"),
+        ErrorKind::Timeout => fl!("error-timeout"),
+        ErrorKind::Network => fl!("error-network"),
+        ErrorKind::RemoteCommand => fl!("error-remote-command"),
+        ErrorKind::Sftp => fl!("error-sftp"),
+        ErrorKind::Io => fl!("error-io"),
+        ErrorKind::ConfigMissing => fl!("error-config-missing"),
+        ErrorKind::Scheduler => fl!("error-scheduler"),
+        ErrorKind::NoItemsChecked => fl!("error-no-items-checked"),
+    }
+}
+
+/// The failed command and the cause chain of `error`, for the details section.
+fn error_details(error: &AppError) -> String {
+    let mut details = Vec::new();
+    if let Some(output) = &error.output {
+        details.push(output.details());
+    }
+    if !error.causes.is_empty() {
+        details.push(fl!("error-caused-by", causes = error.causes.join("\n")));
+    }
+    details.join("\n\n")
+}
+
+/// What the user can do about an error of `kind`, if anything specific.
+fn error_hint(kind: &ErrorKind) -> Option<String> {
+    match kind {
+        ErrorKind::Auth => Some(fl!("error-auth-hint")),
+        ErrorKind::HostKey => Some(fl!("error-host-key-hint")),
+        ErrorKind::ConfigMissing => Some(fl!("error-config-missing-hint")),
+        _ => None,
+    }
+}
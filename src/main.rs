@@ -2,18 +2,49 @@ use app::{
     settings::{flags, settings},
     Tbgui,
 };
+use clap::Parser;
+use std::process::ExitCode;
 
 mod actions;
 mod app;
+mod cli;
 mod content;
 mod context;
 mod dialog;
 mod model;
+mod setup;
+mod summary;
+#[cfg(all(test, unix))]
+mod tests;
 mod views;
 
 const RESULT_DIR_LOCAL: &str = "tb-profiler-results";
 const DEFAULT_TEMPLATE_FILENAME_LOCAL: &str = "default_template.docx";
 
-fn main() -> cosmic::iced::Result {
-    cosmic::app::run::<Tbgui>(settings(), flags())
+fn main() -> ExitCode {
+    let ignored = match cli::Cli::try_parse() {
+        Ok(cli::Cli {
+            profile,
+            command: Some(command),
+        }) => {
+            // stderr is reserved for the JSON errors of the CLI.
+            let _log_guard = app::logging::init(false);
+            return cli::run(profile, command);
+        }
+        Ok(_) => None,
+        Err(err) if cli::requested() => err.exit(),
+        // Launchers may pass arguments of their own, the GUI ignores them.
+        Err(err) => Some(err),
+    };
+    let _log_guard = app::logging::init(true);
+    if let Some(err) = ignored {
+        tracing::warn!("ignoring command line arguments: {}", err.kind());
+    }
+    match cosmic::app::run::<Tbgui>(settings(), flags()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            tracing::error!("{err}");
+            ExitCode::FAILURE
+        }
+    }
 }
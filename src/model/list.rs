@@ -7,6 +7,7 @@ pub struct List {
     pub name: String,
     pub description: String,
     pub icon: Option<String>,
+    pub samples: Vec<String>,
 }
 
 unsafe impl Send for List {}
@@ -28,6 +29,29 @@ impl List {
             name: name.to_string(),
             description: String::new(),
             icon: Some(emojis::get_by_shortcode("pencil").unwrap().to_string()),
+            samples: Vec::new(),
+        }
+    }
+
+    pub fn add_samples(&mut self, samples: &[String]) {
+        for sample in samples {
+            if !self.samples.contains(sample) {
+                self.samples.push(sample.clone());
+            }
+        }
+    }
+
+    pub fn remove_sample(&mut self, sample: &str) {
+        self.samples.retain(|s| s != sample);
+    }
+}
+
+/// Merges `incoming` lists into `lists`, replacing lists with the same id.
+pub fn merge_lists(lists: &mut Vec<List>, incoming: Vec<List>) {
+    for list in incoming {
+        match lists.iter_mut().find(|l| l.id == list.id) {
+            Some(existing) => *existing = list,
+            None => lists.push(list),
         }
     }
 }
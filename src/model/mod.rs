@@ -1,7 +1,9 @@
 mod sample;
 pub use sample::Sample;
 mod list;
-pub use list::List;
+pub use list::{merge_lists, List};
 
 pub mod priority;
+pub mod profile;
 pub mod status;
+pub mod summary;
@@ -0,0 +1,206 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+/// Drugs reported by TB-Profiler, in the order they are usually listed in reports.
+pub const DRUGS: &[&str] = &[
+    "rifampicin",
+    "isoniazid",
+    "ethambutol",
+    "pyrazinamide",
+    "streptomycin",
+    "levofloxacin",
+    "moxifloxacin",
+    "bedaquiline",
+    "linezolid",
+    "clofazimine",
+    "delamanid",
+    "pretomanid",
+    "amikacin",
+    "kanamycin",
+    "capreomycin",
+    "ethionamide",
+    "cycloserine",
+    "para-aminosalicylic_acid",
+];
+
+/// Drug-resistance type as reported in the `drtype` field of a TB-Profiler result.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DrType {
+    #[default]
+    Sensitive,
+    HrTb,
+    RrTb,
+    Mdr,
+    PreXdr,
+    Xdr,
+    Other(String),
+}
+
+impl DrType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            DrType::Sensitive => "Sensitive",
+            DrType::HrTb => "HR-TB",
+            DrType::RrTb => "RR-TB",
+            DrType::Mdr => "MDR-TB",
+            DrType::PreXdr => "Pre-XDR-TB",
+            DrType::Xdr => "XDR-TB",
+            DrType::Other(value) => value,
+        }
+    }
+
+    pub fn from_str_name(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "sensitive" => Self::Sensitive,
+            "hr-tb" => Self::HrTb,
+            "rr-tb" => Self::RrTb,
+            "mdr-tb" | "mdr" => Self::Mdr,
+            "pre-xdr-tb" | "pre-xdr" => Self::PreXdr,
+            "xdr-tb" | "xdr" => Self::Xdr,
+            _ => Self::Other(value.to_string()),
+        }
+    }
+}
+
+impl Serialize for DrType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for DrType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(Self::from_str_name(&value))
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Qc {
+    pub percent_reads_mapped: Option<f64>,
+    pub num_reads_mapped: Option<u64>,
+    pub target_median_depth: Option<f64>,
+    pub genome_median_depth: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Pipeline {
+    pub software_version: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Lineage {
+    pub lineage: String,
+    pub family: Option<String>,
+    pub fraction: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VariantDrug {
+    pub drug: String,
+    /// `confidence` in TB-Profiler 6, `confers` in older versions.
+    #[serde(alias = "confers")]
+    pub confidence: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Variant {
+    pub gene_name: Option<String>,
+    pub gene: Option<String>,
+    pub change: String,
+    pub freq: Option<f64>,
+    #[serde(rename = "type")]
+    pub variant_type: Option<String>,
+    pub drugs: Vec<VariantDrug>,
+}
+
+impl Variant {
+    pub fn gene(&self) -> &str {
+        self.gene_name
+            .as_deref()
+            .or(self.gene.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Short description such as `rpoB p.Ser450Leu (100%)`.
+    pub fn describe(&self) -> String {
+        match self.freq {
+            Some(freq) => format!("{} {} ({:.0}%)", self.gene(), self.change, freq * 100.0),
+            None => format!("{} {}", self.gene(), self.change),
+        }
+    }
+}
+
+/// Resistance call for one drug and the mutations supporting it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DrugCall {
+    pub drug: String,
+    pub mutations: Vec<String>,
+}
+
+impl DrugCall {
+    pub fn is_resistant(&self) -> bool {
+        !self.mutations.is_empty()
+    }
+}
+
+/// Parsed content of a `results/<sample>.results.json` file written by TB-Profiler.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TbProfile {
+    pub id: String,
+    pub timestamp: Option<String>,
+    pub tbprofiler_version: Option<String>,
+    pub pipeline: Option<Pipeline>,
+    pub main_lineage: Option<String>,
+    pub sub_lineage: Option<String>,
+    pub lineage: Vec<Lineage>,
+    pub drtype: DrType,
+    pub dr_variants: Vec<Variant>,
+    pub qc: Qc,
+}
+
+impl TbProfile {
+    pub fn from_json(data: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(data)
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.pipeline
+            .as_ref()
+            .and_then(|pipeline| pipeline.software_version.as_deref())
+            .or(self.tbprofiler_version.as_deref())
+    }
+
+    /// Resistance calls for every drug in [`DRUGS`] followed by any other
+    /// drug that appears in the resistance variants.
+    pub fn drug_calls(&self) -> Vec<DrugCall> {
+        let mut calls: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for variant in &self.dr_variants {
+            for drug in &variant.drugs {
+                calls
+                    .entry(drug.drug.to_lowercase())
+                    .or_default()
+                    .push(variant.describe());
+            }
+        }
+        let mut result: Vec<DrugCall> = DRUGS
+            .iter()
+            .map(|drug| DrugCall {
+                drug: drug.to_string(),
+                mutations: calls.remove(*drug).unwrap_or_default(),
+            })
+            .collect();
+        result.extend(
+            calls
+                .into_iter()
+                .map(|(drug, mutations)| DrugCall { drug, mutations }),
+        );
+        result
+    }
+}
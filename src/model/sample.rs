@@ -1,9 +1,9 @@
 use super::priority::Priority;
 use super::status::Status;
+use crate::app::backend::{Backend, RemoteBackend};
 use crate::app::config::TbguiConfig;
 use crate::app::types::AppError;
-use crate::app::utils::{check_if_dir_exists, log_error};
-use async_ssh2_tokio::client::Client;
+use crate::app::utils::check_if_dir_exists;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use uuid::Uuid;
@@ -39,28 +39,29 @@ impl Sample {
     }
 
     pub async fn get_raw_reads(
-        client: &Client,
+        backend: &Backend,
         config: &TbguiConfig,
     ) -> Result<Vec<Sample>, AppError> {
-        println!("Getting paired reads as items");
+        tracing::debug!("getting paired reads as items");
         let remote_raw_dir = config.remote_raw_dir.as_deref().ok_or_else(|| {
-            AppError::Network(
-                "Remote rawreads directory is not set in the configuration".to_string(),
-            )
+            AppError::config_missing("Remote rawreads directory is not set in the configuration")
         })?;
 
-        check_if_dir_exists(client, remote_raw_dir).await?;
+        check_if_dir_exists(backend, remote_raw_dir).await?;
 
-        let command = format!("ls {}", remote_raw_dir);
-        let result = client.execute(&command).await.map_err(|e| {
-            let msg = format!("Failed to list files in remote directory: {:?}", e);
-            log_error(&msg);
-            AppError::Network(msg)
+        let entries = backend.read_dir(remote_raw_dir).await.inspect_err(|e| {
+            tracing::error!("failed to list files in remote directory: {e}");
         })?;
 
-        let raw_reads: Vec<String> = result.stdout.lines().map(String::from).collect();
+        // Sorted and without hidden files, like `ls` listed them.
+        let mut raw_reads: Vec<String> = entries
+            .into_iter()
+            .map(|entry| entry.name)
+            .filter(|name| !name.starts_with('.'))
+            .collect();
+        raw_reads.sort_unstable();
         let tasks = create_sample_tasks(raw_reads);
-        println!("Tasks: {:?}", tasks);
+        tracing::debug!("tasks: {:?}", tasks);
 
         Ok(tasks)
     }
@@ -0,0 +1,161 @@
+use super::profile::{DrType, TbProfile, DRUGS};
+use std::cmp::Ordering;
+
+/// One row of the cohort summary, equivalent to a line of `tb-profiler collate`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SummaryRow {
+    pub sample: String,
+    pub main_lineage: String,
+    pub sub_lineage: String,
+    pub drtype: DrType,
+    /// Resistance mutations per drug, in the order of [`DRUGS`].
+    pub drugs: Vec<Vec<String>>,
+    /// Mutations for drugs outside [`DRUGS`], as `drug: mutation`.
+    pub other: Vec<String>,
+}
+
+impl From<&TbProfile> for SummaryRow {
+    fn from(profile: &TbProfile) -> Self {
+        let calls = profile.drug_calls();
+        Self {
+            sample: profile.id.clone(),
+            main_lineage: profile.main_lineage.clone().unwrap_or_default(),
+            sub_lineage: profile.sub_lineage.clone().unwrap_or_default(),
+            drtype: profile.drtype.clone(),
+            drugs: DRUGS
+                .iter()
+                .map(|drug| {
+                    calls
+                        .iter()
+                        .find(|call| call.drug == *drug)
+                        .map(|call| call.mutations.clone())
+                        .unwrap_or_default()
+                })
+                .collect(),
+            other: calls
+                .iter()
+                .filter(|call| !DRUGS.contains(&call.drug.as_str()))
+                .flat_map(|call| {
+                    call.mutations
+                        .iter()
+                        .map(|mutation| format!("{}: {mutation}", call.drug))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl SummaryRow {
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || self.sample.to_lowercase().contains(&query)
+            || self.main_lineage.to_lowercase().contains(&query)
+            || self.sub_lineage.to_lowercase().contains(&query)
+            || self.drtype.as_str().to_lowercase().contains(&query)
+    }
+
+    /// Cells as written to CSV/XLSX: mutations per drug, `-` if none.
+    pub fn record(&self) -> Vec<String> {
+        let mut record = vec![
+            self.sample.clone(),
+            self.main_lineage.clone(),
+            self.sub_lineage.clone(),
+            self.drtype.as_str().to_string(),
+        ];
+        record.extend(self.drugs.iter().chain([&self.other]).map(|mutations| {
+            if mutations.is_empty() {
+                "-".to_string()
+            } else {
+                mutations.join(", ")
+            }
+        }));
+        record
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortColumn {
+    #[default]
+    Sample,
+    MainLineage,
+    SubLineage,
+    DrType,
+    Drug(usize),
+    Other,
+}
+
+impl SortColumn {
+    pub fn all() -> Vec<Self> {
+        let mut columns = vec![
+            Self::Sample,
+            Self::MainLineage,
+            Self::SubLineage,
+            Self::DrType,
+        ];
+        columns.extend((0..DRUGS.len()).map(Self::Drug));
+        columns.push(Self::Other);
+        columns
+    }
+
+    pub fn header(&self) -> String {
+        match self {
+            Self::Sample => "sample".to_string(),
+            Self::MainLineage => "main_lineage".to_string(),
+            Self::SubLineage => "sub_lineage".to_string(),
+            Self::DrType => "drtype".to_string(),
+            Self::Drug(index) => DRUGS[*index].to_string(),
+            Self::Other => "other".to_string(),
+        }
+    }
+
+    fn compare(&self, a: &SummaryRow, b: &SummaryRow) -> Ordering {
+        match self {
+            Self::Sample => a.sample.cmp(&b.sample),
+            Self::MainLineage => a.main_lineage.cmp(&b.main_lineage),
+            Self::SubLineage => a.sub_lineage.cmp(&b.sub_lineage),
+            Self::DrType => a.drtype.cmp(&b.drtype),
+            Self::Drug(index) => a.drugs[*index].is_empty().cmp(&b.drugs[*index].is_empty()),
+            Self::Other => a.other.is_empty().cmp(&b.other.is_empty()),
+        }
+        .then_with(|| a.sample.cmp(&b.sample))
+    }
+}
+
+/// Whether `drtype` passes the filter; [`DrType::Other`] matches every
+/// resistance type outside the named ones.
+fn matches_drtype(drtype: &DrType, filter: &DrType) -> bool {
+    match filter {
+        DrType::Other(_) => matches!(drtype, DrType::Other(_)),
+        filter => drtype == filter,
+    }
+}
+
+/// Builds the summary rows from parsed results, sorted and filtered.
+pub fn summary_rows(
+    profiles: &[&TbProfile],
+    sort: SortColumn,
+    ascending: bool,
+    query: &str,
+    drtype: Option<&DrType>,
+) -> Vec<SummaryRow> {
+    let mut rows: Vec<SummaryRow> = profiles
+        .iter()
+        .map(|profile| SummaryRow::from(*profile))
+        .filter(|row| row.matches(query))
+        .filter(|row| drtype.is_none_or(|drtype| matches_drtype(&row.drtype, drtype)))
+        .collect();
+    rows.sort_by(|a, b| {
+        let ordering = sort.compare(a, b);
+        if ascending {
+            ordering
+        } else {
+            ordering.reverse()
+        }
+    });
+    rows
+}
+
+pub fn summary_headers() -> Vec<String> {
+    SortColumn::all().iter().map(SortColumn::header).collect()
+}
@@ -0,0 +1,408 @@
+use crate::app::config::{BackendKind, TbguiConfig};
+use crate::app::config_form::{SettingsDraft, SettingsField};
+use crate::app::icons::get_icon;
+use crate::app::keys::find_ssh_keys;
+use crate::app::profile::Profile;
+use crate::app::types::AppError;
+use crate::fl;
+use cosmic::{
+    iced::{Alignment, Length},
+    theme, widget, Apply, Element,
+};
+use std::path::{Path, PathBuf};
+
+/// First-run assistant collecting the connection, the SSH key and the project
+/// directories before the app connects for the first time.
+pub struct Setup {
+    step: Step,
+    base: TbguiConfig,
+    draft: SettingsDraft,
+    keys: Vec<PathBuf>,
+    key_names: Vec<String>,
+    selected_key: Option<usize>,
+    password: String,
+    key_installed: bool,
+    project_root: String,
+    discovered: Vec<SettingsField>,
+    discovered_once: bool,
+    busy: bool,
+    error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Step {
+    Connection,
+    Key,
+    Directories,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    SetField(SettingsField, String),
+    SetRunLocally(bool),
+    SelectKey(usize),
+    GenerateKey,
+    GeneratedKey(Result<PathBuf, AppError>),
+    SetPassword(String),
+    InstallKey,
+    InstalledKey(Result<(), AppError>),
+    SetProjectRoot(String),
+    Discover,
+    Discovered(Result<Vec<(SettingsField, String)>, AppError>),
+    ImportProfile,
+    ImportedProfile(Result<Profile, AppError>),
+    Back,
+    Next,
+    Finish,
+}
+
+pub enum TaskMessage {
+    GenerateKey,
+    InstallKey(TbguiConfig, String, PathBuf),
+    Discover(TbguiConfig, String),
+    ImportProfile(TbguiConfig),
+    Finish(TbguiConfig),
+}
+
+impl Setup {
+    pub fn new(config: &TbguiConfig) -> Self {
+        let project_root = config
+            .remote_raw_dir
+            .as_deref()
+            .and_then(|dir| Path::new(dir).parent())
+            .map(|dir| dir.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut setup = Self {
+            step: Step::Connection,
+            base: config.clone(),
+            draft: SettingsDraft::from_config(config),
+            keys: Vec::new(),
+            key_names: Vec::new(),
+            selected_key: None,
+            password: String::new(),
+            key_installed: false,
+            project_root,
+            discovered: Vec::new(),
+            discovered_once: false,
+            busy: false,
+            error: None,
+        };
+        setup.set_keys(find_ssh_keys(), Some(config.ssh_key()));
+        setup
+    }
+
+    fn set_keys(&mut self, keys: Vec<PathBuf>, selected: Option<PathBuf>) {
+        self.key_names = keys
+            .iter()
+            .map(|key| key.to_string_lossy().to_string())
+            .collect();
+        self.selected_key = selected
+            .and_then(|selected| keys.iter().position(|key| *key == selected))
+            .or((!keys.is_empty()).then_some(0));
+        self.keys = keys;
+    }
+
+    fn selected_key(&self) -> Option<&PathBuf> {
+        self.selected_key.and_then(|index| self.keys.get(index))
+    }
+
+    /// The configuration as entered so far.
+    fn config(&self) -> TbguiConfig {
+        let mut config = self.draft.apply(&self.base);
+        config.ssh_key_path = self
+            .selected_key()
+            .map(|key| key.to_string_lossy().to_string());
+        config
+    }
+
+    /// No SSH key is needed when tb-profiler runs on this computer.
+    fn runs_locally(&self) -> bool {
+        self.draft.backend == BackendKind::Local
+    }
+
+    fn can_continue(&self) -> bool {
+        match self.step {
+            Step::Connection => SettingsField::connection()
+                .iter()
+                .all(|&field| self.draft.error(field).is_none()),
+            Step::Key => self.selected_key().is_some(),
+            Step::Directories => self.draft.is_valid(),
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Vec<TaskMessage> {
+        let mut tasks = Vec::new();
+        match message {
+            Message::SetField(field, value) => {
+                self.draft.set(field, value);
+            }
+            Message::SetRunLocally(value) => {
+                self.draft.backend = if value {
+                    BackendKind::Local
+                } else {
+                    BackendKind::Ssh
+                };
+            }
+            Message::SelectKey(index) => {
+                self.selected_key = Some(index);
+                self.key_installed = false;
+            }
+            Message::GenerateKey => {
+                self.busy = true;
+                self.error = None;
+                tasks.push(TaskMessage::GenerateKey);
+            }
+            Message::GeneratedKey(result) => {
+                self.busy = false;
+                match result {
+                    Ok(key) => self.set_keys(find_ssh_keys(), Some(key)),
+                    Err(err) => self.error = Some(err.to_string()),
+                }
+            }
+            Message::SetPassword(password) => {
+                self.password = password;
+            }
+            Message::InstallKey => {
+                if let Some(key) = self.selected_key().cloned() {
+                    self.busy = true;
+                    self.error = None;
+                    tasks.push(TaskMessage::InstallKey(
+                        self.config(),
+                        std::mem::take(&mut self.password),
+                        key,
+                    ));
+                }
+            }
+            Message::InstalledKey(result) => {
+                self.busy = false;
+                match result {
+                    Ok(()) => self.key_installed = true,
+                    Err(err) => self.error = Some(err.to_string()),
+                }
+            }
+            Message::SetProjectRoot(root) => {
+                self.project_root = root;
+            }
+            Message::Discover => {
+                self.busy = true;
+                self.error = None;
+                tasks.push(TaskMessage::Discover(
+                    self.config(),
+                    self.project_root.trim().to_string(),
+                ));
+            }
+            Message::Discovered(result) => {
+                self.busy = false;
+                self.discovered_once = true;
+                match result {
+                    Ok(found) => {
+                        self.discovered = found.iter().map(|(field, _)| *field).collect();
+                        for (field, path) in found {
+                            self.draft.set(field, path);
+                        }
+                    }
+                    Err(err) => self.error = Some(err.to_string()),
+                }
+            }
+            Message::ImportProfile => {
+                self.busy = true;
+                self.error = None;
+                tasks.push(TaskMessage::ImportProfile(self.config()));
+            }
+            Message::ImportedProfile(result) => {
+                self.busy = false;
+                match result {
+                    Ok(profile) => {
+                        self.draft = SettingsDraft::from_config(&profile.apply(&self.config()));
+                        self.discovered.clear();
+                        self.discovered_once = false;
+                    }
+                    Err(err) => self.error = Some(err.to_string()),
+                }
+            }
+            Message::Back => {
+                self.error = None;
+                self.step = match self.step {
+                    Step::Connection | Step::Key => Step::Connection,
+                    Step::Directories if self.runs_locally() => Step::Connection,
+                    Step::Directories => Step::Key,
+                };
+            }
+            Message::Next => {
+                self.error = None;
+                self.step = match self.step {
+                    Step::Connection if self.runs_locally() => Step::Directories,
+                    Step::Connection => Step::Key,
+                    Step::Key | Step::Directories => Step::Directories,
+                };
+            }
+            Message::Finish => {
+                let mut config = self.config();
+                config.setup_complete = true;
+                tasks.push(TaskMessage::Finish(config));
+            }
+        }
+        tasks
+    }
+
+    fn field_input(&self, field: SettingsField) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let mut column = widget::column::with_capacity(3)
+            .spacing(spacing.space_xxxs)
+            .push(widget::text::body(field.label()))
+            .push(
+                widget::text_input("", self.draft.value(field))
+                    .on_input(move |value| Message::SetField(field, value)),
+            );
+        if let Some(error) = self.draft.error(field) {
+            column = column.push(widget::text::caption(error));
+        }
+        column.into()
+    }
+
+    fn view_connection(&self) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let mut column = widget::column::with_capacity(4)
+            .spacing(spacing.space_s)
+            .push(widget::text::body(fl!("setup-connection-intro")))
+            .push(
+                widget::checkbox(fl!("run-locally"), self.runs_locally())
+                    .on_toggle(Message::SetRunLocally),
+            );
+        for &field in SettingsField::connection() {
+            column = column.push(self.field_input(field));
+        }
+        column.into()
+    }
+
+    fn view_key(&self) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let keys: Element<Message> = if self.keys.is_empty() {
+            widget::text::body(fl!("setup-no-keys")).into()
+        } else {
+            widget::dropdown(&self.key_names, self.selected_key, Message::SelectKey).into()
+        };
+        let mut column = widget::column::with_capacity(6)
+            .spacing(spacing.space_s)
+            .push(widget::text::body(fl!("setup-key-intro")))
+            .push(
+                widget::row::with_capacity(2)
+                    .spacing(spacing.space_s)
+                    .align_y(Alignment::Center)
+                    .push(keys)
+                    .push(
+                        widget::button::standard(fl!("setup-generate-key"))
+                            .on_press_maybe((!self.busy).then_some(Message::GenerateKey)),
+                    ),
+            )
+            .push(widget::text::body(fl!("setup-install-intro")))
+            .push(
+                widget::row::with_capacity(2)
+                    .spacing(spacing.space_s)
+                    .align_y(Alignment::Center)
+                    .push(
+                        widget::secure_input(fl!("setup-password"), &self.password, None, true)
+                            .on_input(Message::SetPassword),
+                    )
+                    .push(
+                        widget::button::standard(fl!("setup-install-key")).on_press_maybe(
+                            (!self.busy
+                                && !self.password.is_empty()
+                                && self.selected_key().is_some())
+                            .then_some(Message::InstallKey),
+                        ),
+                    ),
+            );
+        if self.key_installed {
+            column = column.push(widget::text::body(fl!("setup-key-installed")));
+        }
+        column.into()
+    }
+
+    fn view_directories(&self) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let mut column = widget::column::with_capacity(SettingsField::remote_paths().len() + 3)
+            .spacing(spacing.space_s)
+            .push(widget::text::body(fl!("setup-directories-intro")))
+            .push(
+                widget::row::with_capacity(3)
+                    .spacing(spacing.space_s)
+                    .align_y(Alignment::Center)
+                    .push(
+                        widget::button::standard(fl!("profile-import-cluster"))
+                            .on_press_maybe((!self.busy).then_some(Message::ImportProfile)),
+                    )
+                    .push(
+                        widget::text_input(fl!("setup-project-root"), &self.project_root)
+                            .on_input(Message::SetProjectRoot),
+                    )
+                    .push(
+                        widget::button::standard(fl!("setup-discover")).on_press_maybe(
+                            (!self.busy && self.project_root.starts_with('/'))
+                                .then_some(Message::Discover),
+                        ),
+                    ),
+            );
+        for &field in SettingsField::remote_paths() {
+            let mut row = widget::row::with_capacity(2)
+                .spacing(spacing.space_xxs)
+                .align_y(Alignment::Center)
+                .push(
+                    self.field_input(field)
+                        .apply(widget::container)
+                        .width(Length::Fill),
+                );
+            if self.discovered_once {
+                row = row.push(if self.discovered.contains(&field) {
+                    get_icon("emblem-ok-symbolic", 16)
+                } else {
+                    get_icon("dialog-warning-symbolic", 16)
+                });
+            }
+            column = column.push(row);
+        }
+        column.into()
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let (title, page) = match self.step {
+            Step::Connection => (fl!("setup-connection"), self.view_connection()),
+            Step::Key => (fl!("setup-key"), self.view_key()),
+            Step::Directories => (fl!("setup-directories"), self.view_directories()),
+        };
+        let next = if self.step == Step::Directories {
+            widget::button::suggested(fl!("setup-finish"))
+                .on_press_maybe((self.can_continue() && !self.busy).then_some(Message::Finish))
+        } else {
+            widget::button::suggested(fl!("setup-next"))
+                .on_press_maybe((self.can_continue() && !self.busy).then_some(Message::Next))
+        };
+        let actions = widget::row::with_capacity(3)
+            .spacing(spacing.space_s)
+            .push(widget::button::standard(fl!("setup-back")).on_press_maybe(
+                (self.step != Step::Connection && !self.busy).then_some(Message::Back),
+            ))
+            .push(widget::horizontal_space())
+            .push(next);
+
+        let mut column = widget::column::with_capacity(5)
+            .spacing(spacing.space_m)
+            .push(widget::text::title2(fl!("setup-title")))
+            .push(widget::text::title4(title))
+            .push(page);
+        if let Some(error) = &self.error {
+            column = column.push(widget::text::body(error.clone()));
+        }
+        column
+            .push(actions)
+            .max_width(640.)
+            .apply(widget::scrollable)
+            .apply(widget::container)
+            .padding(spacing.space_m)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .into()
+    }
+}
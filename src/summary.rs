@@ -0,0 +1,241 @@
+use crate::app::export::ExportFormat;
+use crate::app::icons;
+use crate::fl;
+use crate::model::profile::{DrType, TbProfile};
+use crate::model::summary::{summary_headers, summary_rows, SortColumn, SummaryRow};
+use cosmic::{
+    iced::{
+        alignment::{Horizontal, Vertical},
+        widget::scrollable,
+        Alignment, Length,
+    },
+    theme, widget, Apply, Element,
+};
+
+const SAMPLE_WIDTH: f32 = 160.0;
+const COLUMN_WIDTH: f32 = 120.0;
+
+pub struct Summary {
+    profiles: Vec<TbProfile>,
+    rows: Vec<SummaryRow>,
+    sort: SortColumn,
+    ascending: bool,
+    query: String,
+    drtype_names: Vec<String>,
+    drtype_filter: usize,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    SetProfiles(Vec<TbProfile>),
+    Sort(SortColumn),
+    Filter(String),
+    FilterDrType(usize),
+    Export(ExportFormat),
+}
+
+pub enum TaskMessage {
+    Export(ExportFormat, Vec<String>, Vec<Vec<String>>),
+}
+
+/// Options of the resistance type filter; `None` shows all samples and
+/// `Other` every sample with an unrecognised resistance type.
+fn drtype_filters() -> Vec<Option<DrType>> {
+    vec![
+        None,
+        Some(DrType::Sensitive),
+        Some(DrType::HrTb),
+        Some(DrType::RrTb),
+        Some(DrType::Mdr),
+        Some(DrType::PreXdr),
+        Some(DrType::Xdr),
+        Some(DrType::Other(String::new())),
+    ]
+}
+
+impl Summary {
+    pub fn new() -> Self {
+        Self {
+            profiles: Vec::new(),
+            rows: Vec::new(),
+            sort: SortColumn::default(),
+            ascending: true,
+            query: String::new(),
+            drtype_names: drtype_filters()
+                .iter()
+                .map(|drtype| match drtype {
+                    Some(DrType::Other(_)) => fl!("other-dr-types"),
+                    Some(drtype) => drtype.as_str().to_string(),
+                    None => fl!("all-dr-types"),
+                })
+                .collect(),
+            drtype_filter: 0,
+        }
+    }
+
+    fn refresh(&mut self) {
+        let drtype = drtype_filters().swap_remove(self.drtype_filter);
+        let profiles: Vec<&TbProfile> = self.profiles.iter().collect();
+        self.rows = summary_rows(
+            &profiles,
+            self.sort,
+            self.ascending,
+            &self.query,
+            drtype.as_ref(),
+        );
+    }
+
+    pub fn update(&mut self, message: Message) -> Vec<TaskMessage> {
+        let mut tasks = Vec::new();
+        match message {
+            Message::SetProfiles(profiles) => {
+                self.profiles = profiles;
+            }
+            Message::Sort(column) => {
+                if self.sort == column {
+                    self.ascending = !self.ascending;
+                } else {
+                    self.sort = column;
+                    self.ascending = true;
+                }
+            }
+            Message::Filter(query) => {
+                self.query = query;
+            }
+            Message::FilterDrType(index) => {
+                self.drtype_filter = index;
+            }
+            Message::Export(format) => {
+                let records = self.rows.iter().map(SummaryRow::record).collect();
+                tasks.push(TaskMessage::Export(format, summary_headers(), records));
+            }
+        }
+        self.refresh();
+        tasks
+    }
+
+    fn header(&self) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+        widget::row::with_capacity(5)
+            .align_y(Alignment::Center)
+            .spacing(spacing.space_s)
+            .push(
+                widget::search_input(fl!("filter-samples"), &self.query)
+                    .on_input(Message::Filter)
+                    .width(Length::Fixed(300.0)),
+            )
+            .push(widget::dropdown(
+                &self.drtype_names,
+                Some(self.drtype_filter),
+                Message::FilterDrType,
+            ))
+            .push(widget::horizontal_space())
+            .push(
+                widget::button::standard(fl!("export-csv"))
+                    .on_press(Message::Export(ExportFormat::Csv)),
+            )
+            .push(
+                widget::button::standard(fl!("export-xlsx"))
+                    .on_press(Message::Export(ExportFormat::Xlsx)),
+            )
+            .into()
+    }
+
+    fn table(&self) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let width = |column: &SortColumn| match column {
+            SortColumn::Sample => SAMPLE_WIDTH,
+            _ => COLUMN_WIDTH,
+        };
+
+        let mut header = widget::row::with_capacity(SortColumn::all().len());
+        for column in SortColumn::all() {
+            let mut label = column.header().replace('_', " ");
+            if column == self.sort {
+                label.push_str(if self.ascending { " ▲" } else { " ▼" });
+            }
+            header = header.push(
+                widget::button::text(label)
+                    .on_press(Message::Sort(column))
+                    .width(Length::Fixed(width(&column))),
+            );
+        }
+
+        let mut rows = widget::column::with_capacity(self.rows.len() + 1)
+            .spacing(spacing.space_xxxs)
+            .push(header);
+        for summary_row in &self.rows {
+            let mut row = widget::row::with_capacity(SortColumn::all().len())
+                .align_y(Alignment::Center)
+                .push(cell(summary_row.sample.clone(), SAMPLE_WIDTH))
+                .push(cell(summary_row.main_lineage.clone(), COLUMN_WIDTH))
+                .push(cell(summary_row.sub_lineage.clone(), COLUMN_WIDTH))
+                .push(cell(summary_row.drtype.as_str().to_string(), COLUMN_WIDTH));
+            for mutations in summary_row.drugs.iter().chain([&summary_row.other]) {
+                row = row.push(if mutations.is_empty() {
+                    cell("-".to_string(), COLUMN_WIDTH)
+                } else {
+                    widget::tooltip(
+                        cell("R".to_string(), COLUMN_WIDTH),
+                        widget::text(mutations.join("\n")),
+                        widget::tooltip::Position::Top,
+                    )
+                    .into()
+                });
+            }
+            rows = rows.push(row);
+        }
+
+        widget::scrollable(rows)
+            .direction(scrollable::Direction::Both {
+                vertical: scrollable::Scrollbar::default(),
+                horizontal: scrollable::Scrollbar::default(),
+            })
+            .height(Length::Fill)
+            .into()
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        if self.profiles.is_empty() {
+            return widget::container(
+                widget::column::with_children(vec![
+                    icons::get_icon("meds", 56).into(),
+                    widget::text::title1(fl!("no-results")).into(),
+                    widget::text(fl!("no-results-suggestion")).into(),
+                ])
+                .spacing(10)
+                .align_x(Alignment::Center),
+            )
+            .align_y(Vertical::Center)
+            .align_x(Horizontal::Center)
+            .height(Length::Fill)
+            .width(Length::Fill)
+            .into();
+        }
+
+        widget::column::with_capacity(3)
+            .spacing(spacing.space_s)
+            .push(self.header())
+            .push(widget::text::caption(fl!(
+                "summary-count",
+                shown = self.rows.len(),
+                total = self.profiles.len()
+            )))
+            .push(self.table())
+            .apply(widget::container)
+            .padding(spacing.space_s)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
+
+fn cell<'a>(value: String, width: f32) -> Element<'a, Message> {
+    widget::text(value)
+        .width(Length::Fixed(width))
+        .apply(widget::container)
+        .padding([0, 8])
+        .into()
+}
@@ -0,0 +1,270 @@
+use crate::app::backend::{Backend, LocalBackend};
+use crate::app::config::{BackendKind, TbguiConfig};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+pub const USERNAME: &str = "tester";
+
+/// Submits the array job script read from standard input. Unless
+/// `$MOCK_SLURM_DIR/hold` exists, every task runs right away so that the job
+/// is complete once `sbatch` returns. Task output goes to `--output` with `%A`
+/// and `%a` replaced.
+const SBATCH: &str = r#"#!/bin/sh
+state="$MOCK_SLURM_DIR"
+id=$(( $(cat "$state/last_id" 2>/dev/null || echo 1000) + 1 ))
+array=0-0
+output="$state/%A.out"
+while [ $# -gt 0 ]; do
+    case "$1" in
+        --array) array="$2"; shift 2 ;;
+        --array=*) array="${1#--array=}"; shift ;;
+        --output=*) output="${1#--output=}"; shift ;;
+        -*) shift ;;
+        *) break ;;
+    esac
+done
+script="$state/$id.sh"
+cat > "$script"
+if [ ! -s "$script" ]; then
+    rm -f "$script"
+    echo "sbatch: error: Batch script is empty!" >&2
+    exit 1
+fi
+echo "$id" > "$state/last_id"
+if [ -e "$state/hold" ]; then
+    echo PENDING > "$state/$id.state"
+else
+    status=COMPLETED
+    task=${array%-*}
+    while [ "$task" -le "${array#*-}" ]; do
+        log=$(echo "$output" | sed "s/%A/$id/g; s/%a/$task/g")
+        SLURM_ARRAY_JOB_ID=$id SLURM_ARRAY_TASK_ID=$task sh "$script" \
+            >> "$log" 2>&1 || status=FAILED
+        task=$((task + 1))
+    done
+    echo "$status" > "$state/$id.state"
+fi
+echo "Submitted batch job $id"
+"#;
+
+/// Lists the pending jobs, in the `-o` format used by `list_jobs` if given.
+const SQUEUE: &str = r#"#!/bin/sh
+user=tester
+header=1
+format=
+while [ $# -gt 0 ]; do
+    case "$1" in
+        -u) user="$2"; shift 2 ;;
+        -o) format="$2"; shift 2 ;;
+        -h) header=; shift ;;
+        *) shift ;;
+    esac
+done
+if [ -n "$header" ] && [ -z "$format" ]; then
+    echo "JOBID PARTITION NAME USER ST TIME NODES NODELIST(REASON)"
+fi
+for file in "$MOCK_SLURM_DIR"/*.state; do
+    [ -e "$file" ] || continue
+    if [ "$(cat "$file")" = PENDING ]; then
+        id=$(basename "$file" .state)
+        if [ -n "$format" ]; then
+            echo "$id|tbprofiler|PENDING|0:00|None"
+        else
+            echo "$id standard tbprofiler $user PD 0:00 1 (None)"
+        fi
+    fi
+done
+"#;
+
+/// Prints `<job>|<state>` for all jobs or the one given with `-j`.
+const SACCT: &str = r#"#!/bin/sh
+job=
+while [ $# -gt 0 ]; do
+    case "$1" in
+        -j) job="$2"; shift 2 ;;
+        *) shift ;;
+    esac
+done
+for file in "$MOCK_SLURM_DIR"/*.state; do
+    [ -e "$file" ] || continue
+    id=$(basename "$file" .state)
+    if [ -z "$job" ] || [ "$job" = "$id" ]; then
+        echo "$id|$(cat "$file")"
+    fi
+done
+"#;
+
+const SCANCEL: &str = r#"#!/bin/sh
+for id in "$@"; do
+    if [ ! -e "$MOCK_SLURM_DIR/$id.state" ]; then
+        echo "scancel: error: Invalid job id specified" >&2
+        exit 1
+    fi
+    echo CANCELLED > "$MOCK_SLURM_DIR/$id.state"
+done
+"#;
+
+/// Stands in for the tb-profiler script, called with the samples, the raw
+/// reads, the out directory and the template. Writes the usual result files
+/// of the sample of the task in `TBGUI_TASK_INDEX`.
+const TBPROFILER: &str = r#"#!/bin/sh
+raw="$2"
+out="$3"
+index=0
+for sample in $1; do
+    if [ "$index" = "$TBGUI_TASK_INDEX" ]; then
+        if [ ! -f "$raw/${sample}_1.fastq.gz" ] || [ ! -f "$raw/${sample}_2.fastq.gz" ]; then
+            echo "reads of $sample not found" >&2
+            exit 1
+        fi
+        echo "profiling $sample"
+        mkdir -p "$out/results" "$out/bam" "$out/vcf"
+        echo "{\"id\": \"$sample\"}" > "$out/results/$sample.results.json"
+        echo "report of $sample" > "$out/results/$sample.results.docx"
+        echo "alignment of $sample" > "$out/bam/$sample.bam"
+        echo "variants of $sample" > "$out/vcf/$sample.targets.vcf.gz"
+    fi
+    index=$((index + 1))
+done
+"#;
+
+/// A cluster in a temporary directory, reached through the local backend.
+/// Slurm is replaced by shell scripts that keep their jobs in `slurm/`.
+/// The directory is removed on drop.
+pub struct MockCluster {
+    root: PathBuf,
+    pub backend: Backend,
+    pub config: TbguiConfig,
+}
+
+impl MockCluster {
+    pub fn new() -> Self {
+        let root = std::env::temp_dir().join(format!("tbgui-test-{}", Uuid::new_v4()));
+        for dir in [
+            "bin",
+            "slurm",
+            "raw",
+            "scripts",
+            "out/results",
+            "out/bam",
+            "out/vcf",
+            "template",
+            "trash",
+            "archive",
+            "downloads",
+        ] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for (name, script) in [
+            ("bin/sbatch", SBATCH),
+            ("bin/squeue", SQUEUE),
+            ("bin/sacct", SACCT),
+            ("bin/scancel", SCANCEL),
+            ("scripts/tbprofiler.sh", TBPROFILER),
+        ] {
+            write_executable(&root.join(name), script);
+        }
+        std::fs::write(root.join("template/user_template.docx"), "template").unwrap();
+
+        let path = format!(
+            "{}:{}",
+            root.join("bin").display(),
+            std::env::var("PATH").unwrap_or_default()
+        );
+        let backend = Backend::Local(
+            LocalBackend::default()
+                .with_env("PATH", path)
+                .with_env("MOCK_SLURM_DIR", root.join("slurm").to_string_lossy()),
+        );
+        let remote = |path: &str| Some(root.join(path).to_string_lossy().to_string());
+        let config = TbguiConfig {
+            username: Some(USERNAME.to_string()),
+            backend: BackendKind::Local,
+            remote_raw_dir: remote("raw"),
+            tb_profiler_script: remote("scripts/tbprofiler.sh"),
+            remote_out_dir: remote("out"),
+            default_template_remote: remote("template/user_template.docx"),
+            user_template_remote: remote("template/user_template.docx"),
+            remote_template_dir: remote("template"),
+            remote_batches_file: remote("tbgui/batches.json"),
+            remote_profile_file: remote("tbgui/profile.toml"),
+            remote_trash_dir: remote("trash"),
+            remote_archive_dir: remote("archive"),
+            local_download_dir: remote("downloads"),
+            transfer_concurrency: 2,
+            verify_checksums: true,
+            ..TbguiConfig::default()
+        };
+        Self {
+            root,
+            backend,
+            config,
+        }
+    }
+
+    pub fn path(&self, relative: &str) -> PathBuf {
+        self.root.join(relative)
+    }
+
+    /// Adds both FASTQ files of `sample` to the raw reads.
+    pub fn add_reads(&self, sample: &str) {
+        for mate in 1..=2 {
+            std::fs::write(
+                self.path(&format!("raw/{sample}_{mate}.fastq.gz")),
+                format!("reads {mate} of {sample}"),
+            )
+            .unwrap();
+        }
+    }
+
+    /// Adds the result files tb-profiler writes for `sample`.
+    pub fn add_results(&self, sample: &str) {
+        for (path, content) in [
+            (format!("out/results/{sample}.results.json"), "{}"),
+            (format!("out/results/{sample}.results.docx"), "report"),
+            (format!("out/bam/{sample}.bam"), "alignment"),
+            (format!("out/vcf/{sample}.targets.vcf.gz"), "variants"),
+        ] {
+            std::fs::write(self.path(&path), format!("{content} of {sample}")).unwrap();
+        }
+    }
+
+    /// Keeps jobs submitted from now on pending instead of running them.
+    pub fn hold_jobs(&self) {
+        std::fs::write(self.path("slurm/hold"), "").unwrap();
+    }
+
+    /// State of `job` as recorded by the fake `sbatch` and `scancel`.
+    pub fn job_state(&self, job: &str) -> String {
+        std::fs::read_to_string(self.path(&format!("slurm/{job}.state")))
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    }
+
+    /// Names of the files in `dir`, sorted.
+    pub fn files(&self, dir: &str) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(self.path(dir)) else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_file())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+impl Drop for MockCluster {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+fn write_executable(path: &Path, content: &str) {
+    std::fs::write(path, content).unwrap();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
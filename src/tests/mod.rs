@@ -0,0 +1,11 @@
+//! End-to-end tests of the remote logic against a [`mock_cluster::MockCluster`]
+//! on the local backend.
+
+mod mock_cluster;
+mod profile;
+mod report;
+mod results;
+mod samples;
+mod slurm;
+mod templates;
+mod watch;
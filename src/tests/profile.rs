@@ -0,0 +1,49 @@
+use super::mock_cluster::MockCluster;
+use crate::app::profile::Profile;
+use crate::app::ssh::{fetch_profile, profile_published, push_profile};
+use crate::app::types::ErrorKind;
+
+#[tokio::test]
+async fn publishing_replaces_the_profile_in_place() {
+    let mut cluster = MockCluster::new();
+    assert!(!profile_published(&cluster.backend, &cluster.config)
+        .await
+        .unwrap());
+
+    let first = Profile::from_config(&cluster.config);
+    push_profile(&cluster.backend, &cluster.config, &first)
+        .await
+        .unwrap();
+    assert!(profile_published(&cluster.backend, &cluster.config)
+        .await
+        .unwrap());
+
+    cluster.config.retention_days = 30;
+    let second = Profile::from_config(&cluster.config);
+    push_profile(&cluster.backend, &cluster.config, &second)
+        .await
+        .unwrap();
+
+    let published = fetch_profile(&cluster.backend, &cluster.config)
+        .await
+        .unwrap();
+    assert_eq!(published, second);
+    assert_eq!(cluster.files("tbgui"), ["profile.toml"]);
+}
+
+#[tokio::test]
+async fn an_invalid_profile_is_a_configuration_error() {
+    let cluster = MockCluster::new();
+    std::fs::create_dir_all(cluster.path("tbgui")).unwrap();
+    std::fs::write(
+        cluster.path("tbgui/profile.toml"),
+        "retention_days = \"soon\"",
+    )
+    .unwrap();
+
+    let err = fetch_profile(&cluster.backend, &cluster.config)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.kind, ErrorKind::ConfigMissing);
+}
@@ -0,0 +1,143 @@
+use crate::app::report::{render_xml, template_variables};
+use serde_json::{json, Value};
+
+fn render(xml: &str, context: Value) -> Result<String, String> {
+    let Value::Object(context) = context else {
+        unreachable!()
+    };
+    render_xml(xml, &context)
+}
+
+fn paragraph(text: &str) -> String {
+    format!("<w:p><w:r><w:t>{text}</w:t></w:r></w:p>")
+}
+
+#[test]
+fn renders_variables_and_escapes_them() {
+    let xml = paragraph("Sample {{ id }} from {{ meta.source }}");
+    let context = json!({ "id": "ERR1", "meta": { "source": "A&B <lab>" } });
+
+    assert_eq!(
+        render(&xml, context).unwrap(),
+        paragraph("Sample ERR1 from A&amp;B &lt;lab&gt;")
+    );
+}
+
+#[test]
+fn renders_loops_with_the_loop_variable() {
+    let xml = paragraph("{% for drug in drugs %}{{ loop.index }}. {{ drug }}{% if not loop.last %}, {% endif %}{% endfor %}");
+    let context = json!({ "drugs": ["rifampicin", "isoniazid", "ethambutol"] });
+
+    assert_eq!(
+        render(&xml, context).unwrap(),
+        paragraph("1. rifampicin, 2. isoniazid, 3. ethambutol")
+    );
+}
+
+#[test]
+fn renders_the_first_matching_branch() {
+    let xml = paragraph(
+        "{% if depth > 50 %}high{% elif depth >= 10 and not failed %}medium{% else %}low{% endif %}",
+    );
+
+    for (depth, failed, expected) in [
+        (80, false, "high"),
+        (20, false, "medium"),
+        (20, true, "low"),
+        (5, false, "low"),
+    ] {
+        assert_eq!(
+            render(&xml, json!({ "depth": depth, "failed": failed })).unwrap(),
+            paragraph(expected)
+        );
+    }
+}
+
+#[test]
+fn applies_filters() {
+    let xml = paragraph(
+        "{{ drugs | join(\", \") | upper }}; {{ missing | default(\"none\") }}; {{ ratio | round(2) }}; {{ drugs | length }}",
+    );
+    let context = json!({ "drugs": ["rif", "inh"], "ratio": 0.98765 });
+
+    assert_eq!(
+        render(&xml, context).unwrap(),
+        paragraph("RIF, INH; none; 0.99; 2")
+    );
+}
+
+#[test]
+fn rejects_unknown_filters() {
+    let error = render(&paragraph("{{ id | reverse }}"), json!({ "id": "ERR1" })).unwrap_err();
+
+    assert!(error.contains("reverse"), "{error}");
+}
+
+#[test]
+fn indexes_lists_from_the_end_with_negative_indices() {
+    let xml = paragraph("{{ lineages[-1] }} {{ lineages[0] }} {{ lineages[-3] }}");
+    let context = json!({ "lineages": ["lineage4", "lineage4.9"] });
+
+    assert_eq!(
+        render(&xml, context).unwrap(),
+        paragraph("lineage4.9 lineage4 ")
+    );
+}
+
+#[test]
+fn renders_tags_split_across_runs() {
+    let xml = "<w:p><w:r><w:t>{</w:t></w:r><w:r><w:rPr><w:b/></w:rPr><w:t>{ i</w:t></w:r><w:r><w:t>d }</w:t></w:r><w:r><w:t>}</w:t></w:r></w:p>";
+
+    let output = render(xml, json!({ "id": "ERR1" })).unwrap();
+
+    assert!(output.contains("ERR1"), "{output}");
+    assert!(!output.contains('{') && !output.contains('}'), "{output}");
+}
+
+#[test]
+fn accepts_smart_quotes_typed_by_word() {
+    let xml = paragraph("{{ missing | default(\u{201c}n/a\u{201d}) }} {% if id == \u{2018}ERR1\u{2019} %}match{% endif %}");
+
+    assert_eq!(
+        render(&xml, json!({ "id": "ERR1" })).unwrap(),
+        paragraph("n/a match")
+    );
+}
+
+#[test]
+fn repeats_table_rows_for_prefixed_tags() {
+    let row = |text: &str| format!("<w:tr><w:tc>{}</w:tc></w:tr>", paragraph(text));
+    let xml = format!(
+        "<w:tbl>{}{}{}</w:tbl>",
+        row("{%tr for drug in drugs %}"),
+        row("{{ drug }}"),
+        row("{%tr endfor %}")
+    );
+    let context = json!({ "drugs": ["rif", "inh"] });
+
+    assert_eq!(
+        render(&xml, context).unwrap(),
+        format!("<w:tbl>{}{}</w:tbl>", row("rif"), row("inh"))
+    );
+}
+
+#[test]
+fn reports_unclosed_blocks() {
+    assert!(render(&paragraph("{% for drug in drugs %}{{ drug }}"), json!({})).is_err());
+    assert!(render(&paragraph("{% if id %}x{% endfor %}"), json!({})).is_err());
+}
+
+#[test]
+fn lists_the_variables_a_template_reads() {
+    let xml = paragraph(
+        "{% set title = id | upper %}{{ title }} {% for drug in resistance %}{{ drug.name }} {{ loop.index }}{% endfor %}{% if notes is defined %}{{ notes }}{% endif %}",
+    );
+
+    let variables = template_variables(&xml).unwrap();
+
+    assert_eq!(
+        variables.into_iter().collect::<Vec<_>>(),
+        ["id", "notes", "resistance"]
+    );
+    assert!(template_variables(&paragraph("{{ id | }}")).is_err());
+}
@@ -0,0 +1,187 @@
+use super::mock_cluster::MockCluster;
+use crate::app::delete::move_to_trash;
+use crate::app::ssh::{delete_results, download_results, list_result_sets};
+use crate::app::transfer::{list_remote_files, DownloadRequest, ResultFileType};
+use crate::app::types::ErrorKind;
+use tokio::sync::mpsc::unbounded_channel;
+
+fn all_files() -> DownloadRequest {
+    DownloadRequest {
+        samples: None,
+        file_types: ResultFileType::all().to_vec(),
+    }
+}
+
+#[tokio::test]
+async fn downloads_results_and_skips_them_the_next_time() {
+    let cluster = MockCluster::new();
+    cluster.add_results("ERR1");
+    cluster.add_results("ERR2");
+    let (sender, _receiver) = unbounded_channel();
+
+    let summary = download_results(&cluster.backend, &cluster.config, &all_files(), &sender)
+        .await
+        .unwrap();
+
+    assert_eq!(summary.transferred, 8);
+    assert_eq!(summary.skipped, 0);
+    assert_eq!(
+        cluster.files("downloads"),
+        [
+            "ERR1.results.docx",
+            "ERR1.results.json",
+            "ERR2.results.docx",
+            "ERR2.results.json"
+        ]
+    );
+    assert_eq!(cluster.files("downloads/bam"), ["ERR1.bam", "ERR2.bam"]);
+    assert_eq!(
+        std::fs::read_to_string(cluster.path("downloads/vcf/ERR2.targets.vcf.gz")).unwrap(),
+        "variants of ERR2"
+    );
+
+    let summary = download_results(&cluster.backend, &cluster.config, &all_files(), &sender)
+        .await
+        .unwrap();
+
+    assert_eq!(summary.transferred, 0);
+    assert_eq!(summary.skipped, 8);
+}
+
+#[tokio::test]
+async fn restarts_partial_downloads_of_changed_files() {
+    let cluster = MockCluster::new();
+    cluster.add_results("ERR1");
+    std::fs::create_dir_all(cluster.path("downloads")).unwrap();
+    std::fs::write(cluster.path("downloads/ERR1.results.docx.part"), "stale").unwrap();
+    std::fs::write(
+        cluster.path("downloads/ERR1.results.docx.part.meta"),
+        "12 1",
+    )
+    .unwrap();
+    let (sender, _receiver) = unbounded_channel();
+    let request = DownloadRequest {
+        samples: None,
+        file_types: vec![ResultFileType::Docx],
+    };
+
+    download_results(&cluster.backend, &cluster.config, &request, &sender)
+        .await
+        .unwrap();
+
+    assert_eq!(cluster.files("downloads"), ["ERR1.results.docx"]);
+    assert_eq!(
+        std::fs::read_to_string(cluster.path("downloads/ERR1.results.docx")).unwrap(),
+        "report of ERR1"
+    );
+}
+
+#[tokio::test]
+async fn downloads_only_selected_samples_and_types() {
+    let cluster = MockCluster::new();
+    cluster.add_results("ERR1");
+    cluster.add_results("ERR10");
+    let (sender, _receiver) = unbounded_channel();
+    let request = DownloadRequest {
+        samples: Some(vec!["ERR1".to_string()]),
+        file_types: vec![ResultFileType::Json, ResultFileType::Bam],
+    };
+
+    let summary = download_results(&cluster.backend, &cluster.config, &request, &sender)
+        .await
+        .unwrap();
+
+    assert_eq!(summary.transferred, 2);
+    assert_eq!(cluster.files("downloads"), ["ERR1.results.json"]);
+    assert_eq!(cluster.files("downloads/bam"), ["ERR1.bam"]);
+}
+
+#[tokio::test]
+async fn deleted_results_are_moved_to_the_trash() {
+    let cluster = MockCluster::new();
+    cluster.add_results("ERR1");
+    cluster.add_results("ERR2");
+
+    let summary = delete_results(
+        &cluster.backend,
+        &cluster.config,
+        vec!["ERR1".to_string()],
+        false,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(summary.files, 4);
+    let trash_dir = summary.trash_dir.unwrap();
+    let trash = |subdir: &str| {
+        let mut names: Vec<String> = std::fs::read_dir(format!("{trash_dir}/{subdir}"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+    assert_eq!(trash("results"), ["ERR1.results.docx", "ERR1.results.json"]);
+    assert_eq!(trash("bam"), ["ERR1.bam"]);
+    let sets = list_result_sets(&cluster.backend, &cluster.config)
+        .await
+        .unwrap();
+    let samples: Vec<&str> = sets.iter().map(|set| set.sample.as_str()).collect();
+    assert_eq!(samples, ["ERR2"]);
+}
+
+#[tokio::test]
+async fn files_already_in_the_trash_are_not_counted_as_moved() {
+    let cluster = MockCluster::new();
+    cluster.add_results("ERR1");
+    let trash_dir = cluster.path("trash/day").to_string_lossy().to_string();
+    std::fs::create_dir_all(cluster.path("trash/day/bam")).unwrap();
+    std::fs::write(cluster.path("trash/day/bam/ERR1.bam"), "older").unwrap();
+    let files = list_remote_files(&cluster.backend, &cluster.config, &all_files())
+        .await
+        .unwrap();
+
+    let moved = move_to_trash(&cluster.backend, &files, &trash_dir)
+        .await
+        .unwrap();
+
+    assert_eq!(moved.len(), 3);
+    assert!(moved.iter().all(|file| file.name != "ERR1.bam"));
+    assert_eq!(cluster.files("out/bam"), ["ERR1.bam"]);
+    assert_eq!(
+        std::fs::read_to_string(cluster.path("trash/day/bam/ERR1.bam")).unwrap(),
+        "older"
+    );
+}
+
+#[tokio::test]
+async fn permanently_deleted_results_are_removed() {
+    let cluster = MockCluster::new();
+    cluster.add_results("ERR1");
+
+    let summary = delete_results(
+        &cluster.backend,
+        &cluster.config,
+        vec!["ERR1".to_string()],
+        true,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(summary.files, 4);
+    assert_eq!(summary.trash_dir, None);
+    assert!(cluster.files("out/results").is_empty());
+    assert!(cluster.files("out/bam").is_empty());
+    assert!(cluster.files("trash").is_empty());
+}
+
+#[tokio::test]
+async fn deleting_nothing_is_an_error() {
+    let cluster = MockCluster::new();
+
+    let err = delete_results(&cluster.backend, &cluster.config, Vec::new(), false)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.kind, ErrorKind::NoItemsChecked);
+}
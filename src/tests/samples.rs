@@ -0,0 +1,67 @@
+use super::mock_cluster::MockCluster;
+use crate::app::qc::check_sample;
+use crate::app::types::ErrorKind;
+use crate::model::Sample;
+
+#[tokio::test]
+async fn discovers_paired_samples_in_raw_dir() {
+    let cluster = MockCluster::new();
+    cluster.add_reads("ERR2");
+    cluster.add_reads("ERR1");
+    std::fs::write(cluster.path("raw/.ERR3_1.fastq.gz"), "").unwrap();
+
+    let samples = Sample::get_raw_reads(&cluster.backend, &cluster.config)
+        .await
+        .unwrap();
+
+    let titles: Vec<&str> = samples.iter().map(|sample| sample.title.as_str()).collect();
+    assert_eq!(titles, ["ERR1", "ERR2"]);
+}
+
+#[tokio::test]
+async fn missing_raw_dir_is_a_configuration_error() {
+    let mut cluster = MockCluster::new();
+    cluster.config.remote_raw_dir = Some(cluster.path("missing").to_string_lossy().to_string());
+
+    let err = Sample::get_raw_reads(&cluster.backend, &cluster.config)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.kind, ErrorKind::ConfigMissing);
+}
+
+#[tokio::test]
+async fn checks_reads_and_ignores_sidecar_files() {
+    let cluster = MockCluster::new();
+    let raw_dir = cluster.path("raw").to_string_lossy().to_string();
+    let gzip = |name: &str, records: usize| {
+        let path = cluster.path(&format!("raw/{name}"));
+        std::fs::write(&path, "@read\nACGT\n+\nIIII\n".repeat(records)).unwrap();
+        let status = std::process::Command::new("gzip")
+            .arg("-f")
+            .arg(&path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    gzip("ERR1_1.fastq", 2);
+    gzip("ERR1_2.fastq", 2);
+    std::fs::write(cluster.path("raw/ERR1_1.fastq.gz.md5"), "checksum").unwrap();
+
+    let check = check_sample(&cluster.backend, &raw_dir, "ERR1")
+        .await
+        .unwrap();
+    assert_eq!(check.failure, None);
+
+    gzip("ERR1_2.fastq", 1);
+    let data = std::fs::read(cluster.path("raw/ERR1_2.fastq.gz")).unwrap();
+    std::fs::write(cluster.path("raw/ERR1_2.fastq.gz"), &data[..data.len() / 2]).unwrap();
+
+    let check = check_sample(&cluster.backend, &raw_dir, "ERR1")
+        .await
+        .unwrap();
+    assert_eq!(
+        check.failure.as_deref(),
+        Some("ERR1_2.fastq.gz is corrupt or truncated")
+    );
+}
@@ -0,0 +1,179 @@
+use super::mock_cluster::{MockCluster, USERNAME};
+use crate::app::backend::RemoteBackend;
+use crate::app::config::SchedulerKind;
+use crate::app::ssh::{job_logs, list_jobs, run_tbprofiler};
+use crate::app::types::ErrorKind;
+use crate::app::utils::check_if_running;
+
+#[tokio::test]
+async fn submits_one_array_task_per_sample() {
+    let cluster = MockCluster::new();
+    cluster.add_reads("ERR1");
+    cluster.add_reads("ERR2");
+
+    let stdout = run_tbprofiler(
+        &cluster.backend,
+        2,
+        "ERR1 ERR2".to_string(),
+        &cluster.config,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(stdout.trim(), "Submitted batch job 1001");
+    assert_eq!(cluster.job_state("1001"), "COMPLETED");
+    assert_eq!(
+        cluster.files("out/results"),
+        [
+            "ERR1.results.docx",
+            "ERR1.results.json",
+            "ERR2.results.docx",
+            "ERR2.results.json"
+        ]
+    );
+    assert!(!check_if_running(&cluster.backend, &cluster.config)
+        .await
+        .unwrap());
+    assert_eq!(cluster.files("out/logs"), ["1001_0.log", "1001_1.log"]);
+    let logs = job_logs(&cluster.backend, &cluster.config, "1001")
+        .await
+        .unwrap();
+    assert_eq!(
+        logs,
+        "==> 1001_0.log <==\nprofiling ERR1\n==> 1001_1.log <==\nprofiling ERR2\n"
+    );
+}
+
+#[tokio::test]
+async fn task_without_reads_fails_the_job() {
+    let cluster = MockCluster::new();
+    cluster.add_reads("ERR1");
+
+    run_tbprofiler(
+        &cluster.backend,
+        2,
+        "ERR1 ERR2".to_string(),
+        &cluster.config,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(cluster.job_state("1001"), "FAILED");
+    assert_eq!(
+        cluster.files("out/results"),
+        ["ERR1.results.docx", "ERR1.results.json"]
+    );
+}
+
+#[tokio::test]
+async fn zero_samples_are_not_submitted() {
+    let cluster = MockCluster::new();
+
+    let err = run_tbprofiler(&cluster.backend, 0, String::new(), &cluster.config)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.kind, ErrorKind::NoItemsChecked);
+    assert!(cluster.files("slurm").is_empty());
+}
+
+#[tokio::test]
+async fn rejected_submission_keeps_the_output() {
+    let mut cluster = MockCluster::new();
+    cluster.config.tb_profiler_script = Some(
+        cluster
+            .path("scripts/missing.sh")
+            .to_string_lossy()
+            .to_string(),
+    );
+
+    let err = run_tbprofiler(&cluster.backend, 1, "ERR1".to_string(), &cluster.config)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.kind, ErrorKind::Scheduler);
+    let output = err.output.unwrap();
+    assert!(output.command.contains("| sbatch --array 0-0 "));
+    assert!(output.stderr.contains("Batch script is empty"));
+}
+
+#[tokio::test]
+async fn pending_jobs_are_running_until_cancelled() {
+    let cluster = MockCluster::new();
+    cluster.add_reads("ERR1");
+    cluster.hold_jobs();
+
+    run_tbprofiler(&cluster.backend, 1, "ERR1".to_string(), &cluster.config)
+        .await
+        .unwrap();
+    assert!(check_if_running(&cluster.backend, &cluster.config)
+        .await
+        .unwrap());
+    let queue = cluster.backend.exec("squeue -u tester").await.unwrap();
+    assert!(queue
+        .stdout
+        .contains(&format!("1001 standard tbprofiler {USERNAME} PD")));
+    let jobs = list_jobs(&cluster.backend, &cluster.config).await.unwrap();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].id, "1001");
+    assert_eq!(jobs[0].state, "PENDING");
+
+    cluster
+        .backend
+        .exec("scancel 1001")
+        .await
+        .unwrap()
+        .check("scancel failed")
+        .unwrap();
+
+    assert_eq!(cluster.job_state("1001"), "CANCELLED");
+    let accounting = cluster.backend.exec("sacct -j 1001").await.unwrap();
+    assert_eq!(accounting.stdout.trim(), "1001|CANCELLED");
+    assert!(!check_if_running(&cluster.backend, &cluster.config)
+        .await
+        .unwrap());
+    assert!(cluster.files("out/results").is_empty());
+}
+
+#[tokio::test]
+async fn runs_in_the_background_without_a_scheduler() {
+    let mut cluster = MockCluster::new();
+    cluster.config.scheduler = SchedulerKind::Background;
+    cluster.add_reads("ERR1");
+    cluster.add_reads("ERR2");
+
+    let stdout = run_tbprofiler(
+        &cluster.backend,
+        2,
+        "ERR1 ERR2".to_string(),
+        &cluster.config,
+    )
+    .await
+    .unwrap();
+    let job = SchedulerKind::Background
+        .driver()
+        .submitted_id(&stdout)
+        .unwrap()
+        .to_string();
+
+    for _ in 0..100 {
+        if cluster.files("out/results").len() == 4 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    assert_eq!(
+        cluster.files("out/results"),
+        [
+            "ERR1.results.docx",
+            "ERR1.results.json",
+            "ERR2.results.docx",
+            "ERR2.results.json"
+        ]
+    );
+    assert_eq!(
+        cluster.files("out/logs"),
+        [format!("{job}_0.log"), format!("{job}_1.log")]
+    );
+    assert!(cluster.files("slurm").is_empty());
+}
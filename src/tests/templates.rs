@@ -0,0 +1,35 @@
+use super::mock_cluster::MockCluster;
+use crate::app::ssh::upload_user_template;
+
+#[tokio::test]
+async fn replacing_a_template_keeps_the_previous_version() {
+    let cluster = MockCluster::new();
+    let local_path = cluster.path("report.docx");
+    std::fs::write(&local_path, "first").unwrap();
+    upload_user_template(
+        &cluster.backend,
+        &cluster.config,
+        "report",
+        local_path.clone(),
+    )
+    .await
+    .unwrap();
+
+    std::fs::write(&local_path, "second").unwrap();
+    let remote_path = upload_user_template(&cluster.backend, &cluster.config, "report", local_path)
+        .await
+        .unwrap();
+
+    assert_eq!(std::fs::read_to_string(remote_path).unwrap(), "second");
+    assert_eq!(
+        cluster.files("template"),
+        ["report.docx", "user_template.docx"]
+    );
+    let history = cluster.files("template/.history/report");
+    assert_eq!(history.len(), 1);
+    assert_eq!(
+        std::fs::read_to_string(cluster.path(&format!("template/.history/report/{}", history[0])))
+            .unwrap(),
+        "first"
+    );
+}
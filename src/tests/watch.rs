@@ -0,0 +1,79 @@
+use super::mock_cluster::MockCluster;
+use crate::app::config::SchedulerKind;
+use crate::app::jobs::Job;
+use crate::app::ssh::{cancel_job, run_tbprofiler};
+use crate::app::watch::{poll, Watch};
+
+async fn detect(cluster: &MockCluster, watch: &mut Watch) -> Vec<String> {
+    let polled = poll(&cluster.backend, &cluster.config, false)
+        .await
+        .unwrap();
+    watch.detect(polled.files)
+}
+
+#[tokio::test]
+async fn reports_new_pairs_once_their_size_is_stable() {
+    let cluster = MockCluster::new();
+    cluster.add_reads("ERR1");
+    let mut watch = Watch::default();
+
+    assert!(detect(&cluster, &mut watch).await.is_empty());
+
+    std::fs::write(cluster.path("raw/ERR2_1.fastq.gz"), "reads 1").unwrap();
+    assert!(detect(&cluster, &mut watch).await.is_empty());
+    std::fs::write(cluster.path("raw/ERR2_2.fastq.gz"), "reads").unwrap();
+    assert!(detect(&cluster, &mut watch).await.is_empty());
+    std::fs::write(cluster.path("raw/ERR2_2.fastq.gz"), "reads 2").unwrap();
+    assert!(detect(&cluster, &mut watch).await.is_empty());
+
+    assert_eq!(detect(&cluster, &mut watch).await, ["ERR2"]);
+    assert!(detect(&cluster, &mut watch).await.is_empty());
+}
+
+#[tokio::test]
+async fn submitted_jobs_finish_when_they_leave_the_queue() {
+    let cluster = MockCluster::new();
+    cluster.add_reads("ERR1");
+    cluster.hold_jobs();
+    run_tbprofiler(&cluster.backend, 1, "ERR1".to_string(), &cluster.config)
+        .await
+        .unwrap();
+    let mut watch = Watch::default();
+    watch
+        .jobs
+        .push(("1001".to_string(), vec!["ERR1".to_string()]));
+
+    let polled = poll(&cluster.backend, &cluster.config, true).await.unwrap();
+    assert!(watch
+        .finished(&polled.queue.unwrap(), cluster.config.scheduler.driver())
+        .is_empty());
+
+    cancel_job(&cluster.backend, &cluster.config, "1001")
+        .await
+        .unwrap();
+    let polled = poll(&cluster.backend, &cluster.config, true).await.unwrap();
+    assert_eq!(
+        watch.finished(&polled.queue.unwrap(), cluster.config.scheduler.driver()),
+        [("1001".to_string(), vec!["ERR1".to_string()])]
+    );
+    assert!(watch.jobs.is_empty());
+}
+
+#[test]
+fn pbs_array_tasks_keep_their_job_watched() {
+    let mut watch = Watch::default();
+    watch
+        .jobs
+        .push(("1234[].pbs01".to_string(), vec!["ERR1".to_string()]));
+    let queue = |id: &str| {
+        vec![Job {
+            id: id.to_string(),
+            ..Job::default()
+        }]
+    };
+    let pbs = SchedulerKind::Pbs.driver();
+
+    assert!(watch.finished(&queue("1234[1].pbs01"), pbs).is_empty());
+    assert!(watch.finished(&queue("1234[].pbs*"), pbs).is_empty());
+    assert_eq!(watch.finished(&queue("1235[].pbs01"), pbs).len(), 1);
+}
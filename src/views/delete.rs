@@ -0,0 +1,193 @@
+use crate::app::utils::{format_mtime, format_size};
+use crate::app::{Message, Tbgui};
+use crate::fl;
+use cosmic::iced::{Alignment, Length};
+use cosmic::prelude::*;
+use cosmic::{theme, widget, Element};
+use std::collections::BTreeMap;
+
+impl Tbgui
+where
+    Self: cosmic::Application,
+{
+    pub fn view_delete(&self) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let mut runs: BTreeMap<String, Vec<_>> = BTreeMap::new();
+        for set in &self.result_sets {
+            runs.entry(set.run()).or_default().push(set);
+        }
+
+        let mut sets = widget::list::list_column()
+            .style(theme::Container::ContextDrawer)
+            .spacing(spacing.space_xxxs)
+            .padding([spacing.space_none, spacing.space_xxs]);
+        if runs.is_empty() {
+            sets = sets.add(widget::text::body(fl!("no-result-sets")));
+        }
+        for (run, run_sets) in runs.into_iter().rev() {
+            let selected = run_sets
+                .iter()
+                .all(|set| self.delete_samples.contains(&set.sample));
+            let run_label = fl!("run-of", date = run.clone(), count = run_sets.len());
+            sets = sets.add(
+                widget::checkbox(run_label, selected)
+                    .on_toggle(move |value| Message::ToggleDeleteRun(run.clone(), value)),
+            );
+            for set in run_sets {
+                let details = fl!(
+                    "result-set-details",
+                    files = set.files,
+                    size = format_size(set.size),
+                    modified = set.modified.map(format_mtime).unwrap_or_default()
+                );
+                sets = sets.add(
+                    widget::row::with_capacity(3)
+                        .spacing(spacing.space_s)
+                        .align_y(Alignment::Center)
+                        .push(widget::horizontal_space().width(spacing.space_l))
+                        .push(
+                            widget::checkbox(
+                                set.sample.as_str(),
+                                self.delete_samples.contains(&set.sample),
+                            )
+                            .on_toggle(|value| {
+                                Message::ToggleDeleteSample(set.sample.clone(), value)
+                            })
+                            .width(Length::Fill),
+                        )
+                        .push(widget::text::caption(details)),
+                );
+            }
+        }
+
+        let selected: Vec<_> = self
+            .result_sets
+            .iter()
+            .filter(|set| self.delete_samples.contains(&set.sample))
+            .collect();
+        let preview = fl!(
+            "delete-preview",
+            samples = selected.len(),
+            files = selected.iter().map(|set| set.files).sum::<usize>(),
+            size = format_size(selected.iter().map(|set| set.size).sum())
+        );
+        let destination = if self.delete_permanently {
+            fl!("delete-permanently-warning")
+        } else {
+            fl!(
+                "delete-trash-destination",
+                dir = self.config.remote_trash_dir.clone().unwrap_or_default()
+            )
+        };
+
+        let confirm = widget::text_input(
+            fl!("delete-confirm-hint", word = fl!("delete-confirm-word")),
+            &self.delete_confirmation,
+        )
+        .on_input(Message::SetDeleteConfirmation);
+        let label = if self.delete_permanently {
+            fl!("delete-permanently")
+        } else {
+            fl!("move-to-trash")
+        };
+        let delete = widget::button::destructive(label)
+            .on_press_maybe(self.delete_confirmed().then_some(Message::StartDelete));
+
+        let mut column = widget::column::with_capacity(8)
+            .spacing(spacing.space_m)
+            .push(widget::text::title3(fl!("delete-results")))
+            .push(sets.apply(widget::scrollable).height(Length::Fill))
+            .push(widget::text::body(preview))
+            .push(
+                widget::checkbox(fl!("delete-permanently"), self.delete_permanently)
+                    .on_toggle(Message::SetDeletePermanently),
+            )
+            .push(widget::text::caption(destination))
+            .push(
+                widget::row::with_capacity(2)
+                    .spacing(spacing.space_s)
+                    .align_y(Alignment::Center)
+                    .push(confirm)
+                    .push(delete),
+            );
+        if let Some(summary) = &self.last_delete {
+            let text = match &summary.trash_dir {
+                Some(dir) => fl!(
+                    "moved-summary",
+                    files = summary.files,
+                    size = format_size(summary.bytes),
+                    dir = dir.clone()
+                ),
+                None => fl!(
+                    "deleted-summary",
+                    files = summary.files,
+                    size = format_size(summary.bytes)
+                ),
+            };
+            column = column.push(widget::text::body(text));
+            if summary.skipped > 0 {
+                column = column.push(widget::text::caption(fl!(
+                    "moved-skipped",
+                    files = summary.skipped
+                )));
+            }
+        }
+
+        column = column.push(self.view_retention());
+
+        column
+            .max_width(800.)
+            .apply(widget::container)
+            .padding(spacing.space_m)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// Retention policy and the result sets it would archive.
+    fn view_retention(&self) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let expired = self.expired_result_sets();
+        let size: u64 = expired.iter().map(|set| set.size).sum();
+
+        let mut section = widget::settings::section()
+            .title(fl!("retention"))
+            .add(widget::settings::item(
+                fl!("retention-days"),
+                widget::text_input("0", self.config.retention_days.to_string())
+                    .on_input(Message::SetRetentionDays)
+                    .width(Length::Fixed(80.)),
+            ))
+            .add(widget::settings::item(
+                fl!("archive-download"),
+                widget::checkbox("", self.config.archive_download)
+                    .on_toggle(Message::SetArchiveDownload),
+            ))
+            .add(widget::settings::item(
+                fl!(
+                    "retention-preview",
+                    samples = expired.len(),
+                    size = format_size(size),
+                    dir = self.config.remote_archive_dir.clone().unwrap_or_default()
+                ),
+                widget::button::standard(fl!("archive-now")).on_press_maybe(
+                    (!self.archiving && !expired.is_empty()).then_some(Message::ConfirmArchive),
+                ),
+            ));
+        if let Some(summary) = &self.last_archive {
+            section = section.add(widget::text::body(fl!(
+                "archive-summary",
+                samples = summary.samples,
+                files = summary.files,
+                freed = format_size(summary.freed),
+                size = format_size(summary.archive_size),
+                archive = summary.archive.clone()
+            )));
+        }
+        widget::column::with_capacity(1)
+            .spacing(spacing.space_s)
+            .push(section)
+            .into()
+    }
+}
@@ -0,0 +1,155 @@
+use crate::app::transfer::{ResultFileType, TransferState};
+use crate::app::utils::{format_mtime, format_size};
+use crate::app::{Message, Tbgui};
+use crate::fl;
+use cosmic::iced::{Alignment, Length};
+use cosmic::prelude::*;
+use cosmic::{theme, widget, Element};
+
+impl Tbgui
+where
+    Self: cosmic::Application,
+{
+    pub fn view_download(&self) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let destination =
+            widget::settings::section()
+                .title(fl!("destination"))
+                .add(widget::settings::item(
+                    self.config.download_dir().to_string_lossy().to_string(),
+                    widget::row::with_capacity(2)
+                        .spacing(spacing.space_xxs)
+                        .push(widget::button::standard(fl!("change")).on_press_maybe(
+                            (!self.transfer.running).then_some(Message::ChooseDownloadDir),
+                        ))
+                        .push(
+                            widget::button::standard(fl!("open-folder"))
+                                .on_press(Message::OpenDownloadDir),
+                        ),
+                ));
+
+        let mut file_types =
+            widget::row::with_capacity(ResultFileType::all().len()).spacing(spacing.space_s);
+        for &file_type in ResultFileType::all() {
+            file_types = file_types.push(
+                widget::checkbox(file_type.label(), self.download_types.contains(&file_type))
+                    .on_toggle(move |value| Message::ToggleDownloadType(file_type, value)),
+            );
+        }
+
+        let mut samples = widget::list::list_column()
+            .style(theme::Container::ContextDrawer)
+            .spacing(spacing.space_xxxs)
+            .padding([spacing.space_none, spacing.space_xxs])
+            .add(
+                widget::checkbox(fl!("all-samples"), self.download_samples.is_empty())
+                    .on_toggle(|_| Message::ClearDownloadSamples),
+            );
+        if self.result_sets.is_empty() {
+            samples = samples.add(widget::text::body(fl!("no-result-sets")));
+        }
+        for set in &self.result_sets {
+            let details = fl!(
+                "result-set-details",
+                files = set.files,
+                size = format_size(set.size),
+                modified = set.modified.map(format_mtime).unwrap_or_default()
+            );
+            samples = samples.add(
+                widget::row::with_capacity(2)
+                    .spacing(spacing.space_s)
+                    .align_y(Alignment::Center)
+                    .push(
+                        widget::checkbox(
+                            set.sample.as_str(),
+                            self.download_samples.contains(&set.sample),
+                        )
+                        .on_toggle(|value| Message::ToggleDownloadSample(set.sample.clone(), value))
+                        .width(Length::Fill),
+                    )
+                    .push(widget::text::caption(details)),
+            );
+        }
+
+        let download = widget::button::suggested(fl!("download")).on_press_maybe(
+            (!self.transfer.running && !self.download_types.is_empty())
+                .then_some(Message::StartDownload),
+        );
+        let required: u64 = self
+            .result_sets
+            .iter()
+            .filter(|set| {
+                self.download_samples.is_empty() || self.download_samples.contains(&set.sample)
+            })
+            .map(|set| set.size)
+            .sum();
+
+        let mut column = widget::column::with_capacity(8)
+            .spacing(spacing.space_m)
+            .push(widget::text::title3(fl!("download-results")))
+            .push(self.view_storage(None, Some(required)))
+            .push(destination)
+            .push(
+                widget::settings::section()
+                    .title(fl!("file-types"))
+                    .add(file_types),
+            )
+            .push(samples.apply(widget::scrollable).height(Length::Fill))
+            .push(download)
+            .push(self.view_transfer(&self.transfer));
+        if let Some(summary) = &self.last_download {
+            column = column.push(widget::text::body(fl!(
+                "download-summary",
+                transferred = summary.transferred,
+                skipped = summary.skipped,
+                size = format_size(summary.bytes)
+            )));
+        }
+
+        column
+            .max_width(800.)
+            .apply(widget::container)
+            .padding(spacing.space_m)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// Overall and per-file progress of a running transfer.
+    pub fn view_transfer<'a>(&'a self, transfer: &'a TransferState) -> Element<'a, Message> {
+        let spacing = theme::active().cosmic().spacing;
+        if !transfer.running && transfer.total_files == 0 {
+            return widget::column().into();
+        }
+
+        let mut column = widget::column::with_capacity(transfer.active.len() + 2)
+            .spacing(spacing.space_xxs)
+            .push(widget::progress_bar(0.0..=1.0, transfer.fraction()))
+            .push(widget::text::caption(fl!(
+                "transfer-progress",
+                finished = transfer.finished_files,
+                total = transfer.total_files,
+                skipped = transfer.skipped_files,
+                transferred = format_size(transfer.transferred_bytes()),
+                size = format_size(transfer.total_bytes)
+            )));
+        for (name, (transferred, size)) in &transfer.active {
+            let fraction = if *size == 0 {
+                0.0
+            } else {
+                *transferred as f32 / *size as f32
+            };
+            column = column.push(
+                widget::row::with_capacity(2)
+                    .spacing(spacing.space_s)
+                    .push(widget::text::body(name.clone()).width(Length::FillPortion(2)))
+                    .push(
+                        widget::container(widget::progress_bar(0.0..=1.0, fraction))
+                            .width(Length::FillPortion(3)),
+                    ),
+            );
+        }
+        column.into()
+    }
+}
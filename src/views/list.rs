@@ -0,0 +1,79 @@
+use crate::actions::TasksAction;
+use crate::app::icons;
+use crate::app::{Message, Tbgui};
+use crate::fl;
+use crate::model::List;
+use cosmic::iced::{Alignment, Length};
+use cosmic::prelude::*;
+use cosmic::{theme, widget, Element};
+
+impl Tbgui
+where
+    Self: cosmic::Application,
+{
+    pub fn view_list(&self, list: &List) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let header = widget::column::with_capacity(2)
+            .push(widget::text::title3(list.name.clone()))
+            .push(widget::text::caption(fl!(
+                "batch-samples",
+                count = list.samples.len()
+            )));
+
+        let actions = widget::row::with_capacity(3)
+            .spacing(spacing.space_s)
+            .push(
+                widget::button::suggested(fl!("run-batch")).on_press_maybe(
+                    (!list.samples.is_empty())
+                        .then(|| Message::Tasks(TasksAction::RunList(list.id.clone()))),
+                ),
+            )
+            .push(
+                widget::button::standard(fl!("download-batch-results")).on_press_maybe(
+                    (!list.samples.is_empty())
+                        .then(|| Message::Tasks(TasksAction::DownloadList(list.id.clone()))),
+                ),
+            )
+            .push(
+                widget::button::standard(fl!("push-lists"))
+                    .on_press(Message::Tasks(TasksAction::PushLists)),
+            );
+
+        let samples: Element<Message> = if list.samples.is_empty() {
+            widget::text(fl!("batch-empty")).into()
+        } else {
+            let mut items = widget::list::list_column()
+                .style(theme::Container::ContextDrawer)
+                .spacing(spacing.space_xxxs)
+                .padding([spacing.space_none, spacing.space_xxs]);
+            for sample in &list.samples {
+                let remove = widget::button::icon(icons::get_handle("delete", 16)).on_press(
+                    Message::Tasks(TasksAction::RemoveFromList(list.id.clone(), sample.clone())),
+                );
+                items = items.add(
+                    widget::row::with_capacity(3)
+                        .align_y(Alignment::Center)
+                        .spacing(spacing.space_xxs)
+                        .padding([spacing.space_xxxs, spacing.space_xxs])
+                        .push(widget::text::title4(sample.clone()))
+                        .push(widget::horizontal_space())
+                        .push(remove),
+                );
+            }
+            items.apply(widget::scrollable).into()
+        };
+
+        widget::column::with_capacity(3)
+            .spacing(spacing.space_m)
+            .push(header)
+            .push(actions)
+            .push(samples)
+            .max_width(800.)
+            .apply(widget::container)
+            .padding(spacing.space_m)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
@@ -0,0 +1,82 @@
+use crate::app::{Message, Tbgui};
+use crate::fl;
+use cosmic::iced::{Alignment, Length};
+use cosmic::prelude::*;
+use cosmic::{theme, widget, Element};
+
+/// Levels shown for each entry of the level dropdown, most verbose first.
+const LEVELS: [&[&str]; 3] = [
+    &["TRACE", "DEBUG", "INFO", "WARN", "ERROR"],
+    &["WARN", "ERROR"],
+    &["ERROR"],
+];
+
+impl Tbgui
+where
+    Self: cosmic::Application,
+{
+    /// The end of the current application log, filtered by level.
+    pub fn view_logs(&self) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let levels = LEVELS[self.log_level.min(LEVELS.len() - 1)];
+        let log = self
+            .log_text
+            .lines()
+            .filter(|line| line_level(line).is_none_or(|level| levels.contains(&level)))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let actions = widget::row::with_capacity(5)
+            .spacing(spacing.space_s)
+            .align_y(Alignment::Center)
+            .push(widget::dropdown(
+                &self.log_levels,
+                Some(self.log_level),
+                Message::SetLogLevel,
+            ))
+            .push(widget::horizontal_space())
+            .push(widget::button::standard(fl!("open-log-folder")).on_press(Message::OpenLogDir))
+            .push(
+                widget::button::standard(fl!("copy"))
+                    .leading_icon(widget::icon::from_name("edit-copy-symbolic"))
+                    .on_press_maybe(
+                        (!log.is_empty()).then(|| Message::CopyToClipboard(log.clone())),
+                    ),
+            )
+            .push(
+                widget::button::suggested(fl!("refresh-log"))
+                    .leading_icon(widget::icon::from_name("view-refresh-symbolic"))
+                    .on_press(Message::LoadLog),
+            );
+
+        let content: Element<Message> = if log.is_empty() {
+            widget::text::body(fl!("log-empty")).into()
+        } else {
+            widget::text::monotext(log)
+                .apply(widget::container)
+                .width(Length::Fill)
+                .apply(widget::scrollable)
+                .height(Length::Fill)
+                .into()
+        };
+
+        widget::column::with_capacity(3)
+            .spacing(spacing.space_m)
+            .push(widget::text::title3(fl!("logs")))
+            .push(actions)
+            .push(content)
+            .apply(widget::container)
+            .padding(spacing.space_m)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
+
+/// The level of a log line as written by the file layer, `None` for lines
+/// continuing a multi-line message.
+fn line_level(line: &str) -> Option<&str> {
+    line.split_whitespace()
+        .nth(1)
+        .filter(|word| LEVELS[0].contains(word))
+}
@@ -1,3 +1,11 @@
 pub mod about;
+pub mod delete;
+pub mod download;
+pub mod list;
+pub mod logs;
 pub mod nav;
+pub mod profile;
 pub mod settings;
+pub mod storage;
+pub mod templates;
+pub mod upload;
@@ -10,36 +10,48 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum NavPage {
     RunTbProfiler,
+    Results,
     DownloadResults,
     DeleteResults,
+    Templates,
     Settings,
+    Logs,
 }
 
 impl NavPage {
     pub fn all() -> &'static [Self] {
         &[
             Self::RunTbProfiler,
+            Self::Results,
             Self::DownloadResults,
             Self::DeleteResults,
+            Self::Templates,
             Self::Settings,
+            Self::Logs,
         ]
     }
 
     pub fn title(&self) -> String {
         match self {
             Self::RunTbProfiler => fl!("run-tb-profiler"),
+            Self::Results => fl!("results"),
             Self::DownloadResults => fl!("download-results"),
             Self::DeleteResults => fl!("delete-results"),
+            Self::Templates => fl!("templates"),
             Self::Settings => fl!("settings"),
+            Self::Logs => fl!("logs"),
         }
     }
 
     pub fn icon(&self) -> widget::icon::Icon {
         match self {
             Self::RunTbProfiler => get_icon("play", 16),
+            Self::Results => get_icon("meds", 16),
             Self::DownloadResults => get_icon("download", 16),
             Self::DeleteResults => get_icon("delete", 16),
+            Self::Templates => get_icon("x-office-document-symbolic", 16),
             Self::Settings => get_icon("settings", 16),
+            Self::Logs => get_icon("text-x-generic-symbolic", 16),
         }
     }
 }
@@ -0,0 +1,97 @@
+use crate::app::{Message, Tbgui};
+use crate::fl;
+use crate::model::profile::TbProfile;
+use cosmic::prelude::*;
+use cosmic::widget;
+use cosmic::Element;
+
+fn format_optional<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "—".to_string(), |value| value.to_string())
+}
+
+impl Tbgui
+where
+    Self: cosmic::Application,
+{
+    pub fn view_profile(&self, sample: &str) -> Element<Message> {
+        let Some(profile) = self.profiles.get(sample) else {
+            return widget::text(fl!("no-profile")).into();
+        };
+        widget::scrollable(
+            widget::column::with_capacity(4)
+                .spacing(cosmic::theme::active().cosmic().spacing.space_m)
+                .push(lineage_section(profile))
+                .push(resistance_section(profile))
+                .push(qc_section(profile))
+                .push(
+                    widget::settings::section().add(widget::settings::item::item(
+                        fl!("tbprofiler-version"),
+                        widget::text(format_optional(profile.version())),
+                    )),
+                ),
+        )
+        .into()
+    }
+}
+
+fn lineage_section(profile: &TbProfile) -> Element<Message> {
+    widget::settings::section()
+        .title(fl!("lineage"))
+        .add(widget::settings::item::item(
+            fl!("main-lineage"),
+            widget::text(format_optional(profile.main_lineage.as_deref())),
+        ))
+        .add(widget::settings::item::item(
+            fl!("sub-lineage"),
+            widget::text(format_optional(profile.sub_lineage.as_deref())),
+        ))
+        .into()
+}
+
+fn resistance_section(profile: &TbProfile) -> Element<Message> {
+    let mut section = widget::settings::section()
+        .title(fl!("drug-resistance"))
+        .add(widget::settings::item::item(
+            fl!("dr-type"),
+            widget::text::heading(profile.drtype.as_str().to_string()),
+        ));
+    for call in profile.drug_calls() {
+        let value = if call.is_resistant() {
+            call.mutations.join("\n")
+        } else {
+            fl!("susceptible")
+        };
+        section = section.add(widget::settings::item::item(
+            call.drug.replace('_', " "),
+            widget::text(value),
+        ));
+    }
+    section.into()
+}
+
+fn qc_section(profile: &TbProfile) -> Element<Message> {
+    widget::settings::section()
+        .title(fl!("qc"))
+        .add(widget::settings::item::item(
+            fl!("percent-reads-mapped"),
+            widget::text(format_optional(
+                profile
+                    .qc
+                    .percent_reads_mapped
+                    .map(|value| format!("{value:.2}%")),
+            )),
+        ))
+        .add(widget::settings::item::item(
+            fl!("num-reads-mapped"),
+            widget::text(format_optional(profile.qc.num_reads_mapped)),
+        ))
+        .add(widget::settings::item::item(
+            fl!("target-median-depth"),
+            widget::text(format_optional(profile.qc.target_median_depth)),
+        ))
+        .add(widget::settings::item::item(
+            fl!("genome-median-depth"),
+            widget::text(format_optional(profile.qc.genome_median_depth)),
+        ))
+        .into()
+}
@@ -1,21 +1,154 @@
+use crate::app::config::{BackendKind, SchedulerKind};
+use crate::app::config_form::SettingsField;
+use crate::app::icons::get_icon;
 use crate::app::{Message, Tbgui};
-use cosmic::iced::alignment::{Horizontal, Vertical};
-use cosmic::iced::Length;
+use crate::fl;
+use crate::views::nav::NavPage;
+use cosmic::iced::{Alignment, Length};
 use cosmic::prelude::*;
-use cosmic::widget;
-use cosmic::Element;
+use cosmic::{theme, widget, Element};
 
 impl Tbgui
 where
     Self: cosmic::Application,
 {
     pub fn view_settings(&self) -> Element<Message> {
-        widget::text::title1("Settings")
+        let spacing = theme::active().cosmic().spacing;
+        let draft = &self.settings_draft;
+
+        let default_page = NavPage::all()
+            .iter()
+            .position(|&page| page == draft.default_page);
+        let scheduler = SchedulerKind::all()
+            .iter()
+            .position(|&kind| kind == draft.scheduler);
+        let general = widget::settings::section()
+            .title(fl!("general"))
+            .add(widget::settings::item(
+                fl!("run-locally"),
+                widget::checkbox("", draft.backend == BackendKind::Local)
+                    .on_toggle(Message::EditRunLocally),
+            ))
+            .add(widget::settings::item(
+                fl!("scheduler"),
+                widget::dropdown(&self.scheduler_names, scheduler, Message::EditScheduler),
+            ))
+            .add(widget::settings::item(
+                fl!("default-page"),
+                widget::dropdown(&self.page_names, default_page, Message::EditDefaultPage),
+            ))
+            .add(widget::settings::item(
+                fl!("verify-checksums"),
+                widget::checkbox("", draft.verify_checksums)
+                    .on_toggle(Message::EditVerifyChecksums),
+            ))
+            .add(widget::settings::item(
+                fl!("watch-raw-dir"),
+                widget::checkbox("", draft.watch_raw_dir).on_toggle(Message::EditWatchRawDir),
+            ))
+            .add(widget::settings::item(
+                fl!("auto-submit"),
+                widget::checkbox("", draft.auto_submit).on_toggle(Message::EditAutoSubmit),
+            ));
+
+        let mut profile = widget::settings::section()
+            .title(fl!("profile"))
+            .add(widget::text::caption(fl!("profile-description")))
+            .add(
+                widget::row::with_capacity(4)
+                    .spacing(spacing.space_xxs)
+                    .push(
+                        widget::button::standard(fl!("profile-import"))
+                            .on_press(Message::ImportProfile),
+                    )
+                    .push(
+                        widget::button::standard(fl!("profile-import-cluster"))
+                            .on_press(Message::ImportClusterProfile),
+                    )
+                    .push(
+                        widget::button::standard(fl!("profile-export"))
+                            .on_press(Message::ExportProfile),
+                    )
+                    .push(
+                        widget::button::standard(fl!("profile-publish"))
+                            .on_press(Message::PublishProfile),
+                    ),
+            );
+        if let Some(status) = &self.profile_status {
+            profile = profile.add(widget::text::body(status.clone()));
+        }
+
+        let actions = widget::row::with_capacity(5)
+            .spacing(spacing.space_s)
+            .align_y(Alignment::Center)
+            .push(widget::horizontal_space())
+            .push(widget::button::standard(fl!("setup-title")).on_press(Message::StartSetup))
+            .push(widget::button::standard(fl!("reset")).on_press(Message::ResetSettings))
+            .push(
+                widget::button::standard(fl!("test-settings")).on_press_maybe(
+                    (draft.is_valid() && !self.testing_settings).then_some(Message::TestSettings),
+                ),
+            )
+            .push(
+                widget::button::suggested(fl!("save"))
+                    .on_press_maybe(draft.is_valid().then_some(Message::SaveSettings)),
+            );
+
+        widget::column::with_capacity(7)
+            .spacing(spacing.space_m)
+            .push(widget::text::title3(fl!("settings")))
+            .push(self.settings_section(fl!("connection"), SettingsField::connection()))
+            .push(self.settings_section(fl!("remote-paths"), SettingsField::remote_paths()))
+            .push(self.settings_section(fl!("transfers-and-storage"), SettingsField::other()))
+            .push(general)
+            .push(profile)
+            .push(actions)
+            .max_width(800.)
+            .apply(widget::scrollable)
             .apply(widget::container)
+            .padding(spacing.space_m)
             .width(Length::Fill)
             .height(Length::Fill)
-            .align_x(Horizontal::Center)
-            .align_y(Vertical::Center)
             .into()
     }
+
+    /// Text inputs for `fields` with their validation and test results.
+    fn settings_section(&self, title: String, fields: &[SettingsField]) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let mut section = widget::settings::section().title(title);
+        for &field in fields {
+            let mut input = widget::column::with_capacity(2)
+                .spacing(spacing.space_xxxs)
+                .push(
+                    widget::text_input("", self.settings_draft.value(field))
+                        .on_input(move |value| Message::EditSetting(field, value)),
+                );
+            if let Some(error) = self.settings_draft.error(field) {
+                input = input.push(widget::text::caption(error));
+            }
+            let mut row = widget::row::with_capacity(2)
+                .spacing(spacing.space_xxs)
+                .align_y(Alignment::Center)
+                .push(input.width(Length::FillPortion(3)));
+            match self.settings_tests.get(&field) {
+                Some(true) => {
+                    row = row.push(widget::tooltip(
+                        get_icon("emblem-ok-symbolic", 16),
+                        widget::text(fl!("setting-found")),
+                        widget::tooltip::Position::Top,
+                    ));
+                }
+                Some(false) => {
+                    row = row.push(widget::tooltip(
+                        get_icon("dialog-warning-symbolic", 16),
+                        widget::text(fl!("setting-not-found")),
+                        widget::tooltip::Position::Top,
+                    ));
+                }
+                None => {}
+            }
+            section = section.add(widget::settings::item(field.label(), row));
+        }
+        section.into()
+    }
 }
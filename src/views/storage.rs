@@ -0,0 +1,94 @@
+use crate::app::utils::format_size;
+use crate::app::{Message, Tbgui};
+use crate::fl;
+use cosmic::iced::{Alignment, Length};
+use cosmic::prelude::*;
+use cosmic::{theme, widget, Element};
+
+impl Tbgui
+where
+    Self: cosmic::Application,
+{
+    /// Free space on the cluster and locally, with a warning if `remote_required`
+    /// or `local_required` bytes would not fit.
+    pub fn view_storage(
+        &self,
+        remote_required: Option<u64>,
+        local_required: Option<u64>,
+    ) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let storage = &self.storage;
+
+        let remote = match (storage.remote, storage.quota) {
+            (Some(space), Some(quota)) => fl!(
+                "remote-space-quota",
+                available = format_size(space.available),
+                total = format_size(space.total),
+                quota = format_size(quota.available()),
+                limit = format_size(quota.limit)
+            ),
+            (Some(space), None) => fl!(
+                "remote-space",
+                available = format_size(space.available),
+                total = format_size(space.total)
+            ),
+            (None, Some(quota)) => fl!(
+                "remote-quota",
+                quota = format_size(quota.available()),
+                limit = format_size(quota.limit)
+            ),
+            (None, None) => fl!("remote-space-unknown"),
+        };
+        let local = match storage.local {
+            Some(space) => fl!(
+                "local-space",
+                available = format_size(space.available),
+                total = format_size(space.total)
+            ),
+            None => fl!("local-space-unknown"),
+        };
+
+        let mut column = widget::column::with_capacity(3)
+            .spacing(spacing.space_xxs)
+            .push(
+                widget::row::with_capacity(2)
+                    .spacing(spacing.space_m)
+                    .push(widget::text::caption(remote))
+                    .push(widget::text::caption(local)),
+            );
+        let exceeds = |required: Option<u64>, available: Option<u64>| {
+            required
+                .zip(available)
+                .filter(|(required, available)| required > available)
+        };
+        let mut warnings = Vec::new();
+        if let Some((required, available)) = exceeds(remote_required, storage.remote_available()) {
+            warnings.push(fl!(
+                "remote-space-warning",
+                required = format_size(required),
+                available = format_size(available)
+            ));
+        }
+        if let Some((required, available)) = exceeds(local_required, storage.local_available()) {
+            warnings.push(fl!(
+                "local-space-warning",
+                required = format_size(required),
+                available = format_size(available)
+            ));
+        }
+        for warning in warnings {
+            column = column.push(
+                widget::row::with_capacity(2)
+                    .spacing(spacing.space_xxs)
+                    .align_y(Alignment::Center)
+                    .push(widget::icon::from_name("dialog-warning-symbolic").size(16))
+                    .push(widget::text::body(warning)),
+            );
+        }
+        column
+            .apply(widget::container)
+            .padding([spacing.space_xxs, spacing.space_m])
+            .width(Length::Fill)
+            .into()
+    }
+}
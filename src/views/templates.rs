@@ -0,0 +1,122 @@
+use crate::app::utils::{format_mtime, format_size};
+use crate::app::{Message, Tbgui};
+use crate::fl;
+use cosmic::iced::{Alignment, Length};
+use cosmic::prelude::*;
+use cosmic::{theme, widget, Element};
+
+impl Tbgui
+where
+    Self: cosmic::Application,
+{
+    pub fn view_templates(&self) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let selected = self.config.run_template();
+
+        let mut templates = widget::list::list_column()
+            .style(theme::Container::ContextDrawer)
+            .spacing(spacing.space_xxxs)
+            .padding([spacing.space_none, spacing.space_xxs]);
+        if self.templates.is_empty() {
+            templates = templates.add(widget::text::body(fl!("no-templates")));
+        }
+        for template in &self.templates {
+            let mut name = template.name.clone();
+            if template.default {
+                name = fl!("default-template", name = name);
+            }
+            let details = fl!(
+                "template-details",
+                size = format_size(template.size),
+                modified = template.modified.map(format_mtime).unwrap_or_default(),
+                versions = template.versions
+            );
+            let is_selected = selected == Some(template.path.as_str());
+            let action: Element<Message> = if is_selected {
+                widget::text::body(fl!("used-for-runs")).into()
+            } else {
+                widget::button::standard(fl!("use-for-runs"))
+                    .on_press(Message::SelectTemplate(template.path.clone()))
+                    .into()
+            };
+            templates = templates.add(
+                widget::row::with_capacity(3)
+                    .spacing(spacing.space_s)
+                    .align_y(Alignment::Center)
+                    .push(
+                        widget::column::with_capacity(2)
+                            .push(widget::text::body(name))
+                            .push(widget::text::caption(details))
+                            .width(Length::Fill),
+                    )
+                    .push(action),
+            );
+        }
+
+        let upload = widget::row::with_capacity(2)
+            .spacing(spacing.space_s)
+            .align_y(Alignment::Center)
+            .push(
+                widget::text_input(fl!("template-name"), &self.template_name)
+                    .on_input(Message::SetTemplateName),
+            )
+            .push(
+                widget::button::suggested(fl!("upload-template")).on_press_maybe(
+                    (!self.uploading_template && !self.template_name.trim().is_empty())
+                        .then_some(Message::UploadTemplate),
+                ),
+            );
+
+        widget::column::with_capacity(5)
+            .spacing(spacing.space_m)
+            .push(widget::text::title3(fl!("templates")))
+            .push(templates.apply(widget::scrollable).height(Length::Fill))
+            .push(
+                widget::settings::section()
+                    .title(fl!("upload-template"))
+                    .add(upload),
+            )
+            .push(
+                widget::button::standard(fl!("download-template"))
+                    .on_press(Message::DownloadTemplate),
+            )
+            .push(self.view_render())
+            .max_width(800.)
+            .apply(widget::container)
+            .padding(spacing.space_m)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// Local rendering of reports from downloaded result JSON files.
+    fn view_render(&self) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let mut section = widget::settings::section()
+            .title(fl!("render-reports"))
+            .add(
+                widget::row::with_capacity(2)
+                    .spacing(spacing.space_s)
+                    .align_y(Alignment::Center)
+                    .push(
+                        widget::checkbox(fl!("export-pdf"), self.render_pdf)
+                            .on_toggle(Message::SetRenderPdf)
+                            .width(Length::Fill),
+                    )
+                    .push(
+                        widget::button::standard(fl!("render-reports-button"))
+                            .on_press_maybe((!self.rendering).then_some(Message::RenderReports)),
+                    ),
+            );
+        if !self.rendered_reports.is_empty() {
+            section = section.add(widget::text::body(fl!(
+                "rendered-reports",
+                count = self.rendered_reports.len()
+            )));
+            for report in &self.rendered_reports {
+                section = section.add(widget::text::caption(report.to_string_lossy().to_string()));
+            }
+        }
+        section.into()
+    }
+}
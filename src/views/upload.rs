@@ -0,0 +1,89 @@
+use crate::app::utils::format_size;
+use crate::app::{Message, Tbgui};
+use crate::fl;
+use cosmic::iced::{Alignment, Length};
+use cosmic::prelude::*;
+use cosmic::{theme, widget, Element};
+
+impl Tbgui
+where
+    Self: cosmic::Application,
+{
+    /// FASTQ files picked or dropped onto the sample list for upload.
+    pub fn view_upload(&self) -> Element<Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let fastq = &self.fastq;
+
+        let header = widget::row::with_capacity(2)
+            .spacing(spacing.space_s)
+            .align_y(Alignment::Center)
+            .push(
+                widget::button::standard(fl!("upload-fastq"))
+                    .on_press_maybe((!self.upload.running).then_some(Message::ChooseFastq)),
+            )
+            .push(widget::text::caption(fl!("drop-fastq")));
+        let mut column = widget::column::with_capacity(5)
+            .spacing(spacing.space_xxs)
+            .push(header);
+
+        if !self.fastq_paths.is_empty() {
+            let mut files = widget::list::list_column()
+                .style(theme::Container::ContextDrawer)
+                .spacing(spacing.space_xxxs)
+                .padding([spacing.space_none, spacing.space_xxs]);
+            for file in &fastq.files {
+                files = files.add(
+                    widget::row::with_capacity(2)
+                        .spacing(spacing.space_s)
+                        .push(widget::text::body(file.name.as_str()).width(Length::Fill))
+                        .push(widget::text::caption(format_size(file.size))),
+                );
+            }
+            for problem in &fastq.problems {
+                files = files.add(
+                    widget::row::with_capacity(2)
+                        .spacing(spacing.space_xxs)
+                        .align_y(Alignment::Center)
+                        .push(widget::icon::from_name("dialog-warning-symbolic").size(16))
+                        .push(widget::text::body(problem.as_str())),
+                );
+            }
+            let size: u64 = fastq.files.iter().map(|file| file.size).sum();
+            column = column.push(files).push(
+                widget::row::with_capacity(3)
+                    .spacing(spacing.space_s)
+                    .align_y(Alignment::Center)
+                    .push(
+                        widget::text::body(fl!(
+                            "upload-selection",
+                            samples = fastq.samples(),
+                            size = format_size(size)
+                        ))
+                        .width(Length::Fill),
+                    )
+                    .push(
+                        widget::button::standard(fl!("clear"))
+                            .on_press_maybe((!self.upload.running).then_some(Message::ClearFastq)),
+                    )
+                    .push(widget::button::suggested(fl!("upload")).on_press_maybe(
+                        (fastq.is_valid() && !self.upload.running).then_some(Message::StartUpload),
+                    )),
+            );
+        }
+        column = column.push(self.view_transfer(&self.upload));
+        if let Some(summary) = &self.last_upload {
+            column = column.push(widget::text::body(fl!(
+                "upload-summary",
+                transferred = summary.transferred,
+                skipped = summary.skipped,
+                size = format_size(summary.bytes)
+            )));
+        }
+
+        column
+            .apply(widget::container)
+            .padding([spacing.space_xxs, spacing.space_m])
+            .width(Length::Fill)
+            .into()
+    }
+}